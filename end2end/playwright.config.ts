@@ -36,7 +36,7 @@ export default defineConfig({
     /* Maximum time each action such as `click()` can take. Defaults to 0 (no limit). */
     actionTimeout: 0,
     /* Base URL to use in actions like `await page.goto('/')`. */
-    // baseURL: 'http://localhost:3000',
+    baseURL: "http://127.0.0.1:3000",
 
     /* Collect trace when retrying the failed test. See https://playwright.dev/docs/trace-viewer */
     trace: "on-first-retry",
@@ -97,9 +97,14 @@ export default defineConfig({
   /* Folder for test artifacts such as screenshots, videos, traces, etc. */
   // outputDir: 'test-results/',
 
-  /* Run your local dev server before starting the tests */
-  // webServer: {
-  //   command: 'npm run start',
-  //   port: 3000,
-  // },
+  /* Serve the app against the fixture chain, unless it already runs */
+  webServer: {
+    command: "cargo leptos serve --bin-features ssr,mock-chain",
+    cwd: "..",
+    url: "http://127.0.0.1:3000/healthz",
+    env: { EXPLORER_CONFIG: "end2end/explorer.toml" },
+    reuseExistingServer: !process.env.CI,
+    /* Building the server and the WASM bundle takes a while */
+    timeout: 10 * 60 * 1000,
+  },
 });
@@ -0,0 +1,37 @@
+import { test, expect } from "@playwright/test";
+import { ALICE, BOB, TREASURY } from "./fixture";
+
+test("account shows its balances", async ({ page }) => {
+  await page.goto(`/accounts/${ALICE}`);
+
+  await expect(page.locator("h1")).toHaveText("Account Details");
+  await expect(page.getByText(ALICE).first()).toBeVisible();
+  await expect(page.getByText("Transferable")).toBeVisible();
+  await expect(page.getByText("1.25M $AFT").first()).toBeVisible();
+});
+
+test("account lists its allocations", async ({ page }) => {
+  await page.goto(`/accounts/${ALICE}`);
+
+  await expect(page.getByText("Active Allocations")).toBeVisible();
+  await expect(page.locator("h3", { hasText: "Airdrop" })).toBeVisible();
+  await expect(page.locator("h3", { hasText: "Community Rewards" })).toBeVisible();
+  await expect(page.locator("h3", { hasText: "Teams" })).toHaveCount(0);
+
+  await page.goto(`/accounts/${BOB}`);
+  await expect(page.locator("h3", { hasText: "Teams" })).toBeVisible();
+});
+
+test("account without allocations says so", async ({ page }) => {
+  await page.goto(`/accounts/${TREASURY}`);
+
+  await expect(
+    page.getByText("No active vesting schedules found for this account."),
+  ).toBeVisible();
+});
+
+test("public keys redirect to the address", async ({ page }) => {
+  await page.goto(`/accounts/0x${"01".repeat(32)}`);
+
+  await expect(page).toHaveURL(`/accounts/${ALICE}`);
+});
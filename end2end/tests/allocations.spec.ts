@@ -0,0 +1,26 @@
+import { test, expect } from "@playwright/test";
+
+test("allocations lists every envelope", async ({ page }) => {
+  await page.goto("/allocations");
+
+  await expect(page.locator("h1")).toContainText("Token Sources");
+  await expect(page.getByText("Envelope", { exact: true })).toHaveCount(13);
+  for (const name of ["Airdrop", "Community Rewards", "Teams", "KoL Funding"]) {
+    await expect(page.locator("h3", { hasText: name }).first()).toBeVisible();
+  }
+});
+
+test("allocations shows the distribution progress", async ({ page }) => {
+  await page.goto("/allocations");
+
+  // 2M of the 150M of the Teams envelope were allocated
+  await expect(page.getByText("1.3%", { exact: true })).toBeVisible();
+  await expect(page.getByText("150M $AFT").first()).toBeVisible();
+});
+
+test("allocations shows the payout status", async ({ page }) => {
+  await page.goto("/allocations");
+
+  await expect(page.getByText("Epoch Payouts")).toBeVisible();
+  await expect(page.getByText("Payout History")).toBeVisible();
+});
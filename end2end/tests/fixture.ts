@@ -0,0 +1,8 @@
+// Accounts of `fixtures/chain.json`, with the Allfeat prefix.
+export const ALICE = "qSuM8XReSjEHTwTLP2THjaKHjNzxY49YtAPgaRbn8L1iTtw6q";
+export const BOB = "qSuNSsVxzSFweZt96kjnbPiEqPbsLXE99HwWhH6bMb8WKN7vA";
+export const SUDO = "qSuPmDaHY9HbqCJwpV2HTD7BwQCn8zJjQRVLp8bQarFJAqF7S";
+export const TREASURY = "qSwoJVKfgchSRjD6CZ739j9G7zR1khXqkvbeMVCN1NPKJgeup";
+
+// Latest finalized block of the fixture chain.
+export const HEAD = 19;
@@ -0,0 +1,29 @@
+import { test, expect } from "@playwright/test";
+import { HEAD, SUDO } from "./fixture";
+
+test("overview shows the supply metrics", async ({ page }) => {
+  await page.goto("/");
+
+  await expect(page).toHaveTitle("Allfeat • Economy Explorer");
+  await expect(page.locator("h1")).toContainText("Allfeat Economy");
+
+  await expect(page.getByText("Total Supply")).toBeVisible();
+  await expect(page.getByText("1B $AFT").first()).toBeVisible();
+  await expect(page.getByText("Treasury Funds")).toBeVisible();
+  await expect(page.getByText("50M $AFT").first()).toBeVisible();
+  await expect(page.getByText("Circulating Supply")).toBeVisible();
+});
+
+test("overview shows the sudo key", async ({ page }) => {
+  await page.goto("/");
+
+  await expect(page.locator(`a[href="/accounts/${SUDO}"]`).first()).toBeVisible();
+});
+
+test("overview lists the finalized head", async ({ page }) => {
+  await page.goto("/");
+
+  const head = page.locator(`a[href="/blocks/${HEAD}"]`).first();
+  await expect(head).toBeVisible();
+  await expect(head).toContainText(`#${HEAD}`);
+});
@@ -0,0 +1,225 @@
+//! Labels for well-known accounts (exchanges, foundation, teams...).
+//!
+//! The server loads them from the file at `address_book.path` and reloads it
+//! when it changes. The labels are embedded in every page by
+//! [`AddressBookScript`] and picked up by the browser before hydration, so
+//! both sides render the same names.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::TREASURY_ACCOUNT;
+
+/// Id of the script element holding the labels.
+const SCRIPT_ID: &str = "address-book";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LabelCategory {
+    Exchange,
+    Foundation,
+    Team,
+    Validator,
+    #[default]
+    Other,
+}
+
+impl LabelCategory {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Exchange => "Exchange",
+            Self::Foundation => "Foundation",
+            Self::Team => "Team",
+            Self::Validator => "Validator",
+            Self::Other => "Other",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AddressLabel {
+    pub address: String,
+    pub name: String,
+    #[serde(default)]
+    pub category: LabelCategory,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub logo_url: Option<String>,
+    #[serde(default)]
+    pub verified: bool,
+}
+
+/// Labels known without any address book file.
+fn builtin_labels() -> Vec<AddressLabel> {
+    vec![AddressLabel {
+        address: TREASURY_ACCOUNT.to_string(),
+        name: "Allfeat Foundation".to_string(),
+        category: LabelCategory::Foundation,
+        description: None,
+        logo_url: None,
+        verified: true,
+    }]
+}
+
+static LABELS: LazyLock<RwLock<HashMap<String, AddressLabel>>> = LazyLock::new(|| {
+    RwLock::new(
+        builtin_labels()
+            .into_iter()
+            .map(|l| (l.address.clone(), l))
+            .collect(),
+    )
+});
+
+/// Replaces the labels, on top of the built-in ones. Addresses of other
+/// networks are re-encoded with the Allfeat prefix, invalid ones are skipped.
+/// Returns the number of labels loaded from `labels`.
+pub fn set_labels(labels: Vec<AddressLabel>) -> usize {
+    let mut all: HashMap<String, AddressLabel> = builtin_labels()
+        .into_iter()
+        .map(|l| (l.address.clone(), l))
+        .collect();
+
+    let mut loaded = 0;
+    for mut label in labels {
+        if let Ok(address) = crate::ss58::to_allfeat(&label.address) {
+            label.address = address.clone();
+            all.insert(address, label);
+            loaded += 1;
+        }
+    }
+
+    *LABELS.write().unwrap_or_else(|e| e.into_inner()) = all;
+    loaded
+}
+
+pub fn label_of(address: &str) -> Option<AddressLabel> {
+    LABELS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(address)
+        .cloned()
+}
+
+/// Every label, sorted by name.
+pub fn labels() -> Vec<AddressLabel> {
+    let mut labels: Vec<AddressLabel> = LABELS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .cloned()
+        .collect();
+    labels.sort_by(|a, b| a.name.cmp(&b.name));
+    labels
+}
+
+/// Embeds the labels in the page for [`load_from_document`].
+#[component]
+pub fn AddressBookScript() -> impl IntoView {
+    // Keep the JSON from closing the script element
+    let json = serde_json::to_string(&labels())
+        .unwrap_or_default()
+        .replace("</", "<\\/");
+
+    view! { <script id=SCRIPT_ID type="application/json" inner_html=json></script> }
+}
+
+/// Loads the labels embedded by [`AddressBookScript`]. Called before
+/// hydration.
+#[cfg(feature = "hydrate")]
+pub fn load_from_document() {
+    let labels = document()
+        .get_element_by_id(SCRIPT_ID)
+        .and_then(|el| el.text_content())
+        .and_then(|json| serde_json::from_str::<Vec<AddressLabel>>(&json).ok());
+
+    if let Some(labels) = labels {
+        set_labels(labels);
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub mod file {
+    //! Address book file, in TOML or JSON depending on its extension:
+    //!
+    //! ```toml
+    //! [[labels]]
+    //! address = "qSwoJVKfgchSRjD6CZ739j9G7zR1khXqkvbeMVCN1NPKJgeup"
+    //! name = "Allfeat Foundation"
+    //! category = "foundation"
+    //! verified = true
+    //! ```
+
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, SystemTime};
+
+    use serde::Deserialize;
+    use tracing::{info, warn};
+
+    use super::{AddressLabel, set_labels};
+
+    /// How often the file is checked for changes.
+    const RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+    #[derive(Deserialize)]
+    struct AddressBookFile {
+        #[serde(default)]
+        labels: Vec<AddressLabel>,
+    }
+
+    fn read(path: &Path) -> Result<Vec<AddressLabel>, Box<dyn std::error::Error + Send + Sync>> {
+        let content = std::fs::read_to_string(path)?;
+
+        let file: AddressBookFile = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::from_str(&content)?,
+            _ => toml::from_str(&content)?,
+        };
+
+        Ok(file.labels)
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    fn load(path: &Path) {
+        match read(path) {
+            Ok(labels) => {
+                let total = labels.len();
+                let loaded = set_labels(labels);
+                if loaded < total {
+                    warn!(target: "address_book", "Skipped {} labels with an invalid address", total - loaded);
+                }
+                info!(target: "address_book", "Loaded {loaded} labels from {}", path.display());
+            }
+            // Keep the previous labels until the file is fixed
+            Err(e) => warn!(target: "address_book", "Failed to load {}: {e}", path.display()),
+        }
+    }
+
+    /// Loads the address book and spawns a task reloading it on change.
+    /// A missing file only leaves the built-in labels.
+    pub fn spawn(path: PathBuf) {
+        let mut last_modified = modified(&path);
+        if last_modified.is_some() {
+            load(&path);
+        }
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+            loop {
+                interval.tick().await;
+                let current = modified(&path);
+                if current != last_modified {
+                    last_modified = current;
+                    if current.is_some() {
+                        load(&path);
+                    }
+                }
+            }
+        });
+    }
+}
@@ -3,12 +3,18 @@ use leptos_meta::{MetaTags, Stylesheet, Title, provide_meta_context};
 use leptos_router::{components::*, path};
 
 use crate::{
+    address_book::AddressBookScript,
     components::{
+        block_feed::BlockFeedProvider,
+        block_status::ChainBanner,
         footer::Footer,
         header::Header,
+        live::LiveMetricsProvider,
         toast::{ToastProvider, ToastViewport},
+        watchlist::WatchlistProvider,
     },
-    pages::{Account, Allocations, Overview},
+    config::ChainSettingsScript,
+    pages::{Account, Allocations, Block, Fees, Overview, Sudo, Supply, TopHolders, Watchlist},
 };
 
 pub fn shell(options: LeptosOptions) -> impl IntoView {
@@ -21,6 +27,8 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
                 <AutoReload options=options.clone() />
                 <HydrationScripts options/>
                 <MetaTags/>
+                <AddressBookScript/>
+                <ChainSettingsScript/>
             </head>
 
             <body class="min-h-screen bg-[#151515] text-neutral-200 antialiased">
@@ -30,6 +38,17 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
     }
 }
 
+/// `/faucet`, in builds with the `faucet` feature.
+#[cfg(feature = "faucet")]
+#[component(transparent)]
+fn FaucetRoute() -> impl leptos_router::MatchNestedRoutes + Clone {
+    view! { <Route path=path!("/faucet") view=crate::pages::Faucet/> }.into_inner()
+}
+
+#[cfg(not(feature = "faucet"))]
+#[component(transparent)]
+fn FaucetRoute() -> impl leptos_router::MatchNestedRoutes + Clone {}
+
 #[component]
 pub fn App() -> impl IntoView {
     provide_meta_context();
@@ -51,24 +70,38 @@ pub fn App() -> impl IntoView {
 
                 // --- CONTENT WRAPPER ---
                 <div class="relative z-10 flex flex-col flex-1">
-                    <Header />
+                    <BlockFeedProvider>
+                        <Header />
+                        <ChainBanner />
 
-                    <ToastProvider>
-                        <main class="flex-1 relative">
-                            <div class="absolute left-1/2 top-0 -translate-x-1/2 h-full w-px bg-gradient-to-b from-white/5 to-transparent hidden lg:block"></div>
+                        <ToastProvider>
+                            <WatchlistProvider>
+                                <LiveMetricsProvider>
+                                    <main class="flex-1 relative">
+                                        <div class="absolute left-1/2 top-0 -translate-x-1/2 h-full w-px bg-gradient-to-b from-white/5 to-transparent hidden lg:block"></div>
 
-                            <div class="mx-auto max-w-6xl px-4 sm:px-6 lg:px-8 py-8 sm:py-12">
-                                <Routes fallback=|| view! { "Page not found" }>
-                                    <Route path=path!("") view=Overview/>
-                                    <Route path=path!("/accounts/:id") view=Account/>
-                                    <Route path=path!("/allocations") view=Allocations/>
-                                </Routes>
-                            </div>
-                            <ToastViewport />
-                        </main>
-                    </ToastProvider>
+                                        <div class="mx-auto max-w-6xl px-4 sm:px-6 lg:px-8 py-8 sm:py-12">
+                                            <Routes fallback=|| view! { "Page not found" }>
+                                                <Route path=path!("") view=Overview/>
+                                                <Route path=path!("/accounts") view=TopHolders/>
+                                                <Route path=path!("/accounts/:id") view=Account/>
+                                                <Route path=path!("/allocations") view=Allocations/>
+                                                <Route path=path!("/blocks/:id") view=Block/>
+                                                <FaucetRoute/>
+                                                <Route path=path!("/fees") view=Fees/>
+                                                <Route path=path!("/sudo") view=Sudo/>
+                                                <Route path=path!("/supply") view=Supply/>
+                                                <Route path=path!("/watchlist") view=Watchlist/>
+                                            </Routes>
+                                        </div>
+                                        <ToastViewport />
+                                    </main>
+                                </LiveMetricsProvider>
+                            </WatchlistProvider>
+                        </ToastProvider>
 
-                    <Footer />
+                        <Footer />
+                    </BlockFeedProvider>
                 </div>
             </div>
         </Router>
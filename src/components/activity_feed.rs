@@ -0,0 +1,186 @@
+use gloo_timers::future::sleep;
+use leptos::{prelude::*, task::spawn_local};
+use std::time::Duration;
+
+use crate::{
+    BlockSummary, TransferSummary,
+    components::{Card, block_feed::use_block_feed},
+    utils::{
+        blocks_to_str, display_address, format_age, format_balance, shorten_address,
+        ss58_identicon_svg,
+    },
+};
+
+/// Current time in milliseconds, refreshed every second so ages keep
+/// counting between blocks.
+fn use_clock() -> ReadSignal<u64> {
+    let now = || chrono::Utc::now().timestamp_millis() as u64;
+    let (clock, set_clock) = signal(now());
+
+    Effect::new(move |_| {
+        spawn_local(async move {
+            loop {
+                sleep(Duration::from_secs(1)).await;
+                if set_clock.try_set(now()).is_some() {
+                    // Disposed
+                    return;
+                }
+            }
+        });
+    });
+
+    clock
+}
+
+fn account_label(address: &str) -> String {
+    let name = display_address(address);
+    if name == address {
+        shorten_address(address)
+    } else {
+        name
+    }
+}
+
+#[component]
+fn FeedHeader(title: &'static str) -> impl IntoView {
+    view! {
+        <div class="flex items-center justify-between">
+            <h3 class="text-sm font-medium text-neutral-400 uppercase tracking-wider">{ title }</h3>
+            <span class="flex items-center gap-1.5 text-[10px] uppercase tracking-wider text-emerald-400">
+                <span class="h-1.5 w-1.5 rounded-full bg-emerald-500 animate-pulse"></span>
+                "Live"
+            </span>
+        </div>
+    }
+}
+
+#[component]
+fn EmptyFeed(message: &'static str) -> impl IntoView {
+    view! {
+        <p class="px-5 py-8 text-center text-sm text-neutral-500">{ message }</p>
+    }
+}
+
+#[component]
+pub fn LatestBlocks() -> impl IntoView {
+    let feed = use_block_feed();
+    let clock = use_clock();
+
+    view! {
+        <Card padded=false header=view! { <FeedHeader title="Latest Blocks" /> }.into_any()>
+            <Show
+                when=move || feed.recent.with(|recent| !recent.is_empty())
+                fallback=|| view! { <EmptyFeed message="Waiting for the next finalized block..." /> }
+            >
+                <ul class="divide-y divide-white/5">
+                    <For
+                        each=move || feed.recent.get()
+                        key=|block| block.number
+                        children=move |block| view! { <BlockRow block clock /> }
+                    />
+                </ul>
+            </Show>
+        </Card>
+    }
+}
+
+#[component]
+fn BlockRow(block: BlockSummary, clock: ReadSignal<u64>) -> impl IntoView {
+    let timestamp = block.timestamp;
+
+    view! {
+        <li class="flex items-center justify-between gap-4 px-5 py-3 text-sm animate-fade-in-right">
+            <div class="flex flex-col min-w-0">
+                <a
+                    href=format!("/blocks/{}", block.number)
+                    class="font-mono font-bold text-neutral-200 hover:text-emerald-400 transition-colors"
+                >
+                    { format!("#{}", blocks_to_str(block.number)) }
+                </a>
+                <span class="text-[10px] text-neutral-500 font-mono">
+                    { move || format_age(timestamp, clock.get()) }
+                </span>
+            </div>
+            <div class="flex flex-col items-end min-w-0 text-xs">
+                <span class="font-mono text-neutral-400 whitespace-nowrap">
+                    { format!("{} ext · {} events", block.extrinsic_count, block.event_count) }
+                </span>
+                { block.author.map(|author| view! {
+                    <a
+                        href=format!("/accounts/{author}")
+                        class="text-[10px] text-neutral-600 hover:text-emerald-400 truncate max-w-[12rem] transition-colors"
+                        title=author.clone()
+                    >
+                        { account_label(&author) }
+                    </a>
+                })}
+            </div>
+        </li>
+    }
+}
+
+#[component]
+pub fn LatestTransfers() -> impl IntoView {
+    let feed = use_block_feed();
+    let clock = use_clock();
+
+    view! {
+        <Card padded=false header=view! { <FeedHeader title="Latest Transfers" /> }.into_any()>
+            <Show
+                when=move || feed.transfers.with(|transfers| !transfers.is_empty())
+                fallback=|| view! { <EmptyFeed message="No transfer since the explorer started." /> }
+            >
+                <ul class="divide-y divide-white/5">
+                    <For
+                        each=move || feed.transfers.get()
+                        key=|transfer| (transfer.block, transfer.event_index)
+                        children=move |transfer| view! { <TransferRow transfer clock /> }
+                    />
+                </ul>
+            </Show>
+        </Card>
+    }
+}
+
+#[component]
+fn TransferRow(transfer: TransferSummary, clock: ReadSignal<u64>) -> impl IntoView {
+    let timestamp = transfer.timestamp;
+
+    let account = |address: String| {
+        view! {
+            <a
+                href=format!("/accounts/{address}")
+                class="flex items-center gap-1.5 min-w-0 text-neutral-300 hover:text-emerald-400 transition-colors"
+                title=address.clone()
+            >
+                <div inner_html=ss58_identicon_svg(&address, 16) class="shrink-0" />
+                <span class="truncate">{ account_label(&address) }</span>
+            </a>
+        }
+    };
+
+    view! {
+        <li class="flex items-center justify-between gap-4 px-5 py-3 text-sm animate-fade-in-right">
+            <div class="flex flex-col gap-1 min-w-0 text-xs">
+                <div class="flex items-center gap-2 min-w-0">
+                    { account(transfer.from) }
+                    <span class="text-neutral-600">"→"</span>
+                    { account(transfer.to) }
+                </div>
+                <span class="text-[10px] text-neutral-500 font-mono">
+                    <a
+                        href=format!("/blocks/{}", transfer.block)
+                        class="hover:text-emerald-400 transition-colors"
+                    >
+                        { format!("#{}", blocks_to_str(transfer.block)) }
+                    </a>
+                    " · "
+                    { move || format_age(timestamp, clock.get()) }
+                </span>
+            </div>
+            <span class="font-mono font-bold text-white whitespace-nowrap">
+                { format_balance(transfer.amount, true) }
+            </span>
+        </li>
+    }
+}
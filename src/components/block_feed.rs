@@ -0,0 +1,147 @@
+//! Finalized blocks and transfers pushed by `get_block_stream`, shared by
+//! every component showing chain activity so the browser only opens one
+//! stream.
+
+use gloo_timers::future::sleep;
+use leptos::{prelude::*, task::spawn_local};
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{BlockStreamEvent, BlockSummary, TransferSummary, get_block_stream, utils::json_lines};
+
+/// Blocks and transfers kept for the live views, newest first.
+pub const RECENT_BLOCKS: usize = 20;
+pub const RECENT_TRANSFERS: usize = 20;
+
+/// Wait before reopening the stream after losing the explorer.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChainStatus {
+    Connecting,
+    /// Finalized blocks are coming in
+    Live,
+    /// The last finalized block could not be read
+    Degraded(String),
+    /// The explorer lost its node and is resubscribing
+    Reconnecting(String),
+    /// The stream from the explorer was lost
+    Disconnected(String),
+}
+
+impl ChainStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Connecting => "Syncing...",
+            Self::Live => "Live",
+            Self::Degraded(_) => "Degraded",
+            Self::Reconnecting(_) => "Reconnecting",
+            Self::Disconnected(_) => "Disconnected",
+        }
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Connecting | Self::Live => None,
+            Self::Degraded(detail) | Self::Reconnecting(detail) | Self::Disconnected(detail) => {
+                Some(detail)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct BlockFeedContext {
+    pub status: RwSignal<ChainStatus>,
+    pub recent: RwSignal<VecDeque<BlockSummary>>,
+    pub transfers: RwSignal<VecDeque<TransferSummary>>,
+}
+
+pub fn use_block_feed() -> BlockFeedContext {
+    use_context::<BlockFeedContext>().expect("BlockFeedProvider missing!")
+}
+
+impl BlockFeedContext {
+    pub fn latest(&self) -> Option<BlockSummary> {
+        self.recent.with(|recent| recent.front().cloned())
+    }
+
+    fn apply(&self, event: BlockStreamEvent) {
+        match event {
+            BlockStreamEvent::Block(block) => {
+                self.recent.update(|recent| {
+                    // Recent blocks are sent again when the stream reopens
+                    if recent.front().is_some_and(|b| b.number >= block.number) {
+                        return;
+                    }
+                    recent.push_front(block);
+                    recent.truncate(RECENT_BLOCKS);
+                });
+                self.status.set(ChainStatus::Live);
+            }
+            BlockStreamEvent::Transfers { transfers } => {
+                self.transfers.update(|recent| {
+                    let newest = recent.front().map(|t| (t.block, t.event_index));
+                    for transfer in transfers {
+                        if newest >= Some((transfer.block, transfer.event_index)) {
+                            continue;
+                        }
+                        recent.push_front(transfer);
+                    }
+                    recent.truncate(RECENT_TRANSFERS);
+                });
+            }
+            BlockStreamEvent::Error { message, .. } => {
+                self.status.set(ChainStatus::Degraded(message))
+            }
+            BlockStreamEvent::Reconnecting { reason } => {
+                self.status.set(ChainStatus::Reconnecting(reason))
+            }
+        }
+    }
+}
+
+#[component]
+pub fn BlockFeedProvider(children: Children) -> impl IntoView {
+    let ctx = BlockFeedContext {
+        status: RwSignal::new(ChainStatus::Connecting),
+        recent: RwSignal::new(VecDeque::new()),
+        transfers: RwSignal::new(VecDeque::new()),
+    };
+
+    // Effects only run in the browser, after hydration
+    Effect::new(move |_| {
+        spawn_local(async move {
+            loop {
+                let reason = match follow(ctx).await {
+                    Ok(()) => "The explorer closed the block stream".to_string(),
+                    Err(e) => e.to_string(),
+                };
+                if ctx
+                    .status
+                    .try_set(ChainStatus::Disconnected(reason))
+                    .is_some()
+                {
+                    // Disposed
+                    return;
+                }
+                sleep(RETRY_DELAY).await;
+            }
+        });
+    });
+
+    provide_context(ctx);
+    view! { {children()} }
+}
+
+async fn follow(ctx: BlockFeedContext) -> Result<(), ServerFnError> {
+    use futures::StreamExt;
+
+    let stream = get_block_stream().await?.into_inner();
+    let mut stream = std::pin::pin!(json_lines::<BlockStreamEvent, _>(stream));
+
+    while let Some(event) = stream.next().await {
+        ctx.apply(event?);
+    }
+
+    Ok(())
+}
@@ -1,80 +1,155 @@
 use leptos::prelude::*;
 
-use crate::get_block_number_stream;
+use crate::components::block_feed::{ChainStatus, use_block_feed};
+use crate::utils::{display_address, shorten_address};
+
+/// Blocks listed in the ticker under the status pill.
+const TICKER_BLOCKS: usize = 5;
 
 #[component]
 pub fn BlockStatus() -> impl IntoView {
-    let current_block_num = RwSignal::new("Syncing...".to_string());
-
-    let stream_worker = Action::new(move |_: &()| async move {
-        match get_block_number_stream().await {
-            Ok(stream) => {
-                use futures::StreamExt;
-
-                let mut stream = stream.into_inner();
-                while let Some(Ok(data)) = stream.next().await {
-                    current_block_num.set(data);
-                }
-            }
-            Err(e) => leptos::logging::error!("Stream init error: {:?}", e),
-        }
-    });
+    let feed = use_block_feed();
 
-    Effect::new(move |_| {
-        stream_worker.dispatch(());
-    });
+    let current_block_num = move || feed.latest().map(|b| b.number);
+    let healthy = move || feed.status.with(|s| matches!(s, ChainStatus::Live));
 
-    // --- 2. VIEW ---
+    // --- VIEW ---
     view! {
-        <div class="flex items-center gap-3 px-3 py-1.5 rounded-full border border-white/5 bg-white/[0.03]">
+        <div class="relative group">
+            <div
+                class="flex items-center gap-3 px-3 py-1.5 rounded-full border border-white/5 bg-white/[0.03]"
+                title=move || feed.status.with(|s| s.detail().map(str::to_string))
+            >
 
-            // --- RING ANIMATION ---
-            <div class="relative h-5 w-5 flex items-center justify-center">
-                {move || {
-                    view! {
-                        <For
-                            each=move || std::iter::once(current_block_num.get())
-                            key=|bn| bn.clone()
-                            children=move |_| {
-                                view! {
-                                    <svg class="transform -rotate-90 w-5 h-5" viewBox="0 0 24 24">
-                                        <circle cx="12" cy="12" r="10" stroke="currentColor" stroke-width="3" fill="transparent" class="text-white/10" />
-                                        <circle
-                                            cx="12" cy="12" r="10"
-                                            stroke="currentColor" stroke-width="3" fill="transparent"
-                                            stroke-dasharray="62.83"
-                                            stroke-dashoffset="62.83"
-                                            stroke-linecap="round"
-                                            class="text-emerald-500 animate-block-fill"
-                                        />
-                                    </svg>
+                // --- RING ANIMATION ---
+                <div class="relative h-5 w-5 flex items-center justify-center">
+                    {move || {
+                        view! {
+                            <For
+                                each=move || std::iter::once(current_block_num())
+                                key=|bn| *bn
+                                children=move |_| {
+                                    view! {
+                                        <svg class="transform -rotate-90 w-5 h-5" viewBox="0 0 24 24">
+                                            <circle cx="12" cy="12" r="10" stroke="currentColor" stroke-width="3" fill="transparent" class="text-white/10" />
+                                            <circle
+                                                cx="12" cy="12" r="10"
+                                                stroke="currentColor" stroke-width="3" fill="transparent"
+                                                stroke-dasharray="62.83"
+                                                stroke-dashoffset="62.83"
+                                                stroke-linecap="round"
+                                                class=move || if healthy() {
+                                                    "text-emerald-500 animate-block-fill"
+                                                } else {
+                                                    "text-amber-500 animate-block-fill"
+                                                }
+                                            />
+                                        </svg>
+                                    }
                                 }
-                            }
-                        />
-                    }
-                }}
+                            />
+                        }
+                    }}
 
-                <div class="absolute inset-0 flex items-center justify-center">
-                    <div class="h-1.5 w-1.5 rounded-sm bg-emerald-500/50"></div>
+                    <div class="absolute inset-0 flex items-center justify-center">
+                        <div class=move || if healthy() {
+                            "h-1.5 w-1.5 rounded-sm bg-emerald-500/50"
+                        } else {
+                            "h-1.5 w-1.5 rounded-sm bg-amber-500 animate-pulse"
+                        }></div>
+                    </div>
                 </div>
-            </div>
 
-            // --- BLOCK NUMBER TEXT ---
-            <div class="flex flex-col leading-none">
-                <span class="text-[9px] uppercase tracking-wider text-neutral-500 font-bold">
-                    "Current Block"
-                </span>
-                <div class="flex items-center gap-1 min-h-[1rem]">
-                    <span class="text-xs font-mono font-bold text-neutral-200">
-                        {move || {
-                                if let Ok(num) = current_block_num.get().parse::<u64>() {
-                                    format!("#{}", format_number(num))
-                                } else {
-                                    "Syncing...".to_string()
-                                }
+                // --- BLOCK NUMBER TEXT ---
+                <div class="flex flex-col leading-none">
+                    <span class=move || if healthy() {
+                        "text-[9px] uppercase tracking-wider text-neutral-500 font-bold"
+                    } else {
+                        "text-[9px] uppercase tracking-wider text-amber-400 font-bold"
+                    }>
+                        {move || match feed.status.get() {
+                            ChainStatus::Live => "Current Block",
+                            status => status.label(),
                         }}
                     </span>
+                    <div class="flex items-center gap-1 min-h-[1rem]">
+                        <span class="text-xs font-mono font-bold text-neutral-200">
+                            {move || match current_block_num() {
+                                Some(num) => format!("#{}", format_number(num as u64)),
+                                None => "Syncing...".to_string(),
+                            }}
+                        </span>
+                        {move || feed.latest().filter(|b| b.finality_lag > 0).map(|b| view! {
+                            <span class="text-[9px] font-mono text-neutral-500" title="Blocks between the best and the finalized block">
+                                "+" { b.finality_lag }
+                            </span>
+                        })}
+                    </div>
+                </div>
+            </div>
+
+            <BlockTicker />
+        </div>
+    }
+    // Keeps the header's view type shallow enough for the compiler
+    .into_any()
+}
+
+/// Shown while the explorer can't reach any node, so cached pages aren't
+/// mistaken for live ones.
+#[component]
+pub fn ChainBanner() -> impl IntoView {
+    let feed = use_block_feed();
+
+    move || match feed.status.get() {
+        ChainStatus::Reconnecting(reason) => Some(view! {
+            <div class="border-b border-amber-500/20 bg-amber-500/10 px-4 py-2 text-center text-xs text-amber-300" title=reason>
+                "The chain is unreachable, data may be outdated. Reconnecting..."
+            </div>
+        }),
+        _ => None,
+    }
+}
+
+/// Latest finalized blocks, shown when hovering the status pill.
+#[component]
+fn BlockTicker() -> impl IntoView {
+    let feed = use_block_feed();
+
+    view! {
+        <div class="absolute right-0 top-full z-50 pt-2 w-72 hidden group-hover:block">
+            <div class="rounded-xl border border-white/10 bg-[#0b0c0c]/95 backdrop-blur-xl shadow-xl overflow-hidden">
+                <div class="px-4 py-2 border-b border-white/5 text-[9px] uppercase tracking-wider text-neutral-500 font-bold">
+                    "Latest Blocks"
                 </div>
+                <For
+                    each=move || feed.recent.with(|recent| recent.iter().take(TICKER_BLOCKS).cloned().collect::<Vec<_>>())
+                    key=|block| block.number
+                    children=move |block| {
+                        let author = block.author.as_deref().map(|author| {
+                            let name = display_address(author);
+                            if name == author { shorten_address(author) } else { name }
+                        });
+                        view! {
+                            <a
+                                href=format!("/blocks/{}", block.number)
+                                class="flex items-center justify-between gap-3 px-4 py-2 text-xs hover:bg-white/5 transition-colors animate-fade-in-right"
+                            >
+                                <span class="font-mono font-bold text-neutral-200">
+                                    { format!("#{}", format_number(block.number as u64)) }
+                                </span>
+                                <span class="flex flex-col items-end leading-tight">
+                                    <span class="font-mono text-neutral-400">
+                                        { format!("{} ext · {} events", block.extrinsic_count, block.event_count) }
+                                    </span>
+                                    { author.map(|author| view! {
+                                        <span class="text-[10px] text-neutral-600 truncate max-w-[10rem]">{ author }</span>
+                                    })}
+                                </span>
+                            </a>
+                        }
+                    }
+                />
             </div>
         </div>
     }
@@ -1,15 +1,31 @@
-use leptos::prelude::*;
+use gloo_timers::future::sleep;
+use leptos::{prelude::*, task::spawn_local};
+use serde::{Serialize, de::DeserializeOwned};
+use std::time::Duration;
 
-use crate::{get_balance_of, get_circulating_supply, get_total_issuance, utils::format_balance};
+use crate::{
+    AppError, MetricKey, MetricValue,
+    components::{live::LiveValue, toast::ToastContext},
+    get_balance_of, get_circulating_supply, get_sudo_key, get_total_issuance,
+    utils::{display_address_with_hint, format_balance, ss58_identicon_svg},
+};
 
 #[component]
 pub fn FetchableBalanceFree(address: String) -> impl IntoView {
-    let balance = OnceResource::new(get_balance_of(address));
+    let balance = Resource::new(|| (), {
+        let address = address.clone();
+        move |_| get_balance_of(address.clone())
+    });
 
     view! {
         <FetchableData data=balance render=move |value| { view! {
             <span class="text-3xl font-semibold text-neutral-50">
-                { format_balance(value.free, true) }
+                <LiveValue
+                    key=MetricKey::Balances(address.clone())
+                    initial=value.free
+                    value=|v: &MetricValue| v.balances().map(|b| b.free)
+                    render=|free| format_balance(free, true)
+                />
             </span>
         }.into_any() }/>
     }
@@ -17,12 +33,17 @@ pub fn FetchableBalanceFree(address: String) -> impl IntoView {
 
 #[component]
 pub fn FetchableTotalIssuance() -> impl IntoView {
-    let balance = OnceResource::new(get_total_issuance());
+    let balance = Resource::new(|| (), |_| get_total_issuance());
 
     view! {
         <FetchableData data=balance render=move |value| { view! {
             <span class="text-3xl font-semibold text-neutral-50">
-                { format_balance(value, true) }
+                <LiveValue
+                    key=MetricKey::TotalIssuance
+                    initial=value
+                    value=MetricValue::amount
+                    render=|amount| format_balance(amount, true)
+                />
             </span>
         }.into_any() }/>
     }
@@ -30,23 +51,72 @@ pub fn FetchableTotalIssuance() -> impl IntoView {
 
 #[component]
 pub fn FetchableCirculatingSupply() -> impl IntoView {
-    let balance = OnceResource::new(get_circulating_supply());
+    let balance = Resource::new(|| (), |_| get_circulating_supply(None));
 
     view! {
         <FetchableData data=balance render=move |value| { view! {
             <span class="text-3xl font-semibold text-neutral-50">
-                { format_balance(value, true) }
+                <LiveValue
+                    key=MetricKey::CirculatingSupply
+                    initial=value
+                    value=MetricValue::amount
+                    render=|amount| format_balance(amount, true)
+                />
             </span>
         }.into_any() }/>
     }
 }
 
 #[component]
-pub fn FetchableData<T, F>(data: OnceResource<Result<T, ServerFnError>>, render: F) -> impl IntoView
+pub fn FetchableSudoKey() -> impl IntoView {
+    let key = Resource::new(|| (), |_| get_sudo_key());
+
+    view! {
+        <FetchableData data=key render={move |value: Option<String>| match value {
+            Some(address) => view! {
+                <a
+                    href=format!("/accounts/{}", address)
+                    class="flex items-center gap-3 min-w-0 text-base sm:text-lg text-neutral-50 hover:text-emerald-400 transition-colors"
+                    title=address.clone()
+                >
+                    <div inner_html=ss58_identicon_svg(&address, 28) class="shrink-0" />
+                    <span class="truncate">{ display_address_with_hint(&address) }</span>
+                </a>
+            }.into_any(),
+            None => view! {
+                <span class="text-base sm:text-lg text-neutral-500">"No sudo key"</span>
+            }.into_any(),
+        }}/>
+    }
+}
+
+/// Renders the value of `data` once loaded. Failures show what went wrong,
+/// raise a toast and offer a retry when it may help.
+#[component]
+pub fn FetchableData<T, F>(data: Resource<Result<T, AppError>>, render: F) -> impl IntoView
 where
-    T: 'static + Send + Sync + Clone,
+    T: 'static + Send + Sync + Clone + Serialize + DeserializeOwned,
     F: Fn(T) -> AnyView + 'static + Clone + Send + Sync,
 {
+    let toast = use_context::<ToastContext>();
+
+    // Effects only run in the browser, errors of the server render included
+    Effect::new(move |_| {
+        let Some(Err(e)) = data.get() else {
+            return;
+        };
+        if let Some(toast) = toast.as_ref() {
+            (toast.add_toast)(e.to_string());
+        }
+        // Tried again on its own once the quota allows it
+        if let AppError::RateLimited { retry_after_secs } = e {
+            spawn_local(async move {
+                sleep(Duration::from_secs(retry_after_secs)).await;
+                data.refetch();
+            });
+        }
+    });
+
     view! {
                     <Suspense
                         fallback=move || view! {
@@ -56,11 +126,40 @@ where
                         {
                             move || data.get().map(|res| match res {
                                 Ok(value) => { render(value) },
-                                Err(_) => view! {
-                                   <span class="inline-block h-[1.2em] w-32 rounded bg-white/10 animate-pulse align-middle"></span>
+                                Err(error) => view! {
+                                    <ErrorNotice error on_retry=move || data.refetch() />
                                 }.into_any(),
                             })
                         }
                     </Suspense>
     }
 }
+
+/// Compact error view fitting in the cards, with a retry button for
+/// transient failures.
+#[component]
+pub fn ErrorNotice<R>(error: AppError, on_retry: R) -> impl IntoView
+where
+    R: Fn() + 'static + Send + Sync,
+{
+    let retry = error.is_retryable().then(|| {
+        view! {
+            <button
+                class="rounded-full border border-white/10 px-2.5 py-0.5 text-[10px] uppercase tracking-wider text-neutral-400 hover:text-white hover:border-white/20 transition-colors"
+                on:click=move |_| on_retry()
+            >
+                "Retry"
+            </button>
+        }
+    });
+
+    view! {
+        <span class="inline-flex flex-wrap items-center gap-2 align-middle" title=error.to_string()>
+            <span class="flex items-center gap-1.5 text-sm font-medium text-rose-400">
+                <span class="h-1.5 w-1.5 rounded-full bg-rose-500"></span>
+                { error.title() }
+            </span>
+            { retry }
+        </span>
+    }
+}
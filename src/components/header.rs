@@ -77,6 +77,23 @@ pub fn Header() -> impl IntoView {
                         <a href="/allocations" class=move || get_nav_class("/allocations", false)>
                             "Token Sources"
                         </a>
+                        <a href="/accounts" class=move || get_nav_class("/accounts", false)>
+                            "Holders"
+                        </a>
+                        <a href="/fees" class=move || get_nav_class("/fees", false)>
+                            "Fees"
+                        </a>
+                        <a href="/sudo" class=move || get_nav_class("/sudo", false)>
+                            "Sudo"
+                        </a>
+                        <a href="/watchlist" class=move || get_nav_class("/watchlist", false)>
+                            "Watchlist"
+                        </a>
+                        { cfg!(feature = "faucet").then(|| view! {
+                            <a href="/faucet" class=move || get_nav_class("/faucet", false)>
+                                "Faucet"
+                            </a>
+                        }) }
                     </nav>
 
                     // --- RIGHT SIDE (BLOCK STATUS + MOBILE BTN) ---
@@ -103,7 +120,7 @@ pub fn Header() -> impl IntoView {
                 // --- MOBILE NAVIGATION DROPDOWN ---
                 <div class=move || format!(
                     "sm:hidden border-t border-white/5 bg-[#0b0c0c] overflow-hidden transition-all duration-300 ease-in-out {}",
-                    if is_menu_open.get() { "max-h-[300px] opacity-100" } else { "max-h-0 opacity-0" }
+                    if is_menu_open.get() { "max-h-[400px] opacity-100" } else { "max-h-0 opacity-0" }
                 )>
                     <nav class="flex flex-col p-4 gap-2">
 
@@ -123,6 +140,23 @@ pub fn Header() -> impl IntoView {
                         <a href="/allocations" class=move || get_nav_class("/allocations", true) on:click=close_menu>
                             "Token Sources"
                         </a>
+                        <a href="/accounts" class=move || get_nav_class("/accounts", true) on:click=close_menu>
+                            "Holders"
+                        </a>
+                        <a href="/fees" class=move || get_nav_class("/fees", true) on:click=close_menu>
+                            "Fees"
+                        </a>
+                        <a href="/sudo" class=move || get_nav_class("/sudo", true) on:click=close_menu>
+                            "Sudo"
+                        </a>
+                        <a href="/watchlist" class=move || get_nav_class("/watchlist", true) on:click=close_menu>
+                            "Watchlist"
+                        </a>
+                        { cfg!(feature = "faucet").then(|| view! {
+                            <a href="/faucet" class=move || get_nav_class("/faucet", true) on:click=close_menu>
+                                "Faucet"
+                            </a>
+                        }) }
                     </nav>
                 </div>
             </div>
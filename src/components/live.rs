@@ -0,0 +1,158 @@
+//! Values kept up to date by the server while the page is open.
+//!
+//! Components register the `MetricKey`s they display and the provider keeps a
+//! single `subscribe_metrics` stream open for all of them, reopened whenever
+//! the set of keys changes.
+
+use gloo_timers::future::sleep;
+use leptos::{prelude::*, task::spawn_local};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+use crate::{MetricKey, MetricUpdate, MetricValue, subscribe_metrics, utils::json_lines};
+
+/// Wait before reopening a stream that failed or was closed by the server.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// How long a changed value stays highlighted, matching `animate-value-flash`.
+const FLASH_DURATION: Duration = Duration::from_millis(1500);
+
+#[derive(Clone, Copy)]
+pub struct LiveMetricsContext {
+    /// Number of mounted components displaying each key
+    subscribers: RwSignal<BTreeMap<MetricKey, usize>>,
+    values: RwSignal<HashMap<MetricKey, MetricValue>>,
+}
+
+/// Latest value pushed for `key`, `None` until the first update arrives or
+/// outside a `LiveMetricsProvider`.
+pub fn use_live_metric(key: MetricKey) -> Signal<Option<MetricValue>> {
+    let Some(ctx) = use_context::<LiveMetricsContext>() else {
+        return Signal::stored(None);
+    };
+
+    // Effects only run in the browser, the server never subscribes
+    Effect::new({
+        let key = key.clone();
+        move |_| {
+            ctx.subscribers
+                .update(|subscribers| *subscribers.entry(key.clone()).or_default() += 1);
+        }
+    });
+    on_cleanup({
+        let key = key.clone();
+        move || {
+            ctx.subscribers.try_update(|subscribers| {
+                if let Some(count) = subscribers.get_mut(&key) {
+                    *count -= 1;
+                    if *count == 0 {
+                        subscribers.remove(&key);
+                    }
+                }
+            });
+        }
+    });
+
+    Signal::derive(move || ctx.values.with(|values| values.get(&key).cloned()))
+}
+
+#[component]
+pub fn LiveMetricsProvider(children: Children) -> impl IntoView {
+    let ctx = LiveMetricsContext {
+        subscribers: RwSignal::new(BTreeMap::new()),
+        values: RwSignal::new(HashMap::new()),
+    };
+    let keys = Memo::new(move |_| {
+        ctx.subscribers
+            .with(|subscribers| subscribers.keys().cloned().collect::<Vec<_>>())
+    });
+    // Bumped on every change of `keys`, telling the previous stream to stop
+    let generation = StoredValue::new(0u64);
+
+    Effect::new(move |_| {
+        let keys = keys.get();
+        let current = generation.get_value() + 1;
+        generation.set_value(current);
+        if keys.is_empty() {
+            return;
+        }
+
+        spawn_local(async move {
+            while generation.try_get_value() == Some(current) {
+                if let Err(e) = follow(keys.clone(), ctx, generation, current).await {
+                    leptos::logging::error!("Metrics stream error: {:?}", e);
+                }
+                sleep(RETRY_DELAY).await;
+            }
+        });
+    });
+
+    provide_context(ctx);
+    view! { {children()} }
+}
+
+/// Applies the updates of one stream until it ends or a newer one replaces
+/// it. A replaced stream is only dropped when its next line arrives.
+async fn follow(
+    keys: Vec<MetricKey>,
+    ctx: LiveMetricsContext,
+    generation: StoredValue<u64>,
+    current: u64,
+) -> Result<(), ServerFnError> {
+    use futures::StreamExt;
+
+    let stream = subscribe_metrics(keys).await?.into_inner();
+    let mut stream = std::pin::pin!(json_lines::<Vec<MetricUpdate>, _>(stream));
+
+    while let Some(updates) = stream.next().await {
+        if generation.try_get_value() != Some(current) {
+            return Ok(());
+        }
+        let updates = updates?;
+        ctx.values.try_update(|values| {
+            for update in updates {
+                values.insert(update.key, update.value);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Renders `initial`, then every value pushed for `key`. The text is briefly
+/// highlighted each time it changes.
+#[component]
+pub fn LiveValue<T, V, R>(key: MetricKey, initial: T, value: V, render: R) -> impl IntoView
+where
+    T: 'static + Send + Sync + Clone + PartialEq,
+    V: Fn(&MetricValue) -> Option<T> + 'static + Send + Sync,
+    R: Fn(T) -> String + 'static + Send + Sync,
+{
+    let live = use_live_metric(key);
+    let current = Memo::new(move |_| {
+        live.with(|live| live.as_ref().and_then(&value))
+            .unwrap_or_else(|| initial.clone())
+    });
+
+    // Number of highlights running, so a quick second change isn't cut short
+    let flashing = RwSignal::new(0usize);
+    Effect::new(move |previous: Option<T>| {
+        let value = current.get();
+        if previous.is_some_and(|previous| previous != value) {
+            flashing.update(|n| *n += 1);
+            spawn_local(async move {
+                sleep(FLASH_DURATION).await;
+                flashing.try_update(|n| *n -= 1);
+            });
+        }
+        value
+    });
+
+    view! {
+        <span class={move || if flashing.get() > 0 { "animate-value-flash" } else { "" }}>
+            { move || render(current.get()) }
+        </span>
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
     components::{
         fetchable_balance::{
-            FetchableBalanceFree, FetchableCirculatingSupply, FetchableTotalIssuance,
+            FetchableBalanceFree, FetchableCirculatingSupply, FetchableSudoKey,
+            FetchableTotalIssuance,
         },
         simple_card_metrics::SimpleCardMetrics,
     },
@@ -44,3 +45,15 @@ pub fn TreasuryBalance() -> impl IntoView {
             </SimpleCardMetrics>
     }
 }
+
+#[component]
+pub fn SudoKey() -> impl IntoView {
+    view! {
+            <SimpleCardMetrics
+                title="Sudo Key"
+                description="Account currently holding root privileges. Every use is recorded in the sudo activity log."
+            >
+              <FetchableSudoKey />
+            </SimpleCardMetrics>
+    }
+}
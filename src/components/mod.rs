@@ -1,12 +1,19 @@
 use leptos::prelude::*;
 
+pub mod activity_feed;
+pub mod block_feed;
 pub mod block_status;
 pub mod fetchable_balance;
 pub mod footer;
 pub mod header;
+pub mod live;
 pub mod metrics_cards;
+pub mod payout_status;
+pub mod search;
 pub mod simple_card_metrics;
+pub mod supply_breakdown;
 pub mod toast;
+pub mod watchlist;
 
 #[component]
 pub fn Card(
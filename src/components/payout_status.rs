@@ -0,0 +1,182 @@
+use leptos::prelude::*;
+
+use crate::{
+    EpochPayoutSummary, PayoutHistoryPage, PayoutStatus,
+    components::{Card, fetchable_balance::FetchableData},
+    get_payout_history, get_payout_status,
+    utils::{blocks_to_human_duration, blocks_to_str, format_balance, format_timestamp},
+};
+
+/// Epochs listed in the payout history.
+const HISTORY_EPOCHS: usize = 10;
+
+/// Human readable ETA of the next epoch payout.
+pub fn payout_eta(status: &PayoutStatus) -> String {
+    if status.in_progress() {
+        "In progress".to_string()
+    } else if status.blocks_until_next() == 0 {
+        "Due now".to_string()
+    } else {
+        format!(
+            "In {}",
+            blocks_to_human_duration(status.blocks_until_next())
+        )
+    }
+}
+
+/// Next payout block and ETA, with the progress of a batched payout.
+#[component]
+pub fn PayoutStatusCard() -> impl IntoView {
+    let status = Resource::new(|| (), |_| get_payout_status());
+
+    view! {
+        <FetchableData
+            data=status
+            render={move |status: PayoutStatus| view! { <PayoutStatusView status /> }.into_any()}
+        />
+    }
+}
+
+#[component]
+fn PayoutStatusView(status: PayoutStatus) -> impl IntoView {
+    let eta = payout_eta(&status);
+
+    // The cursor is the next allocation id to pay, ids start at 0
+    let progress = status.payout_cursor.map(|cursor| {
+        let total = status.total_allocations.max(1);
+        let pct = (cursor.min(total) as f64 / total as f64 * 100.0).clamp(0.0, 100.0);
+        (cursor, pct)
+    });
+
+    view! {
+        <Card header=view! {
+            <div class="flex items-center justify-between">
+                <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">"Epoch Payouts"</h2>
+                <span class="text-xs text-neutral-500 font-mono">"Epoch " { status.epoch_index }</span>
+            </div>
+        }.into_any()>
+            <div class="flex flex-col gap-5">
+                <div class="grid grid-cols-2 lg:grid-cols-4 gap-4">
+                    <div>
+                        <div class="text-[10px] uppercase text-neutral-500 mb-1">"Next Payout"</div>
+                        <div class="font-mono font-bold text-white">"#" { blocks_to_str(status.next_payout_at) }</div>
+                    </div>
+                    <div>
+                        <div class="text-[10px] uppercase text-neutral-500 mb-1">"ETA"</div>
+                        <div class="font-mono font-bold text-emerald-400">{ eta }</div>
+                    </div>
+                    <div>
+                        <div class="text-[10px] uppercase text-neutral-500 mb-1">"Epoch Duration"</div>
+                        <div class="font-mono text-neutral-200">{ blocks_to_human_duration(status.epoch_duration) }</div>
+                    </div>
+                    <div>
+                        <div class="text-[10px] uppercase text-neutral-500 mb-1">"Batch Size"</div>
+                        <div class="font-mono text-neutral-200">{ status.max_payouts_per_block } " / block"</div>
+                    </div>
+                </div>
+
+                { progress.map(|(cursor, pct)| view! {
+                    <div class="space-y-2">
+                        <div class="flex justify-between text-xs">
+                            <span class="text-neutral-400">"Paying allocations"</span>
+                            <span class="text-white font-mono">
+                                { cursor } " / " { status.total_allocations }
+                            </span>
+                        </div>
+                        <div class="h-1.5 w-full rounded-full bg-white/5 overflow-hidden">
+                            <div
+                                class="h-full bg-emerald-500 shadow-[0_0_10px_rgba(16,185,129,0.4)]"
+                                style=format!("width: {:.2}%;", pct)
+                            ></div>
+                        </div>
+                    </div>
+                })}
+            </div>
+        </Card>
+    }
+}
+
+/// Amounts released during the latest epoch payouts, per envelope.
+#[component]
+pub fn PayoutHistory() -> impl IntoView {
+    let history = Resource::new(|| (), |_| get_payout_history(HISTORY_EPOCHS));
+
+    view! {
+        <FetchableData
+            data=history
+            render={move |history: PayoutHistoryPage| {
+                let since = history
+                    .indexed_since
+                    .map(|block| format!("Indexed since block #{}", blocks_to_str(block)));
+                if history.epochs.is_empty() {
+                    let message = match history.indexed_since {
+                        Some(block) => format!("No epoch payout since block #{}.", blocks_to_str(block)),
+                        None => "No epoch payout recorded yet.".to_string(),
+                    };
+                    view! {
+                        <div class="rounded-2xl border border-dashed border-white/10 bg-white/[0.02] p-6 sm:p-8 text-center">
+                            <p class="text-neutral-500 text-sm">{ message }</p>
+                        </div>
+                    }.into_any()
+                } else {
+                    view! {
+                        <div class="flex flex-col gap-4">
+                            { history.epochs.into_iter().map(|epoch| view! { <EpochItem epoch /> }).collect::<Vec<_>>() }
+                            { since.map(|since| view! {
+                                <p class="text-[10px] text-neutral-600 font-mono">{ since }</p>
+                            })}
+                        </div>
+                    }.into_any()
+                }
+            }}
+        />
+    }
+}
+
+#[component]
+fn EpochItem(epoch: EpochPayoutSummary) -> impl IntoView {
+    let blocks = if epoch.first_block == epoch.last_block {
+        format!("Block #{}", blocks_to_str(epoch.first_block))
+    } else {
+        format!(
+            "Blocks #{} – #{}",
+            blocks_to_str(epoch.first_block),
+            blocks_to_str(epoch.last_block)
+        )
+    };
+
+    view! {
+        <Card>
+            <div class="flex flex-col gap-3">
+                <div class="flex flex-wrap items-center justify-between gap-2">
+                    <div class="flex items-center gap-2">
+                        <span class="text-[10px] font-bold uppercase tracking-wider text-emerald-500">"Epoch"</span>
+                        <h3 class="text-sm sm:text-base font-semibold font-mono text-white">{ epoch.epoch }</h3>
+                        { (!epoch.completed).then(|| view! {
+                            <span class="rounded-md border px-2 py-0.5 text-[10px] font-medium text-amber-400 bg-amber-500/10 border-amber-500/20">
+                                "Partial"
+                            </span>
+                        })}
+                    </div>
+                    <span class="font-mono font-bold text-emerald-400">
+                        "+" { format_balance(epoch.total, true) }
+                    </span>
+                </div>
+
+                <div class="grid grid-cols-1 sm:grid-cols-2 gap-x-6 gap-y-1 text-xs">
+                    { epoch.per_envelope.into_iter().map(|p| view! {
+                        <div class="flex justify-between gap-3">
+                            <span class="text-neutral-400 truncate">{ p.envelope }</span>
+                            <span class="font-mono text-neutral-200">{ format_balance(p.amount, true) }</span>
+                        </div>
+                    }).collect::<Vec<_>>() }
+                </div>
+
+                <div class="flex flex-wrap justify-between gap-2 text-[10px] text-neutral-500 font-mono">
+                    <span>{ blocks } " • " { epoch.allocations } " allocations"</span>
+                    <span>{ format_timestamp(epoch.timestamp) }</span>
+                </div>
+            </div>
+        </Card>
+    }
+}
@@ -0,0 +1,187 @@
+use leptos::prelude::*;
+use leptos_router::hooks::use_navigate;
+use web_sys::SubmitEvent;
+
+use crate::{
+    SearchKind, SearchSuggestion,
+    components::toast::use_toast,
+    search, ss58,
+    utils::{decode_hex, search_known_addresses},
+};
+
+/// Shortest query worth sending for autocompletion.
+const MIN_QUERY_LEN: usize = 3;
+
+/// Rejects queries that can't match anything, before asking the server.
+fn validate_query(query: &str) -> Result<(), String> {
+    let query = query.trim();
+
+    if query.starts_with("0x") {
+        return match decode_hex(query) {
+            Some(bytes) if bytes.len() == 32 => Ok(()),
+            _ => Err("Hashes and public keys are 0x followed by 64 hex characters".to_string()),
+        };
+    }
+
+    let number = query.trim_start_matches('#');
+    if !number.is_empty()
+        && number
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, ' ' | ',' | '_'))
+    {
+        return Ok(());
+    }
+
+    if !search_known_addresses(query).is_empty() {
+        return Ok(());
+    }
+
+    // Anything else has to be an account address
+    ss58::decode_account(query)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Search box resolving addresses, public keys, names, blocks and extrinsics,
+/// with autocomplete suggestions. Submitting navigates to the best match.
+#[component]
+pub fn SearchBox() -> impl IntoView {
+    let toast = use_toast();
+    let navigate = use_navigate();
+    let query = RwSignal::new(String::new());
+    let open = RwSignal::new(false);
+    let error = RwSignal::new(None::<String>);
+
+    let suggestions = Resource::new(
+        move || query.get(),
+        |q| async move {
+            if q.trim().len() < MIN_QUERY_LEN || validate_query(&q).is_err() {
+                Ok(vec![])
+            } else {
+                search(q).await
+            }
+        },
+    );
+
+    let resolve = Action::new(|q: &String| search(q.clone()));
+
+    Effect::new(move |_| {
+        let Some(res) = resolve.value().get() else {
+            return;
+        };
+        match res.map(|s| s.into_iter().next()) {
+            Ok(Some(best)) => navigate(&best.href, Default::default()),
+            Ok(None) => (toast.add_toast)("No account, block or extrinsic found".to_string()),
+            Err(e) => (toast.add_toast)(e.to_string()),
+        }
+    });
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        let q = query.get().trim().to_string();
+        if q.is_empty() {
+            return;
+        }
+        match validate_query(&q) {
+            Ok(()) => {
+                open.set(false);
+                resolve.dispatch(q);
+            }
+            Err(e) => error.set(Some(e)),
+        }
+    };
+
+    view! {
+        <form on:submit=on_submit class="relative">
+            <div class="relative flex items-center overflow-hidden rounded-[2rem] border border-white/10 bg-[#0F0F0F] shadow-2xl transition-all focus-within:border-emerald-500/50 focus-within:ring-1 focus-within:ring-emerald-500/20">
+
+                <div class="pl-4 sm:pl-5 text-neutral-500">
+                    <svg xmlns="http://www.w3.org/2000/svg" width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                        <circle cx="11" cy="11" r="8"></circle>
+                        <path d="m21 21-4.3-4.3"></path>
+                    </svg>
+                </div>
+
+                <input
+                    type="text"
+                    autocomplete="off"
+                    spellcheck="false"
+                    placeholder="Address, public key, name, block or extrinsic hash"
+                    class="h-12 sm:h-14 w-full bg-transparent px-3 sm:px-4 text-base text-white placeholder:text-neutral-600 focus:outline-none"
+                    prop:value=query
+                    on:input=move |ev| {
+                        query.set(event_target_value(&ev));
+                        error.set(None);
+                        open.set(true);
+                    }
+                    on:focus=move |_| open.set(true)
+                    on:blur=move |_| open.set(false)
+                    on:keydown=move |ev| {
+                        if ev.key() == "Escape" {
+                            open.set(false);
+                        }
+                    }
+                />
+
+                <div class="pr-1.5 sm:pr-2">
+                    <button
+                        type="submit"
+                        class="flex h-9 w-9 sm:h-10 sm:w-10 items-center justify-center rounded-full bg-white/5 text-neutral-400 transition-colors hover:bg-emerald-500 hover:text-white active:scale-95 disabled:opacity-40"
+                        aria-label="Search"
+                        disabled=move || resolve.pending().get()
+                    >
+                        <svg xmlns="http://www.w3.org/2000/svg" width="18" height="18" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                            <path d="M5 12h14"></path>
+                            <path d="m12 5 7 7-7 7"></path>
+                        </svg>
+                    </button>
+                </div>
+            </div>
+
+            { move || error.get().map(|e| view! {
+                <p class="mt-2 text-center text-xs text-rose-400">{ e }</p>
+            })}
+
+            <Transition>
+                {move || {
+                    let items = suggestions.get().and_then(Result::ok).unwrap_or_default();
+                    (open.get() && !items.is_empty()).then(|| view! {
+                        // Keep the input focused while a suggestion is clicked
+                        <ul
+                            class="absolute left-0 right-0 top-full z-30 mt-2 overflow-hidden rounded-2xl border border-white/10 bg-[#0F0F0F] text-left shadow-2xl"
+                            on:mousedown=|ev| ev.prevent_default()
+                        >
+                            { items.into_iter().map(|item| view! { <SuggestionItem item /> }).collect::<Vec<_>>() }
+                        </ul>
+                    })
+                }}
+            </Transition>
+        </form>
+    }
+    .into_any()
+}
+
+#[component]
+fn SuggestionItem(item: SearchSuggestion) -> impl IntoView {
+    let badge_class = match item.kind {
+        SearchKind::Account => "text-emerald-400 bg-emerald-500/10 border-emerald-500/20",
+        SearchKind::Block => "text-cyan-400 bg-cyan-500/10 border-cyan-500/20",
+        SearchKind::Extrinsic => "text-amber-400 bg-amber-500/10 border-amber-500/20",
+    };
+
+    view! {
+        <li class="border-b border-white/5 last:border-0">
+            <a href=item.href class="flex items-center gap-3 px-4 py-3 hover:bg-white/[0.03] transition-colors">
+                <span class=format!("shrink-0 rounded-md border px-2 py-0.5 text-[10px] font-medium uppercase tracking-wider {}", badge_class)>
+                    { item.kind.label() }
+                </span>
+                <div class="min-w-0 flex flex-col">
+                    <span class="text-sm text-white font-mono truncate">{ item.label }</span>
+                    { item.detail.map(|detail| view! {
+                        <span class="text-[10px] text-neutral-500 font-mono truncate">{ detail }</span>
+                    })}
+                </div>
+            </a>
+        </li>
+    }
+}
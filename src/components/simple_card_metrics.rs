@@ -4,7 +4,7 @@ use leptos::prelude::*;
 #[component]
 pub fn SimpleCardMetrics(
     title: &'static str,
-    #[prop(optional)] description: Option<&'static str>,
+    #[prop(optional, into)] description: Option<String>,
     children: Children,
 ) -> impl IntoView {
     view! {
@@ -19,7 +19,7 @@ pub fn SimpleCardMetrics(
                     </div>
                 </div>
 
-                {move || description.map(|desc| view! {
+                {move || description.clone().map(|desc| view! {
                       <div class="pt-4 mt-auto border-t border-dashed border-white/10">
                         <p class="text-xs text-neutral-500 leading-relaxed">
                             {desc}
@@ -0,0 +1,92 @@
+use leptos::prelude::*;
+
+use crate::{
+    SupplyBreakdown,
+    components::{Card, fetchable_balance::FetchableData},
+    get_supply_breakdown,
+    utils::{blocks_to_str, format_balance},
+};
+
+/// Share of the total issuance, in percent.
+pub fn percent_of_issuance(amount: u128, data: &SupplyBreakdown) -> f64 {
+    if data.total_issuance == 0 {
+        0.0
+    } else {
+        amount as f64 / data.total_issuance as f64 * 100.0
+    }
+}
+
+/// Total issuance split into the parts that do and don't circulate.
+pub fn supply_segments(data: &SupplyBreakdown) -> [(&'static str, &'static str, u128); 4] {
+    [
+        ("Circulating", "bg-emerald-500/70", data.circulating()),
+        (
+            "Locked in Vesting",
+            "bg-amber-500/60",
+            data.locked_in_vesting(),
+        ),
+        ("Undistributed", "bg-cyan-500/40", data.undistributed()),
+        ("Outside Envelopes", "bg-white/10", data.outside_envelopes()),
+    ]
+}
+
+/// Stacked bar of the total issuance with a legend of each component.
+#[component]
+pub fn SupplyBar(data: SupplyBreakdown) -> impl IntoView {
+    let segments = supply_segments(&data);
+
+    view! {
+        <div class="flex flex-col gap-4">
+            <div class="flex h-4 w-full overflow-hidden rounded-full bg-white/5">
+                { segments.iter().map(|(label, color, amount)| view! {
+                    <div
+                        class=format!("h-full {color}")
+                        style=format!("width: {:.4}%;", percent_of_issuance(*amount, &data))
+                        title=format!("{label}: {}", format_balance(*amount, true))
+                    ></div>
+                }).collect::<Vec<_>>() }
+            </div>
+
+            <div class="grid gap-3 grid-cols-1 sm:grid-cols-2 lg:grid-cols-4">
+                { segments.iter().map(|(label, color, amount)| view! {
+                    <div class="flex flex-col gap-1">
+                        <span class="flex items-center gap-1.5 text-[10px] uppercase tracking-wider text-neutral-500">
+                            <span class=format!("h-2 w-2 rounded-sm {color}")></span>
+                            { *label }
+                        </span>
+                        <span class="font-mono text-sm text-white">{ format_balance(*amount, true) }</span>
+                        <span class="font-mono text-[10px] text-neutral-500">
+                            { format!("{:.2}%", percent_of_issuance(*amount, &data)) }
+                        </span>
+                    </div>
+                }).collect::<Vec<_>>() }
+            </div>
+        </div>
+    }
+}
+
+#[component]
+pub fn SupplyBreakdownCard() -> impl IntoView {
+    let breakdown = Resource::new(|| (), |_| get_supply_breakdown());
+
+    view! {
+        <Card header=view! {
+            <div class="flex items-center justify-between">
+                <h3 class="text-sm font-medium text-neutral-400 uppercase tracking-wider">"Supply Breakdown"</h3>
+                <a href="/supply" class="text-xs text-neutral-500 hover:text-emerald-400 transition-colors">
+                    "Methodology →"
+                </a>
+            </div>
+        }.into_any()>
+            <FetchableData data=breakdown render=move |data: SupplyBreakdown| {
+                let block = data.block;
+                view! {
+                    <SupplyBar data />
+                    <p class="mt-4 text-[10px] text-neutral-600 font-mono">
+                        "At block #" { blocks_to_str(block) }
+                    </p>
+                }.into_any()
+            } />
+        </Card>
+    }
+}
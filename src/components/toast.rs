@@ -27,6 +27,10 @@ pub fn ToastProvider(children: Children) -> impl IntoView {
     let counter = RwSignal::new(0u32);
 
     let add_toast = Arc::new(move |msg: String| {
+        // Several views can fail on the same request
+        if toasts.with_untracked(|ts| ts.iter().any(|t| t.message == msg)) {
+            return;
+        }
         let id = counter.get() + 1;
         counter.set(id);
         toasts.update(|ts| {
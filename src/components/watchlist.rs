@@ -0,0 +1,194 @@
+//! Accounts followed by the user, with private labels. The list lives in the
+//! browser's localStorage and is never stored on the server.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{components::toast::use_toast, ss58};
+
+const STORAGE_KEY: &str = "allfeat-explorer.watchlist";
+
+/// Accounts fetched by a single batched call.
+pub const MAX_WATCHED: usize = 100;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WatchedAccount {
+    pub address: String,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+pub struct WatchlistContext {
+    pub accounts: RwSignal<Vec<WatchedAccount>>,
+    /// Set once the list has been read from localStorage, which only happens
+    /// in the browser
+    pub loaded: RwSignal<bool>,
+}
+
+pub fn use_watchlist() -> WatchlistContext {
+    use_context::<WatchlistContext>().expect("WatchlistProvider missing!")
+}
+
+impl WatchlistContext {
+    pub fn is_watched(&self, address: &str) -> bool {
+        self.accounts
+            .with(|accounts| accounts.iter().any(|a| a.address == address))
+    }
+
+    /// Adds an account given as an SS58 address of any network. Returns
+    /// false if it was already watched.
+    pub fn add(&self, address: &str) -> Result<bool, String> {
+        let address = ss58::to_allfeat(address).map_err(|e| e.to_string())?;
+        if self.is_watched(&address) {
+            return Ok(false);
+        }
+
+        if self.accounts.with(Vec::len) >= MAX_WATCHED {
+            return Err(format!(
+                "The watchlist is limited to {MAX_WATCHED} accounts"
+            ));
+        }
+
+        self.accounts.update(|accounts| {
+            accounts.push(WatchedAccount {
+                address,
+                label: None,
+            })
+        });
+        Ok(true)
+    }
+
+    /// Adds the account, or removes it if already watched. Returns whether
+    /// the account is now watched.
+    pub fn toggle(&self, address: &str) -> Result<bool, String> {
+        if self.is_watched(address) {
+            self.remove(address);
+            Ok(false)
+        } else {
+            self.add(address)
+        }
+    }
+
+    pub fn remove(&self, address: &str) {
+        self.accounts
+            .update(|accounts| accounts.retain(|a| a.address != address));
+    }
+
+    /// Sets the private label of a watched account, an empty label clears it.
+    pub fn set_label(&self, address: &str, label: &str) {
+        let label = label.trim();
+        self.accounts.update(|accounts| {
+            if let Some(account) = accounts.iter_mut().find(|a| a.address == address) {
+                account.label = (!label.is_empty()).then(|| label.to_string());
+            }
+        });
+    }
+
+    pub fn export(&self) -> String {
+        self.accounts
+            .with(serde_json::to_string_pretty)
+            .unwrap_or_default()
+    }
+
+    /// Merges an exported list into the watchlist. Labels of accounts already
+    /// watched are replaced. Returns the number of imported accounts.
+    pub fn import(&self, json: &str) -> Result<usize, String> {
+        let imported: Vec<WatchedAccount> =
+            serde_json::from_str(json).map_err(|e| format!("Invalid watchlist file: {e}"))?;
+
+        let mut accounts = self.accounts.get_untracked();
+        let mut count = 0;
+        for entry in imported {
+            let address =
+                ss58::to_allfeat(&entry.address).map_err(|e| format!("{}: {e}", entry.address))?;
+
+            match accounts.iter_mut().find(|a| a.address == address) {
+                Some(account) => account.label = entry.label,
+                None => accounts.push(WatchedAccount {
+                    address,
+                    label: entry.label,
+                }),
+            }
+            count += 1;
+        }
+
+        if accounts.len() > MAX_WATCHED {
+            return Err(format!(
+                "The watchlist is limited to {MAX_WATCHED} accounts"
+            ));
+        }
+
+        self.accounts.set(accounts);
+        Ok(count)
+    }
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    window().local_storage().ok().flatten()
+}
+
+#[component]
+pub fn WatchlistProvider(children: Children) -> impl IntoView {
+    let ctx = WatchlistContext {
+        accounts: RwSignal::new(vec![]),
+        loaded: RwSignal::new(false),
+    };
+
+    // Effects only run in the browser, after hydration
+    Effect::new(move |_| {
+        let saved = local_storage()
+            .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+            .and_then(|json| serde_json::from_str::<Vec<WatchedAccount>>(&json).ok());
+
+        if let Some(accounts) = saved {
+            ctx.accounts.set(accounts);
+        }
+        ctx.loaded.set(true);
+    });
+
+    Effect::new(move |_| {
+        if !ctx.loaded.get() {
+            return;
+        }
+        let json = ctx.accounts.with(serde_json::to_string).unwrap_or_default();
+        if let Some(storage) = local_storage() {
+            let _ = storage.set_item(STORAGE_KEY, &json);
+        }
+    });
+
+    provide_context(ctx);
+    view! { {children()} }
+}
+
+/// Star button adding or removing an account from the watchlist.
+#[component]
+pub fn WatchToggle(#[prop(into)] address: Signal<String>) -> impl IntoView {
+    let toast = use_toast();
+    let watchlist = use_watchlist();
+
+    let watched = move || watchlist.is_watched(&address.get());
+
+    let on_click = move |_| match watchlist.toggle(&address.get()) {
+        Ok(true) => (toast.add_toast)("Added to watchlist".to_string()),
+        Ok(false) => (toast.add_toast)("Removed from watchlist".to_string()),
+        Err(e) => (toast.add_toast)(e),
+    };
+
+    view! {
+        <button
+            class=move || format!(
+                "flex h-7 w-7 sm:h-8 sm:w-8 items-center justify-center rounded-full bg-white/5 transition-colors shrink-0 {}",
+                if watched() { "text-amber-400 hover:text-amber-300" } else { "text-neutral-400 hover:text-white" }
+            )
+            on:click=on_click
+            title=move || if watched() { "Remove from watchlist" } else { "Add to watchlist" }
+            aria-pressed=move || watched().to_string()
+        >
+            <svg xmlns="http://www.w3.org/2000/svg" width="14" height="14" viewBox="0 0 24 24" fill=move || if watched() { "currentColor" } else { "none" } stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                <polygon points="12 2 15.09 8.26 22 9.27 17 14.14 18.18 21.02 12 17.77 5.82 21.02 7 14.14 2 9.27 8.91 8.26 12 2"></polygon>
+            </svg>
+        </button>
+    }
+    .into_any()
+}
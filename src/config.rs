@@ -0,0 +1,657 @@
+//! Explorer configuration.
+//!
+//! Settings come, from lowest to highest precedence, from their defaults, the
+//! config file, environment variables and command line flags, and are
+//! validated once at startup. [`ChainSettings`] is also needed by the browser
+//! to format amounts and durations, so like the address book it is embedded
+//! in every page by [`ChainSettingsScript`] and picked up before hydration.
+
+use std::sync::{LazyLock, RwLock};
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::SECS_PER_BLOCK;
+
+/// Id of the script element holding the chain settings.
+const SCRIPT_ID: &str = "chain-settings";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainSettings {
+    /// Expected time between two blocks, used to turn blocks into durations
+    pub block_time_secs: u64,
+    pub token_symbol: String,
+    pub token_decimals: u32,
+}
+
+impl Default for ChainSettings {
+    fn default() -> Self {
+        Self {
+            block_time_secs: SECS_PER_BLOCK,
+            token_symbol: "AFT".to_string(),
+            token_decimals: 12,
+        }
+    }
+}
+
+static CHAIN_SETTINGS: LazyLock<RwLock<ChainSettings>> = LazyLock::new(Default::default);
+
+pub fn chain_settings() -> ChainSettings {
+    CHAIN_SETTINGS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+pub fn set_chain_settings(settings: ChainSettings) {
+    *CHAIN_SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = settings;
+}
+
+/// Embeds the chain settings in the page for [`load_from_document`].
+#[component]
+pub fn ChainSettingsScript() -> impl IntoView {
+    // Keep the JSON from closing the script element
+    let json = serde_json::to_string(&chain_settings())
+        .unwrap_or_default()
+        .replace("</", "<\\/");
+
+    view! { <script id=SCRIPT_ID type="application/json" inner_html=json></script> }
+}
+
+/// Loads the settings embedded by [`ChainSettingsScript`]. Called before
+/// hydration.
+#[cfg(feature = "hydrate")]
+pub fn load_from_document() {
+    let settings = document()
+        .get_element_by_id(SCRIPT_ID)
+        .and_then(|el| el.text_content())
+        .and_then(|json| serde_json::from_str::<ChainSettings>(&json).ok());
+
+    if let Some(settings) = settings {
+        set_chain_settings(settings);
+    }
+}
+
+#[cfg(feature = "ssr")]
+pub use server::*;
+
+#[cfg(feature = "ssr")]
+mod server {
+    use std::net::SocketAddr;
+    use std::path::{Path, PathBuf};
+
+    use serde::{Deserialize, Serialize};
+
+    use super::ChainSettings;
+    #[cfg(feature = "faucet")]
+    use crate::faucet::FaucetConfig;
+    use crate::indexer::tokenomics::AlertConfig;
+    use crate::rate_limit::RateLimitConfig;
+    use crate::substrate::connection::RpcConfig;
+    use crate::substrate::dev_seed::SeedDevOptions;
+    use crate::supply::SupplyMethodology;
+
+    /// Read when neither `--config` nor `EXPLORER_CONFIG` is given, if it
+    /// exists.
+    const DEFAULT_PATH: &str = "explorer.toml";
+
+    /// Setting kept out of `Debug` output and of the printed configuration.
+    #[derive(Clone, Default, PartialEq, Deserialize)]
+    #[serde(transparent)]
+    pub struct Secret(pub String);
+
+    impl std::fmt::Debug for Secret {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            if self.0.is_empty() {
+                f.write_str("\"\"")
+            } else {
+                f.write_str("<redacted>")
+            }
+        }
+    }
+
+    /// Environment variables and the setting they override, the later ones
+    /// winning.
+    const ENV_VARS: &[(&str, &str)] = &[
+        ("RPC_URL", "rpc.endpoints"),
+        ("RPC_URLS", "rpc.endpoints"),
+        ("RPC_RECORD", "rpc.record"),
+        ("SITE_ADDR", "server.site_addr"),
+        ("BLOCK_TIME_SECS", "chain.block_time_secs"),
+        ("TOKEN_SYMBOL", "chain.token_symbol"),
+        ("TOKEN_DECIMALS", "chain.token_decimals"),
+        ("ALLOCATIONS_CACHE_TTL", "cache.allocations_ttl_secs"),
+        ("HOLDERS_REFRESH_INTERVAL", "cache.holders_refresh_blocks"),
+        ("CIRCULATING_SUPPLY_METHODOLOGY", "supply.methodology"),
+        ("ADDRESS_BOOK_PATH", "address_book.path"),
+        ("INDEXER_BACKFILL_BLOCKS", "indexer.backfill_blocks"),
+        ("ALERT_WEBHOOKS", "tokenomics.webhooks"),
+        ("PAYOUT_GRACE_BLOCKS", "tokenomics.payout_grace_blocks"),
+        ("TOKENOMICS_CHECK_INTERVAL", "tokenomics.check_interval"),
+        ("RATE_LIMIT_PAGE", "rate_limit.page"),
+        ("RATE_LIMIT_PAGE_GLOBAL", "rate_limit.page_global"),
+        ("RATE_LIMIT_STANDARD", "rate_limit.standard"),
+        ("RATE_LIMIT_STANDARD_GLOBAL", "rate_limit.standard_global"),
+        ("RATE_LIMIT_EXPENSIVE", "rate_limit.expensive"),
+        ("RATE_LIMIT_EXPENSIVE_GLOBAL", "rate_limit.expensive_global"),
+        ("RATE_LIMIT_STREAM", "rate_limit.stream"),
+        ("RATE_LIMIT_STREAM_GLOBAL", "rate_limit.stream_global"),
+        ("TRUSTED_PROXY_HOPS", "rate_limit.trusted_proxy_hops"),
+        ("MAX_CONCURRENT_SCANS", "rate_limit.max_concurrent_scans"),
+        #[cfg(feature = "faucet")]
+        ("FAUCET_SEED", "faucet.seed"),
+        #[cfg(feature = "faucet")]
+        ("FAUCET_AMOUNT", "faucet.amount"),
+        #[cfg(feature = "faucet")]
+        ("FAUCET_BALANCE_FLOOR", "faucet.balance_floor"),
+        #[cfg(feature = "faucet")]
+        ("FAUCET_CAPTCHA_SECRET", "faucet.captcha.secret"),
+    ];
+
+    /// Settings given as comma-separated lists in the environment and flags.
+    const LIST_KEYS: &[&str] = &["rpc.endpoints", "tokenomics.webhooks"];
+
+    pub const USAGE: &str = "\
+Usage: token-app [OPTIONS]
+       token-app seed-dev [SEED OPTIONS] [OPTIONS]
+
+Commands:
+  seed-dev               Fill a development chain with envelopes, allocations, transfers and payouts
+
+Options:
+  --config <PATH>        Config file (default: EXPLORER_CONFIG, else explorer.toml if present)
+  --print-config         Print the effective configuration and exit
+  --rpc-url <URL>        RPC endpoint, may be repeated (same as --rpc.endpoints)
+  --<section>.<key> <V>  Any setting of the config file, e.g. --cache.allocations-ttl-secs 60
+  -h, --help             Print this help
+
+Seed options:
+  --allocations <N>      Allocations to add (default: 300)
+  --transfers <N>        Transfers between the dev accounts (default: 100)
+  --epochs <N>           Payouts to bring forward (default: 3)
+  --sudo <URI>           Secret URI of the sudo key (default: //Alice)";
+
+    /// Invalid configuration, with every problem found.
+    #[derive(Debug)]
+    pub struct ConfigError(pub Vec<String>);
+
+    impl ConfigError {
+        fn new(message: impl Into<String>) -> Self {
+            Self(vec![message.into()])
+        }
+    }
+
+    impl std::fmt::Display for ConfigError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("Invalid configuration:")?;
+            for problem in &self.0 {
+                write!(f, "\n  - {problem}")?;
+            }
+            Ok(())
+        }
+    }
+
+    impl std::error::Error for ConfigError {}
+
+    /// Run instead of the server.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Command {
+        SeedDev(SeedDevOptions),
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct Cli {
+        pub config: Option<PathBuf>,
+        pub print_config: bool,
+        pub help: bool,
+        pub command: Option<Command>,
+        /// Settings given as flags, by key
+        overrides: Vec<(String, String)>,
+    }
+
+    impl Cli {
+        /// Parses the arguments, without the program name.
+        pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, ConfigError> {
+            let mut cli = Self::default();
+            let mut endpoints = vec![];
+            let mut args = args.into_iter();
+
+            while let Some(arg) = args.next() {
+                let (flag, inline) = match arg.split_once('=') {
+                    Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                    None => (arg, None),
+                };
+                let mut value = || {
+                    inline
+                        .clone()
+                        .or_else(|| args.next())
+                        .ok_or_else(|| ConfigError::new(format!("{flag} expects a value")))
+                };
+
+                match flag.as_str() {
+                    "-h" | "--help" => cli.help = true,
+                    "--print-config" => cli.print_config = true,
+                    "--config" => cli.config = Some(PathBuf::from(value()?)),
+                    "--rpc-url" => endpoints.push(value()?),
+                    "seed-dev" if cli.command.is_none() => {
+                        cli.command = Some(Command::SeedDev(SeedDevOptions::default()));
+                    }
+                    "--allocations" | "--transfers" | "--epochs" | "--sudo" => {
+                        let Some(Command::SeedDev(options)) = &mut cli.command else {
+                            return Err(ConfigError::new(format!(
+                                "{flag} is an option of seed-dev, see --help"
+                            )));
+                        };
+                        let value = value()?;
+                        match flag.as_str() {
+                            "--allocations" => options.allocations = number(&flag, &value)?,
+                            "--transfers" => options.transfers = number(&flag, &value)?,
+                            "--epochs" => options.epochs = number(&flag, &value)?,
+                            _ => options.sudo = value,
+                        }
+                    }
+                    _ => match flag.strip_prefix("--").filter(|key| key.contains('.')) {
+                        Some(key) => {
+                            let value = value()?;
+                            cli.overrides.push((key.replace('-', "_"), value));
+                        }
+                        None => {
+                            return Err(ConfigError::new(format!(
+                                "Unknown argument {flag}, see --help"
+                            )));
+                        }
+                    },
+                }
+            }
+
+            if !endpoints.is_empty() {
+                cli.overrides
+                    .push(("rpc.endpoints".to_string(), endpoints.join(",")));
+            }
+
+            Ok(cli)
+        }
+    }
+
+    fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, ConfigError> {
+        value
+            .parse()
+            .map_err(|_| ConfigError::new(format!("{flag} expects a number, got {value}")))
+    }
+
+    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct ServerConfig {
+        /// Overrides the Leptos `site-addr`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub site_addr: Option<SocketAddr>,
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct CacheConfig {
+        pub allocations_ttl_secs: u64,
+        /// Blocks between two scans of every account balance
+        pub holders_refresh_blocks: u32,
+    }
+
+    impl Default for CacheConfig {
+        fn default() -> Self {
+            Self {
+                allocations_ttl_secs: 300,
+                holders_refresh_blocks: crate::indexer::holders::DEFAULT_REFRESH_INTERVAL,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct SupplyConfig {
+        /// Default of `get_circulating_supply`
+        pub methodology: SupplyMethodology,
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct AddressBookConfig {
+        /// TOML, or JSON when the file ends in `.json`
+        pub path: PathBuf,
+    }
+
+    impl Default for AddressBookConfig {
+        fn default() -> Self {
+            Self {
+                path: PathBuf::from("address_book.toml"),
+            }
+        }
+    }
+
+    /// The indexes are rebuilt in memory from the chain on startup (see
+    /// `backfill_blocks`), so there is no database path to set.
+    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct IndexerConfig {
+        /// Finalized blocks indexed backwards on startup
+        pub backfill_blocks: u32,
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct Features {
+        /// Follow finalized blocks. Without it the live views, the sudo log,
+        /// fees and payouts stay empty
+        pub indexer: bool,
+        /// Scan every account for the top holders and the frozen supply
+        pub holders: bool,
+        /// Check token allocation invariants and send alerts
+        pub tokenomics_alerts: bool,
+    }
+
+    impl Default for Features {
+        fn default() -> Self {
+            Self {
+                indexer: true,
+                holders: true,
+                tokenomics_alerts: true,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct Config {
+        pub server: ServerConfig,
+        pub rpc: RpcConfig,
+        pub chain: ChainSettings,
+        pub cache: CacheConfig,
+        pub supply: SupplyConfig,
+        pub address_book: AddressBookConfig,
+        pub indexer: IndexerConfig,
+        pub tokenomics: AlertConfig,
+        pub rate_limit: RateLimitConfig,
+        pub features: Features,
+        #[cfg(feature = "faucet")]
+        pub faucet: FaucetConfig,
+    }
+
+    impl Config {
+        /// Reads and validates the configuration.
+        pub fn load(cli: &Cli) -> Result<Self, ConfigError> {
+            let env = std::env::vars().collect::<Vec<_>>();
+            Self::from_layers(cli, &env)
+        }
+
+        fn from_layers(cli: &Cli, env: &[(String, String)]) -> Result<Self, ConfigError> {
+            let mut table = toml::Table::new();
+
+            let explicit = cli.config.clone().or_else(|| {
+                env.iter()
+                    .find(|(name, _)| name == "EXPLORER_CONFIG")
+                    .map(|(_, path)| PathBuf::from(path))
+            });
+            match explicit {
+                Some(path) => merge(&mut table, read_file(&path)?),
+                None if Path::new(DEFAULT_PATH).exists() => {
+                    merge(&mut table, read_file(Path::new(DEFAULT_PATH))?)
+                }
+                None => {}
+            }
+
+            for (var, key) in ENV_VARS {
+                if let Some((_, value)) = env.iter().find(|(name, _)| name == var) {
+                    set(&mut table, key, value);
+                }
+            }
+
+            for (key, value) in &cli.overrides {
+                set(&mut table, key, value);
+            }
+
+            // Rejected by name, an unknown field wouldn't tell why
+            let indexer = table.get("indexer").and_then(toml::Value::as_table);
+            if indexer.is_some_and(|section| section.contains_key("db_path")) {
+                return Err(ConfigError::new(
+                    "indexer.db_path: the indexes are kept in memory, there is no database to configure",
+                ));
+            }
+
+            // Going through the text format gets errors naming the setting
+            let merged = toml::to_string(&table).map_err(|e| ConfigError::new(e.to_string()))?;
+            let config: Self =
+                toml::from_str(&merged).map_err(|e| ConfigError::new(e.to_string()))?;
+
+            config.validate()?;
+            Ok(config)
+        }
+
+        fn validate(&self) -> Result<(), ConfigError> {
+            let mut problems = vec![];
+
+            if self.rpc.endpoints.is_empty() {
+                problems.push("rpc.endpoints: at least one endpoint is required".to_string());
+            }
+            for endpoint in &self.rpc.endpoints {
+                let mock = cfg!(feature = "mock-chain") && endpoint.starts_with("mock://");
+                if !endpoint.starts_with("ws://") && !endpoint.starts_with("wss://") && !mock {
+                    problems.push(format!(
+                        "rpc.endpoints: {endpoint:?} is not a ws:// or wss:// URL"
+                    ));
+                }
+            }
+            if self.chain.block_time_secs == 0 {
+                problems.push("chain.block_time_secs: must be at least 1".to_string());
+            }
+            if self.chain.token_symbol.trim().is_empty() {
+                problems.push("chain.token_symbol: must not be empty".to_string());
+            }
+            // u128 amounts have at most 39 digits
+            if self.chain.token_decimals > 38 {
+                problems.push("chain.token_decimals: must be at most 38".to_string());
+            }
+            if self.cache.holders_refresh_blocks == 0 {
+                problems.push("cache.holders_refresh_blocks: must be at least 1".to_string());
+            }
+            if self.tokenomics.check_interval == 0 {
+                problems.push("tokenomics.check_interval: must be at least 1".to_string());
+            }
+            // Webhook URLs usually embed a token, so they are not echoed
+            for (i, webhook) in self.tokenomics.webhooks.iter().enumerate() {
+                if !webhook.0.starts_with("http://") && !webhook.0.starts_with("https://") {
+                    problems.push(format!(
+                        "tokenomics.webhooks[{i}]: not an http:// or https:// URL"
+                    ));
+                }
+            }
+            if self.rate_limit.max_concurrent_scans == 0 {
+                problems.push("rate_limit.max_concurrent_scans: must be at least 1".to_string());
+            }
+            #[cfg(feature = "faucet")]
+            self.validate_faucet(&mut problems);
+
+            if problems.is_empty() {
+                Ok(())
+            } else {
+                Err(ConfigError(problems))
+            }
+        }
+
+        #[cfg(feature = "faucet")]
+        fn validate_faucet(&self, problems: &mut Vec<String>) {
+            match self.faucet.keypair() {
+                Ok(None) => return,
+                Ok(Some(_)) => {}
+                Err(e) => problems.push(format!("faucet.seed: {e}")),
+            }
+            if self.faucet.amount == 0 {
+                problems.push("faucet.amount: must be at least 1".to_string());
+            }
+            if let Some(captcha) = &self.faucet.captcha {
+                if captcha.site_key.trim().is_empty() {
+                    problems.push("faucet.captcha.site_key: must not be empty".to_string());
+                }
+                if captcha.secret.0.trim().is_empty() {
+                    problems.push("faucet.captcha.secret: must not be empty".to_string());
+                }
+                let url = &captcha.verify_url;
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    problems.push(format!(
+                        "faucet.captcha.verify_url: {url:?} is not an http:// or https:// URL"
+                    ));
+                }
+            }
+        }
+
+        /// The effective configuration, as a config file.
+        pub fn to_toml(&self) -> String {
+            toml::to_string_pretty(self).unwrap_or_default()
+        }
+    }
+
+    fn read_file(path: &Path) -> Result<toml::Table, ConfigError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::new(format!("Failed to read {}: {e}", path.display())))?;
+
+        toml::from_str(&content).map_err(|e| ConfigError::new(format!("{}: {e}", path.display())))
+    }
+
+    /// Merges `layer` into `table`, sections key by key.
+    fn merge(table: &mut toml::Table, layer: toml::Table) {
+        for (key, value) in layer {
+            match (table.get_mut(&key), value) {
+                (Some(toml::Value::Table(section)), toml::Value::Table(layer)) => {
+                    merge(section, layer)
+                }
+                (_, value) => {
+                    table.insert(key, value);
+                }
+            }
+        }
+    }
+
+    /// Sets the dotted `key` to a value given as text: a list when the key
+    /// is one, else a TOML literal, else a plain string.
+    fn set(table: &mut toml::Table, key: &str, raw: &str) {
+        let value = if LIST_KEYS.contains(&key) {
+            toml::Value::Array(
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| toml::Value::String(item.to_string()))
+                    .collect(),
+            )
+        } else {
+            toml::from_str::<toml::Table>(&format!("value = {raw}"))
+                .ok()
+                .and_then(|mut t| t.remove("value"))
+                .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+        };
+
+        let mut layer = toml::Table::new();
+        let mut path = key.rsplit('.');
+        if let Some(last) = path.next() {
+            layer.insert(last.to_string(), value);
+        }
+        for section in path {
+            let mut parent = toml::Table::new();
+            parent.insert(section.to_string(), toml::Value::Table(layer));
+            layer = parent;
+        }
+
+        merge(table, layer);
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn env(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+            vars.iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        }
+
+        fn args(args: &[&str]) -> Cli {
+            Cli::parse(args.iter().map(|a| a.to_string())).unwrap()
+        }
+
+        #[test]
+        fn flags_override_env_over_defaults() {
+            let env = env(&[
+                ("RPC_URL", "ws://127.0.0.1:9944"),
+                ("TOKEN_SYMBOL", "tAFT"),
+                ("RATE_LIMIT_STREAM", "off"),
+            ]);
+            let cli = args(&[
+                "--chain.token-symbol=dAFT",
+                "--cache.allocations-ttl-secs",
+                "60",
+            ]);
+
+            let config = Config::from_layers(&cli, &env).unwrap();
+            assert_eq!(config.rpc.endpoints, ["ws://127.0.0.1:9944"]);
+            assert_eq!(config.chain.token_symbol, "dAFT");
+            assert_eq!(config.chain.token_decimals, 12);
+            assert_eq!(config.cache.allocations_ttl_secs, 60);
+            assert_eq!(config.rate_limit.stream.0, None);
+        }
+
+        #[test]
+        fn repeated_rpc_urls_make_a_list() {
+            let cli = args(&["--rpc-url", "wss://a.example", "--rpc-url=wss://b.example"]);
+            let config = Config::from_layers(&cli, &[]).unwrap();
+            assert_eq!(config.rpc.endpoints, ["wss://a.example", "wss://b.example"]);
+        }
+
+        #[test]
+        fn reports_every_problem() {
+            let cli = args(&["--chain.block-time-secs", "0", "--rpc-url", "http://node"]);
+            let error = Config::from_layers(&cli, &[]).unwrap_err();
+            assert_eq!(error.0.len(), 2);
+        }
+
+        #[test]
+        fn rejects_unknown_settings() {
+            let cli = args(&["--chain.colour", "blue"]);
+            assert!(Config::from_layers(&cli, &[]).is_err());
+            assert!(Cli::parse(["--verbose".to_string()]).is_err());
+        }
+
+        #[test]
+        fn explains_there_is_no_indexer_database() {
+            let cli = args(&["--indexer.db-path", "/var/lib/explorer"]);
+            let error = Config::from_layers(&cli, &[]).unwrap_err();
+            assert!(error.0[0].contains("kept in memory"));
+        }
+
+        #[test]
+        fn parses_the_seed_command() {
+            let cli = args(&["seed-dev", "--allocations", "50", "--sudo=//Bob"]);
+            let Some(Command::SeedDev(options)) = cli.command else {
+                panic!("no command parsed");
+            };
+            assert_eq!(options.allocations, 50);
+            assert_eq!(options.sudo, "//Bob");
+            assert_eq!(options.epochs, SeedDevOptions::default().epochs);
+
+            assert!(Cli::parse(["--epochs".to_string(), "2".to_string()]).is_err());
+            assert!(Cli::parse(["seed-dev".to_string(), "--epochs=many".to_string()]).is_err());
+        }
+
+        #[test]
+        fn printed_config_hides_webhooks() {
+            let env = env(&[("ALERT_WEBHOOKS", "https://hooks.example/T0KEN")]);
+            let config = Config::from_layers(&Cli::default(), &env).unwrap();
+            assert_eq!(config.tokenomics.webhooks.len(), 1);
+            assert!(!config.to_toml().contains("T0KEN"));
+            assert!(!format!("{config:?}").contains("T0KEN"));
+        }
+
+        #[test]
+        fn printed_config_loads_back() {
+            let config = Config::default();
+            let printed: Config = toml::from_str(&config.to_toml()).unwrap();
+            assert_eq!(printed.to_toml(), config.to_toml());
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! Error returned by the server functions.
+//!
+//! It is serialized as is to the browser, so pages can tell a mistyped
+//! address from an unreachable node and offer a retry only when it can help.
+//! The streaming functions keep `ServerFnError` and report chain failures
+//! inside their events instead.
+
+use leptos::server_fn::codec::JsonEncoding;
+use leptos::server_fn::error::{FromServerFnError, ServerFnErrorErr};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "detail", rename_all = "snake_case")]
+pub enum AppError {
+    /// Not an SS58 address, a hex public key or a known name
+    InvalidAddress(String),
+    /// Any other malformed argument
+    InvalidInput(String),
+    NotFound(String),
+    /// The node could not be reached or refused the request
+    RpcUnavailable(String),
+    /// Chain data doesn't match the metadata the explorer was built with
+    Decode(String),
+    Timeout,
+    RateLimited {
+        retry_after_secs: u64,
+    },
+    /// The explorer can't fund the request, e.g. the faucet ran dry
+    OutOfFunds(String),
+    /// Anything else, including failures to reach the explorer itself
+    Internal(String),
+}
+
+impl AppError {
+    /// Short heading for the error views.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::InvalidAddress(_) => "Invalid address",
+            Self::InvalidInput(_) => "Invalid input",
+            Self::NotFound(_) => "Not found",
+            Self::RpcUnavailable(_) => "Node unavailable",
+            Self::Decode(_) => "Unexpected chain data",
+            Self::Timeout => "Timed out",
+            Self::RateLimited { .. } => "Too many requests",
+            Self::OutOfFunds(_) => "Out of funds",
+            Self::Internal(_) => "Something went wrong",
+        }
+    }
+
+    /// Whether the same request may succeed later.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::RpcUnavailable(_) | Self::Timeout | Self::RateLimited { .. } | Self::Internal(_)
+        )
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidAddress(input) => write!(f, "Invalid address format: {input}"),
+            Self::InvalidInput(detail) | Self::NotFound(detail) | Self::OutOfFunds(detail) => {
+                f.write_str(detail)
+            }
+            Self::RpcUnavailable(detail) => write!(f, "The node is unavailable: {detail}"),
+            Self::Decode(detail) => write!(f, "Failed to decode chain data: {detail}"),
+            Self::Timeout => f.write_str("The node took too long to answer"),
+            Self::RateLimited { retry_after_secs } => {
+                write!(f, "Too many requests, retry in {retry_after_secs}s")
+            }
+            Self::Internal(detail) => f.write_str(detail),
+        }
+    }
+}
+
+impl FromServerFnError for AppError {
+    type Encoder = JsonEncoding;
+
+    fn from_server_fn_error(value: ServerFnErrorErr) -> Self {
+        Self::Internal(value.to_string())
+    }
+}
+
+/// Lets `?` classify any error. Like `anyhow::Error`, `AppError` doesn't
+/// implement `std::error::Error` itself so this doesn't overlap with
+/// `From<T> for T`.
+impl<E> From<E> for AppError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn from(e: E) -> Self {
+        classify(&e)
+    }
+}
+
+#[cfg(feature = "ssr")]
+fn classify(e: &(dyn std::error::Error + 'static)) -> AppError {
+    use subxt::Error;
+
+    let mut source = Some(e);
+    while let Some(err) = source {
+        if err.is::<crate::substrate::ChainUnavailable>() {
+            return AppError::RpcUnavailable(e.to_string());
+        }
+        if err.is::<tokio::time::error::Elapsed>() {
+            return AppError::Timeout;
+        }
+        match err.downcast_ref::<Error>() {
+            Some(Error::Io(_) | Error::Rpc(_)) => return AppError::RpcUnavailable(e.to_string()),
+            Some(Error::Codec(_) | Error::Metadata(_) | Error::Decode(_)) => {
+                return AppError::Decode(e.to_string());
+            }
+            _ => {}
+        }
+        if err.is::<subxt::ext::codec::Error>() || err.is::<subxt::ext::scale_decode::Error>() {
+            return AppError::Decode(e.to_string());
+        }
+        source = err.source();
+    }
+
+    AppError::Internal(e.to_string())
+}
+
+#[cfg(not(feature = "ssr"))]
+fn classify(e: &(dyn std::error::Error + 'static)) -> AppError {
+    AppError::Internal(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_the_wire_format() {
+        let errors = [
+            AppError::InvalidAddress("0xzz".to_string()),
+            AppError::Timeout,
+            AppError::RateLimited {
+                retry_after_secs: 3,
+            },
+        ];
+        for error in errors {
+            let json = serde_json::to_string(&error).unwrap();
+            assert_eq!(serde_json::from_str::<AppError>(&json).unwrap(), error);
+        }
+    }
+
+    #[test]
+    fn only_transient_errors_are_retryable() {
+        assert!(AppError::Timeout.is_retryable());
+        assert!(AppError::RpcUnavailable("down".to_string()).is_retryable());
+        assert!(!AppError::InvalidAddress("x".to_string()).is_retryable());
+        assert!(!AppError::NotFound("Block not found".to_string()).is_retryable());
+        assert!(!AppError::OutOfFunds("Empty".to_string()).is_retryable());
+    }
+}
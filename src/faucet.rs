@@ -0,0 +1,541 @@
+//! Testnet faucet, built with the `faucet` feature.
+//!
+//! Sends `faucet.amount` from the account of `faucet.seed` with
+//! `Balances::transfer_keep_alive`. The address and the client IP then wait
+//! for their cooldown, and nothing is sent that would take the faucet below
+//! `faucet.balance_floor`. With a `[faucet.captcha]` section, requests also
+//! need a token confirmed by the captcha provider.
+
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::AppError;
+
+/// Captcha to render on the faucet page.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CaptchaWidget {
+    pub script_url: String,
+    pub site_key: String,
+    /// Class of the element the provider's script renders the widget in
+    pub widget_class: String,
+    /// Form field the script fills with the token
+    pub response_field: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Drip {
+    pub address: String,
+    pub amount: u128,
+    pub extrinsic_hash: String,
+    /// Finalized block including the transfer
+    pub block_hash: String,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FaucetInfo {
+    /// Account the tokens are sent from
+    pub address: String,
+    /// Sent per request
+    pub amount: u128,
+    /// Transferable balance above the floor
+    pub available: u128,
+    pub address_cooldown_secs: u64,
+    pub ip_cooldown_secs: u64,
+    pub captcha: Option<CaptchaWidget>,
+    /// Latest drips, newest first
+    pub recent: Vec<Drip>,
+}
+
+#[leptos::server]
+pub async fn get_faucet_info() -> Result<FaucetInfo, AppError> {
+    let state = expect_context::<crate::state::AppState>();
+    enabled(&state)?.info(state.backend.as_ref()).await
+}
+
+/// Sends the faucet amount to `address`. `captcha` is the token of the
+/// captcha widget, when the faucet has one.
+#[leptos::server]
+pub async fn request_drip(address: String, captcha: Option<String>) -> Result<Drip, AppError> {
+    use axum::extract::ConnectInfo;
+    use axum::http::request::Parts;
+    use std::net::SocketAddr;
+
+    let state = expect_context::<crate::state::AppState>();
+    let faucet = enabled(&state)?;
+    let account = crate::require_account(&address)?;
+
+    let ip = use_context::<Parts>().and_then(|parts| {
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        state.rate_limiter.client_ip(&parts.headers, peer)
+    });
+
+    faucet.drip(&state, account, ip, captcha.as_deref()).await
+}
+
+#[cfg(feature = "ssr")]
+pub use server::*;
+
+#[cfg(feature = "ssr")]
+mod server {
+    use std::collections::{HashMap, VecDeque};
+    use std::net::IpAddr;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use serde::{Deserialize, Serialize};
+    use subxt::utils::{AccountId32, MultiAddress};
+    use subxt_signer::SecretUri;
+    use subxt_signer::sr25519::Keypair;
+    use tracing::info;
+
+    use super::{CaptchaWidget, Drip, FaucetInfo};
+    use crate::AppError;
+    use crate::config::Secret;
+    use crate::state::AppState;
+    use crate::substrate::ChainBackend;
+    use crate::substrate::allfeat;
+    use crate::utils::format_balance;
+
+    /// Drips kept for the faucet page.
+    const RECENT_DRIPS: usize = 20;
+
+    /// How long a submitted transfer may take to be finalized.
+    const FINALIZATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct FaucetConfig {
+        /// Secret URI of the account sending the tokens, e.g. `//Alice` on a
+        /// dev chain or a mnemonic. The faucet is disabled without it
+        #[serde(skip_serializing)]
+        pub seed: Secret,
+        /// Sent per request, in the smallest unit
+        pub amount: u64,
+        /// Transferable balance the faucet keeps, in the smallest unit
+        pub balance_floor: u64,
+        pub address_cooldown_secs: u64,
+        pub ip_cooldown_secs: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub captcha: Option<CaptchaConfig>,
+    }
+
+    impl Default for FaucetConfig {
+        fn default() -> Self {
+            Self {
+                seed: Secret::default(),
+                // 10 tokens at 12 decimals
+                amount: 10_000_000_000_000,
+                balance_floor: 0,
+                address_cooldown_secs: 86_400,
+                ip_cooldown_secs: 3_600,
+                captcha: None,
+            }
+        }
+    }
+
+    impl FaucetConfig {
+        /// Account of the seed, `None` when the faucet is disabled.
+        pub fn keypair(&self) -> Result<Option<Keypair>, String> {
+            if self.seed.0.trim().is_empty() {
+                return Ok(None);
+            }
+
+            // Neither error mentions the seed itself
+            let uri = SecretUri::from_str(self.seed.0.trim())
+                .map_err(|_| "not a valid secret URI".to_string())?;
+            Keypair::from_uri(&uri)
+                .map(Some)
+                .map_err(|_| "no account can be derived from it".to_string())
+        }
+    }
+
+    /// Captcha checked before each drip. The defaults are hCaptcha's, and
+    /// reCAPTCHA or Turnstile work as well with their own URLs and names,
+    /// all of them answering the same verification request.
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    pub struct CaptchaConfig {
+        pub site_key: String,
+        #[serde(skip_serializing)]
+        pub secret: Secret,
+        /// Endpoint the token is verified with
+        pub verify_url: String,
+        /// Script rendering the widget
+        pub script_url: String,
+        pub widget_class: String,
+        pub response_field: String,
+    }
+
+    impl Default for CaptchaConfig {
+        fn default() -> Self {
+            Self {
+                site_key: String::new(),
+                secret: Secret::default(),
+                verify_url: "https://api.hcaptcha.com/siteverify".to_string(),
+                script_url: "https://js.hcaptcha.com/1/api.js".to_string(),
+                widget_class: "h-captcha".to_string(),
+                response_field: "h-captcha-response".to_string(),
+            }
+        }
+    }
+
+    /// End of the cooldown of each address and client IP.
+    #[derive(Debug)]
+    pub struct Cooldowns {
+        address_cooldown: Duration,
+        ip_cooldown: Duration,
+        /// By raw account id, `AccountId32` isn't `Hash`
+        addresses: HashMap<[u8; 32], Instant>,
+        ips: HashMap<IpAddr, Instant>,
+    }
+
+    impl Cooldowns {
+        pub fn new(address_cooldown: Duration, ip_cooldown: Duration) -> Self {
+            Self {
+                address_cooldown,
+                ip_cooldown,
+                addresses: Default::default(),
+                ips: Default::default(),
+            }
+        }
+
+        /// Starts the cooldowns of `account` and `ip`, or tells how long
+        /// until both are over.
+        pub fn start(
+            &mut self,
+            account: &AccountId32,
+            ip: Option<IpAddr>,
+            now: Instant,
+        ) -> Result<(), Duration> {
+            self.addresses.retain(|_, end| *end > now);
+            self.ips.retain(|_, end| *end > now);
+
+            let address_end = self.addresses.get(&account.0);
+            let ip_end = ip.and_then(|ip| self.ips.get(&ip));
+            if let Some(end) = address_end.into_iter().chain(ip_end).max() {
+                return Err(end.saturating_duration_since(now));
+            }
+
+            self.addresses
+                .insert(account.0, now + self.address_cooldown);
+            if let Some(ip) = ip {
+                self.ips.insert(ip, now + self.ip_cooldown);
+            }
+            Ok(())
+        }
+
+        /// Lifts the cooldowns of a drip that wasn't sent.
+        pub fn cancel(&mut self, account: &AccountId32, ip: Option<IpAddr>) {
+            self.addresses.remove(&account.0);
+            if let Some(ip) = ip {
+                self.ips.remove(&ip);
+            }
+        }
+    }
+
+    pub fn enabled(state: &AppState) -> Result<Arc<Faucet>, AppError> {
+        state
+            .faucet
+            .clone()
+            .ok_or_else(|| AppError::NotFound("The faucet is not enabled".to_string()))
+    }
+
+    pub struct Faucet {
+        pub config: FaucetConfig,
+        pub account: AccountId32,
+        signer: Keypair,
+        cooldowns: Mutex<Cooldowns>,
+        recent: Mutex<VecDeque<Drip>>,
+        /// Held from the balance check to the submission, so concurrent
+        /// drips don't reuse a nonce
+        submitting: tokio::sync::Mutex<()>,
+        /// Sent by the drips submitted but not finalized yet, which the
+        /// finalized balance doesn't show
+        pending: Mutex<u128>,
+        http: reqwest::Client,
+    }
+
+    impl Faucet {
+        pub fn new(config: FaucetConfig) -> Result<Option<Self>, String> {
+            let Some(signer) = config.keypair().map_err(|e| format!("faucet.seed: {e}"))? else {
+                return Ok(None);
+            };
+
+            Ok(Some(Self {
+                account: AccountId32(signer.public_key().0),
+                signer,
+                cooldowns: Mutex::new(Cooldowns::new(
+                    Duration::from_secs(config.address_cooldown_secs),
+                    Duration::from_secs(config.ip_cooldown_secs),
+                )),
+                recent: Default::default(),
+                submitting: Default::default(),
+                pending: Default::default(),
+                http: reqwest::Client::new(),
+                config,
+            }))
+        }
+
+        /// Address the tokens are sent from.
+        pub fn address(&self) -> String {
+            crate::format_ss58(&self.account)
+        }
+
+        pub async fn info(&self, backend: &dyn ChainBackend) -> Result<FaucetInfo, AppError> {
+            let captcha = self.config.captcha.as_ref().map(|c| CaptchaWidget {
+                script_url: c.script_url.clone(),
+                site_key: c.site_key.clone(),
+                widget_class: c.widget_class.clone(),
+                response_field: c.response_field.clone(),
+            });
+
+            Ok(FaucetInfo {
+                address: self.address(),
+                amount: self.config.amount as u128,
+                available: self.available(backend).await?,
+                address_cooldown_secs: self.config.address_cooldown_secs,
+                ip_cooldown_secs: self.config.ip_cooldown_secs,
+                captcha,
+                recent: self
+                    .recent
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .iter()
+                    .cloned()
+                    .collect(),
+            })
+        }
+
+        /// Transferable balance above the floor, less the drips on their way.
+        async fn available(&self, backend: &dyn ChainBackend) -> Result<u128, AppError> {
+            let at = backend.latest_block().await?.hash;
+            let transferable = backend
+                .account(at, self.account.clone())
+                .await?
+                .map(|b| b.free.saturating_sub(b.frozen))
+                .unwrap_or(0);
+
+            let pending = *self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            Ok(transferable
+                .saturating_sub(self.config.balance_floor as u128)
+                .saturating_sub(pending))
+        }
+
+        pub async fn drip(
+            &self,
+            state: &AppState,
+            account: AccountId32,
+            ip: Option<IpAddr>,
+            captcha: Option<&str>,
+        ) -> Result<Drip, AppError> {
+            self.verify_captcha(captcha, ip).await?;
+
+            self.cooldowns
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .start(&account, ip, Instant::now())
+                .map_err(|wait| AppError::RateLimited {
+                    retry_after_secs: wait.as_secs().max(1),
+                })?;
+
+            let (extrinsic_hash, block_hash) = match self.transfer(state, &account).await {
+                Ok(hashes) => hashes,
+                Err(e) => {
+                    // A transfer that timed out may still go through
+                    if e != AppError::Timeout {
+                        self.cooldowns
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .cancel(&account, ip);
+                    }
+                    return Err(e);
+                }
+            };
+
+            let drip = Drip {
+                address: crate::format_ss58(&account),
+                amount: self.config.amount as u128,
+                extrinsic_hash: format!("{extrinsic_hash:?}"),
+                block_hash: format!("{block_hash:?}"),
+                timestamp_ms: chrono::Utc::now().timestamp_millis() as u64,
+            };
+            info!(
+                target: "faucet",
+                "Sent {} to {} for {}, extrinsic {}",
+                format_balance(drip.amount, true),
+                drip.address,
+                ip.map(|ip| ip.to_string()).unwrap_or_else(|| "unknown client".to_string()),
+                drip.extrinsic_hash,
+            );
+
+            let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+            recent.push_front(drip.clone());
+            recent.truncate(RECENT_DRIPS);
+
+            Ok(drip)
+        }
+
+        async fn verify_captcha(
+            &self,
+            token: Option<&str>,
+            ip: Option<IpAddr>,
+        ) -> Result<(), AppError> {
+            let Some(captcha) = &self.config.captcha else {
+                return Ok(());
+            };
+            let token = token
+                .map(str::trim)
+                .filter(|token| !token.is_empty())
+                .ok_or_else(|| AppError::InvalidInput("Solve the captcha first".to_string()))?;
+
+            #[derive(Deserialize)]
+            struct Verdict {
+                success: bool,
+            }
+
+            let mut form = vec![
+                ("secret", captcha.secret.0.clone()),
+                ("response", token.to_string()),
+            ];
+            if let Some(ip) = ip {
+                form.push(("remoteip", ip.to_string()));
+            }
+
+            let verdict = async {
+                self.http
+                    .post(&captcha.verify_url)
+                    .form(&form)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<Verdict>()
+                    .await
+            }
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to verify the captcha: {e}")))?;
+
+            if !verdict.success {
+                return Err(AppError::InvalidInput(
+                    "The captcha was not solved, try again".to_string(),
+                ));
+            }
+            Ok(())
+        }
+
+        /// Sends the amount to `dest` and waits for the transfer to be
+        /// finalized.
+        async fn transfer(
+            &self,
+            state: &AppState,
+            dest: &AccountId32,
+        ) -> Result<(subxt::utils::H256, subxt::utils::H256), AppError> {
+            let amount = self.config.amount as u128;
+
+            let (progress, _pending) = {
+                let _submitting = self.submitting.lock().await;
+
+                if self.available(state.backend.as_ref()).await? < amount {
+                    return Err(AppError::OutOfFunds(
+                        "The faucet is running low, come back later".to_string(),
+                    ));
+                }
+
+                let call = allfeat::tx()
+                    .balances()
+                    .transfer_keep_alive(MultiAddress::Id(dest.clone()), amount);
+                let progress = state
+                    .chain
+                    .client()?
+                    .tx()
+                    .sign_and_submit_then_watch_default(&call, &self.signer)
+                    .await?;
+                (progress, Pending::add(&self.pending, amount))
+            };
+
+            let in_block =
+                tokio::time::timeout(FINALIZATION_TIMEOUT, progress.wait_for_finalized())
+                    .await??;
+            let events = in_block.wait_for_success().await?;
+
+            Ok((events.extrinsic_hash(), in_block.block_hash()))
+        }
+    }
+
+    /// Amount of a submitted drip, counted until it is finalized or failed.
+    struct Pending<'a> {
+        pending: &'a Mutex<u128>,
+        amount: u128,
+    }
+
+    impl<'a> Pending<'a> {
+        fn add(pending: &'a Mutex<u128>, amount: u128) -> Self {
+            *pending.lock().unwrap_or_else(|e| e.into_inner()) += amount;
+            Self { pending, amount }
+        }
+    }
+
+    impl Drop for Pending<'_> {
+        fn drop(&mut self) {
+            *self.pending.lock().unwrap_or_else(|e| e.into_inner()) -= self.amount;
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn cooldowns_apply_per_address_and_ip() {
+            let mut cooldowns = Cooldowns::new(Duration::from_secs(100), Duration::from_secs(10));
+            let (alice, bob) = (AccountId32([1; 32]), AccountId32([2; 32]));
+            let ip = Some(IpAddr::from([10, 0, 0, 1]));
+            let now = Instant::now();
+
+            assert!(cooldowns.start(&alice, ip, now).is_ok());
+            assert_eq!(
+                cooldowns.start(&alice, None, now),
+                Err(Duration::from_secs(100))
+            );
+            // Another address from the same client waits for the IP cooldown
+            assert_eq!(
+                cooldowns.start(&bob, ip, now + Duration::from_secs(4)),
+                Err(Duration::from_secs(6))
+            );
+            assert!(
+                cooldowns
+                    .start(&bob, ip, now + Duration::from_secs(10))
+                    .is_ok()
+            );
+        }
+
+        #[test]
+        fn cancelled_drips_lift_the_cooldowns() {
+            let mut cooldowns = Cooldowns::new(Duration::from_secs(100), Duration::from_secs(100));
+            let alice = AccountId32([1; 32]);
+            let ip = Some(IpAddr::from([10, 0, 0, 1]));
+            let now = Instant::now();
+
+            assert!(cooldowns.start(&alice, ip, now).is_ok());
+            cooldowns.cancel(&alice, ip);
+            assert!(cooldowns.start(&alice, ip, now).is_ok());
+        }
+
+        #[test]
+        fn seed_is_never_printed() {
+            let config = FaucetConfig {
+                seed: Secret("//Alice".to_string()),
+                ..Default::default()
+            };
+
+            assert!(config.keypair().unwrap().is_some());
+            assert!(!format!("{config:?}").contains("Alice"));
+            assert!(!toml::to_string(&config).unwrap().contains("Alice"));
+            assert!(FaucetConfig::default().keypair().unwrap().is_none());
+        }
+    }
+}
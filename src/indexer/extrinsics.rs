@@ -0,0 +1,89 @@
+//! Extrinsic hashes of recent blocks, so that a hash typed in the search box
+//! can be resolved to the block that included it.
+//!
+//! Nodes don't index extrinsics by hash, hence the lookup only covers blocks
+//! seen by the indexer since startup (plus the backfill).
+
+use std::collections::{HashMap, VecDeque};
+
+use subxt::utils::H256;
+
+use super::{FinalizedBlock, IndexerError};
+use crate::state::AppState;
+
+/// Extrinsics retained in the index.
+const MAX_EXTRINSICS: usize = 500_000;
+
+#[derive(Debug, Default)]
+pub struct ExtrinsicIndex {
+    /// Extrinsic hash to (block number, extrinsic index)
+    by_hash: HashMap<H256, (u32, u32)>,
+    /// Indexed extrinsics, oldest first
+    order: VecDeque<((u32, u32), H256)>,
+}
+
+impl ExtrinsicIndex {
+    pub fn get(&self, hash: &H256) -> Option<(u32, u32)> {
+        self.by_hash.get(hash).copied()
+    }
+
+    fn insert(&mut self, hash: H256, location: (u32, u32)) {
+        // Backfill and live indexing may hand over the same block
+        let pos = match self.order.binary_search_by_key(&location, |(loc, _)| *loc) {
+            Ok(_) => return,
+            Err(pos) => pos,
+        };
+        self.order.insert(pos, (location, hash));
+        self.by_hash
+            .entry(hash)
+            .and_modify(|loc| *loc = (*loc).max(location))
+            .or_insert(location);
+
+        // Evicts the oldest blocks, even when the backfill is the one adding
+        while self.order.len() > MAX_EXTRINSICS {
+            let Some((location, oldest)) = self.order.pop_front() else {
+                break;
+            };
+            if self.by_hash.get(&oldest) == Some(&location) {
+                self.by_hash.remove(&oldest);
+            }
+        }
+    }
+}
+
+pub async fn index_block(state: &AppState, block: &FinalizedBlock) -> Result<(), IndexerError> {
+    let extrinsics = block.extrinsics().await?;
+    let number = block.number();
+
+    let mut index = state.extrinsic_index.write().await;
+    for ext in extrinsics.iter() {
+        index.insert(ext.hash(), (number, ext.index()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backfill_does_not_evict_recent_extrinsics() {
+        let mut index = ExtrinsicIndex::default();
+        let newest = MAX_EXTRINSICS as u64 + 10;
+        for block in 10..=newest {
+            index.insert(H256::from_low_u64_be(block), (block as u32, 0));
+        }
+        // Older blocks walked back by the backfill
+        for block in 0..10 {
+            index.insert(H256::from_low_u64_be(block), (block as u32, 0));
+        }
+
+        assert_eq!(
+            index.get(&H256::from_low_u64_be(newest)),
+            Some((newest as u32, 0))
+        );
+        assert_eq!(index.get(&H256::from_low_u64_be(0)), None);
+        assert_eq!(index.order.len(), MAX_EXTRINSICS);
+    }
+}
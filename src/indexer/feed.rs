@@ -0,0 +1,292 @@
+//! Live feed of finalized blocks, their transfers and the state of the node
+//! connection, relayed to the browsers by `get_block_stream` and `subscribe_metrics`.
+
+use std::collections::VecDeque;
+use std::sync::RwLock;
+
+use subxt::config::substrate::DigestItem;
+use subxt::ext::codec::Decode;
+use subxt::utils::AccountId32;
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use super::{FinalizedBlock, IndexerError};
+use crate::state::AppState;
+use crate::substrate::allfeat::balances::events::Transfer;
+use crate::{BlockStreamEvent, BlockSummary, TransferSummary, format_ss58};
+
+/// Events a slow subscriber can fall behind by before missing some.
+const CHANNEL_CAPACITY: usize = 16;
+
+/// Blocks and transfers replayed to new subscribers.
+pub const RECENT_BLOCKS: usize = 20;
+pub const RECENT_TRANSFERS: usize = 20;
+
+const AURA_ENGINE_ID: [u8; 4] = *b"aura";
+
+#[derive(Debug, Default)]
+struct Recent {
+    blocks: VecDeque<BlockSummary>,
+    transfers: VecDeque<TransferSummary>,
+    /// Error or reconnection not followed by a block yet
+    status: Option<BlockStreamEvent>,
+}
+
+#[derive(Debug)]
+pub struct BlockFeed {
+    sender: broadcast::Sender<BlockStreamEvent>,
+    /// Replayed to new subscribers so they don't start empty
+    recent: RwLock<Recent>,
+}
+
+impl Default for BlockFeed {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+            recent: Default::default(),
+        }
+    }
+}
+
+impl BlockFeed {
+    pub fn publish(&self, event: BlockStreamEvent) {
+        let mut recent = self.recent.write().unwrap_or_else(|e| e.into_inner());
+        match &event {
+            BlockStreamEvent::Block(block) => {
+                recent.blocks.push_back(block.clone());
+                if recent.blocks.len() > RECENT_BLOCKS {
+                    recent.blocks.pop_front();
+                }
+                recent.status = None;
+            }
+            BlockStreamEvent::Transfers { transfers } => {
+                recent.transfers.extend(transfers.iter().cloned());
+                let excess = recent.transfers.len().saturating_sub(RECENT_TRANSFERS);
+                recent.transfers.drain(..excess);
+            }
+            BlockStreamEvent::Error { .. } | BlockStreamEvent::Reconnecting { .. } => {
+                recent.status = Some(event.clone());
+            }
+        }
+        // Nobody listening is not an error
+        let _ = self.sender.send(event);
+    }
+
+    /// Recent events, oldest first, and a receiver for the following ones,
+    /// without gap or duplicate between the two.
+    pub fn subscribe(
+        &self,
+    ) -> (
+        VecDeque<BlockStreamEvent>,
+        broadcast::Receiver<BlockStreamEvent>,
+    ) {
+        let recent = self.recent.read().unwrap_or_else(|e| e.into_inner());
+
+        let mut backlog: VecDeque<_> = recent
+            .blocks
+            .iter()
+            .cloned()
+            .map(BlockStreamEvent::Block)
+            .collect();
+        if !recent.transfers.is_empty() {
+            backlog.push_back(BlockStreamEvent::Transfers {
+                transfers: recent.transfers.iter().cloned().collect(),
+            });
+        }
+        backlog.extend(recent.status.clone());
+
+        (backlog, self.sender.subscribe())
+    }
+}
+
+/// Publishes a finalized block and its transfers, or why it couldn't be read.
+/// Blocks changing the token allocations, or that couldn't be read, expire
+/// the envelope supply cached for the metrics.
+pub async fn publish_block(state: &AppState, block: &FinalizedBlock) {
+    let summary = summarize(state, block).await;
+
+    // Before the block is published, for the metrics read at it
+    let allocations_changed = !matches!(summary, Ok((_, _, false)));
+    if allocations_changed {
+        let mut cache = state.envelope_supply.write().await;
+        cache.changed_at = cache.changed_at.max(block.number());
+    }
+
+    match summary {
+        Ok((summary, transfers, _)) => {
+            state.telemetry.set_finality_lag(summary.finality_lag);
+            state.block_feed.publish(BlockStreamEvent::Block(summary));
+            if !transfers.is_empty() {
+                state
+                    .block_feed
+                    .publish(BlockStreamEvent::Transfers { transfers });
+            }
+        }
+        Err(e) => state.block_feed.publish(BlockStreamEvent::Error {
+            number: block.number(),
+            message: format!("Failed to read block #{}: {e}", block.number()),
+        }),
+    }
+}
+
+/// Summary and transfers of a block, and whether it changed the token
+/// allocations.
+async fn summarize(
+    state: &AppState,
+    block: &FinalizedBlock,
+) -> Result<(BlockSummary, Vec<TransferSummary>, bool), IndexerError> {
+    let rpc = state.chain.rpc()?;
+    let (extrinsics, events, best) = tokio::try_join!(
+        block.extrinsics(),
+        block.events(),
+        async { rpc.chain_get_header(None).await.map_err(subxt::Error::from) }
+    )?;
+    let timestamp = super::block_timestamp(block).await?;
+    let author = block_author(block).await.unwrap_or_else(|e| {
+        debug!(target: "indexer", "No author for #{}: {e}", block.number());
+        None
+    });
+
+    let mut transfers = vec![];
+    let mut allocations_changed = false;
+    for event in events.iter() {
+        let event = event?;
+        allocations_changed |= event.pallet_name() == "TokenAllocation";
+        if let Some(Transfer { from, to, amount }) = event.as_event::<Transfer>()? {
+            transfers.push(TransferSummary {
+                block: block.number(),
+                event_index: event.index(),
+                from: format_ss58(&from),
+                to: format_ss58(&to),
+                amount,
+                timestamp,
+            });
+        }
+    }
+    // Only the latest ones are ever shown
+    let excess = transfers.len().saturating_sub(RECENT_TRANSFERS);
+    transfers.drain(..excess);
+
+    let summary = BlockSummary {
+        number: block.number(),
+        hash: format!("{:?}", block.hash()),
+        timestamp,
+        extrinsic_count: extrinsics.len() as u32,
+        event_count: events.len(),
+        author,
+        finality_lag: best
+            .map(|header| header.number.saturating_sub(block.number()))
+            .unwrap_or(0),
+    };
+
+    Ok((summary, transfers, allocations_changed))
+}
+
+/// Collator of an Aura block: the authority of the slot in the pre-runtime
+/// digest, mapped back to its account by the session pallet.
+async fn block_author(block: &FinalizedBlock) -> Result<Option<String>, IndexerError> {
+    use subxt::dynamic::Value;
+
+    let Some(slot) = block.header().digest.logs.iter().find_map(|log| match log {
+        DigestItem::PreRuntime(engine, data) if *engine == AURA_ENGINE_ID => {
+            u64::decode(&mut &data[..]).ok()
+        }
+        _ => None,
+    }) else {
+        return Ok(None);
+    };
+
+    let storage = block.storage();
+    let authorities = storage
+        .fetch(&subxt::dynamic::storage("Aura", "Authorities", ()))
+        .await?
+        .map(|thunk| thunk.as_type::<Vec<[u8; 32]>>())
+        .transpose()?
+        .unwrap_or_default();
+    if authorities.is_empty() {
+        return Ok(None);
+    }
+    let key = authorities[(slot % authorities.len() as u64) as usize];
+
+    let key_owner = subxt::dynamic::storage(
+        "Session",
+        "KeyOwner",
+        vec![Value::unnamed_composite([
+            Value::from_bytes(AURA_ENGINE_ID),
+            Value::from_bytes(key),
+        ])],
+    );
+    let owner = storage
+        .fetch(&key_owner)
+        .await?
+        .map(|thunk| thunk.as_type::<AccountId32>())
+        .transpose()?;
+
+    Ok(owner.map(|account| format_ss58(&account)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(number: u32) -> BlockStreamEvent {
+        BlockStreamEvent::Block(BlockSummary {
+            number,
+            hash: format!("0x{number:064x}"),
+            timestamp: 0,
+            extrinsic_count: 1,
+            event_count: 2,
+            author: None,
+            finality_lag: 0,
+        })
+    }
+
+    fn transfer(block: u32, event_index: u32) -> TransferSummary {
+        TransferSummary {
+            block,
+            event_index,
+            from: "a".to_string(),
+            to: "b".to_string(),
+            amount: 1,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn replays_recent_blocks_then_transfers() {
+        let feed = BlockFeed::default();
+        for number in 0..30 {
+            feed.publish(block(number));
+        }
+        feed.publish(BlockStreamEvent::Transfers {
+            transfers: (0..25).map(|i| transfer(29, i)).collect(),
+        });
+
+        let (backlog, _) = feed.subscribe();
+        assert_eq!(backlog.len(), RECENT_BLOCKS + 1);
+        assert_eq!(backlog.front(), Some(&block(10)));
+        match backlog.back() {
+            Some(BlockStreamEvent::Transfers { transfers }) => {
+                assert_eq!(transfers.len(), RECENT_TRANSFERS);
+                assert_eq!(transfers[0].event_index, 5);
+            }
+            other => panic!("expected transfers, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn replays_status_until_next_block() {
+        let feed = BlockFeed::default();
+        feed.publish(block(1));
+        feed.publish(BlockStreamEvent::Reconnecting {
+            reason: "gone".to_string(),
+        });
+        assert!(matches!(
+            feed.subscribe().0.back(),
+            Some(BlockStreamEvent::Reconnecting { .. })
+        ));
+
+        feed.publish(block(2));
+        assert_eq!(feed.subscribe().0.back(), Some(&block(2)));
+    }
+}
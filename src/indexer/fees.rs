@@ -0,0 +1,370 @@
+//! Aggregation of `TransactionPayment::TransactionFeePaid` events.
+//!
+//! How much of a fee was burned isn't emitted by the chain, it is derived
+//! from the events of the extrinsic paying it, whatever their order: the
+//! treasury's share is what was deposited to the treasury account, and the
+//! rest of `TransactionFeePaid::actual_fee` has been burned.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::str::FromStr;
+
+use subxt::events::Phase;
+use subxt::utils::AccountId32;
+
+use super::{FinalizedBlock, IndexerError, block_timestamp};
+use crate::state::AppState;
+use crate::substrate::allfeat;
+use crate::substrate::allfeat::runtime_types::{
+    pallet_balances::pallet::Event as BalancesEvent,
+    pallet_transaction_payment::pallet::Event as TransactionPaymentEvent,
+    pallet_treasury::pallet::Event as TreasuryEvent,
+};
+use crate::{CallFeeStats, FeeBucket};
+
+/// Fee-paying blocks retained for the time series (7 days of full blocks).
+const MAX_BLOCKS: usize = 100_800;
+
+/// Fee totals of a single block.
+#[derive(Clone, Debug)]
+pub struct FeeBlock {
+    pub block: u32,
+    pub timestamp: u64,
+    pub paid: u128,
+    pub tips: u128,
+    pub to_treasury: u128,
+    pub burned: u128,
+    pub extrinsics: u32,
+    /// Fee of each extrinsic, by `Pallet.call`
+    pub calls: Vec<(String, u128)>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CallFees {
+    pub count: u64,
+    pub total: u128,
+}
+
+/// Everything the fee indexer has accumulated since startup.
+#[derive(Debug, Default)]
+pub struct FeeIndex {
+    /// Fee-paying blocks, oldest first
+    pub blocks: VecDeque<FeeBlock>,
+    /// Fees per `Pallet.call`, over the retained blocks
+    pub per_call: HashMap<String, CallFees>,
+}
+
+impl FeeIndex {
+    fn insert(&mut self, entry: FeeBlock) {
+        // Backfill and live indexing may hand over the same block
+        let pos = match self.blocks.binary_search_by_key(&entry.block, |b| b.block) {
+            Ok(_) => return,
+            Err(pos) => pos,
+        };
+
+        for (call, fee) in &entry.calls {
+            let stats = self.per_call.entry(call.clone()).or_default();
+            stats.count += 1;
+            stats.total += fee;
+        }
+        self.blocks.insert(pos, entry);
+
+        // Per-call totals cover the same blocks as the time series
+        while self.blocks.len() > MAX_BLOCKS {
+            let Some(evicted) = self.blocks.pop_front() else {
+                break;
+            };
+            for (call, fee) in evicted.calls {
+                if let Some(stats) = self.per_call.get_mut(&call) {
+                    stats.count -= 1;
+                    stats.total -= fee;
+                    if stats.count == 0 {
+                        self.per_call.remove(&call);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Groups the indexed blocks into `count` buckets of `bucket_blocks`
+    /// blocks each, ending at the latest indexed block. Oldest first.
+    /// Buckets are at most as long as the retained blocks.
+    pub fn buckets(&self, bucket_blocks: u32, count: u32) -> Vec<FeeBucket> {
+        let Some(last) = self.blocks.back().map(|b| b.block) else {
+            return vec![];
+        };
+
+        let bucket_blocks = bucket_blocks.clamp(1, MAX_BLOCKS as u32);
+        let end = (last - last % bucket_blocks).saturating_add(bucket_blocks);
+        let start = end.saturating_sub(bucket_blocks.saturating_mul(count));
+
+        let mut buckets: Vec<FeeBucket> = (0..count)
+            .map_while(|i| start.checked_add(i.checked_mul(bucket_blocks)?))
+            .take_while(|from| *from < end)
+            .map(|from| FeeBucket {
+                from_block: from,
+                to_block: from.saturating_add(bucket_blocks - 1),
+                timestamp: 0,
+                paid: 0,
+                burned: 0,
+                to_treasury: 0,
+                tips: 0,
+                extrinsics: 0,
+            })
+            .collect();
+
+        for b in self.blocks.iter().filter(|b| b.block >= start) {
+            let Some(bucket) = buckets.get_mut(((b.block - start) / bucket_blocks) as usize) else {
+                continue;
+            };
+            if bucket.timestamp == 0 {
+                bucket.timestamp = b.timestamp;
+            }
+            bucket.paid += b.paid;
+            bucket.burned += b.burned;
+            bucket.to_treasury += b.to_treasury;
+            bucket.tips += b.tips;
+            bucket.extrinsics += b.extrinsics;
+        }
+
+        buckets
+    }
+
+    /// Average fee per call, most used first.
+    pub fn per_call(&self) -> Vec<CallFeeStats> {
+        let mut stats: Vec<CallFeeStats> = self
+            .per_call
+            .iter()
+            .map(|(call, fees)| CallFeeStats {
+                call: call.clone(),
+                count: fees.count,
+                total: fees.total,
+                average: fees.total / fees.count.max(1) as u128,
+            })
+            .collect();
+        stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.call.cmp(&b.call)));
+        stats
+    }
+}
+
+/// A fee settled for one extrinsic.
+#[derive(Debug, PartialEq)]
+struct SettledFee {
+    extrinsic: u32,
+    fee: u128,
+    tip: u128,
+    to_treasury: u128,
+    burned: u128,
+}
+
+/// Fee related events of one extrinsic.
+#[derive(Debug, Default)]
+struct ExtrinsicFees {
+    /// `actual_fee` and `tip` of `TransactionFeePaid`
+    paid: Option<(u128, u128)>,
+    /// `Treasury::Deposit` values
+    treasury_pallet: u128,
+    /// `Balances::Deposit` amounts to the treasury account
+    treasury_account: u128,
+}
+
+impl ExtrinsicFees {
+    fn add(&mut self, event: allfeat::Event, treasury: &AccountId32) {
+        match event {
+            allfeat::Event::Treasury(TreasuryEvent::Deposit { value }) => {
+                self.treasury_pallet += value;
+            }
+            allfeat::Event::Balances(BalancesEvent::Deposit { who, amount })
+                if who == *treasury =>
+            {
+                self.treasury_account += amount;
+            }
+            allfeat::Event::TransactionPayment(TransactionPaymentEvent::TransactionFeePaid {
+                actual_fee,
+                tip,
+                ..
+            }) => {
+                self.paid = Some((actual_fee, tip));
+            }
+            _ => {}
+        }
+    }
+
+    /// `None` for extrinsics that paid no fee.
+    fn settle(self, extrinsic: u32) -> Option<SettledFee> {
+        let (fee, tip) = self.paid?;
+        // The treasury's share usually shows up both as `Treasury::Deposit`
+        // and as a `Balances::Deposit` to the treasury account
+        let to_treasury = self.treasury_account.max(self.treasury_pallet).min(fee);
+
+        Some(SettledFee {
+            extrinsic,
+            fee,
+            tip,
+            to_treasury,
+            burned: fee - to_treasury,
+        })
+    }
+}
+
+pub async fn index_block(state: &AppState, block: &FinalizedBlock) -> Result<(), IndexerError> {
+    let treasury = AccountId32::from_str(crate::utils::TREASURY_ACCOUNT)
+        .map_err(|e| format!("Invalid treasury account: {e}"))?;
+    let events = block.events().await?;
+
+    let mut by_extrinsic: BTreeMap<u32, ExtrinsicFees> = BTreeMap::new();
+    for event in events.iter() {
+        let event = event?;
+        let Phase::ApplyExtrinsic(idx) = event.phase() else {
+            continue;
+        };
+
+        let relevant = matches!(
+            (event.pallet_name(), event.variant_name()),
+            ("Treasury", "Deposit") | ("Balances", "Deposit") | ("TransactionPayment", _)
+        );
+        if relevant {
+            by_extrinsic
+                .entry(idx)
+                .or_default()
+                .add(event.as_root_event::<allfeat::Event>()?, &treasury);
+        }
+    }
+
+    let settled: Vec<SettledFee> = by_extrinsic
+        .into_iter()
+        .filter_map(|(idx, fees)| fees.settle(idx))
+        .collect();
+    if settled.is_empty() {
+        return Ok(());
+    }
+
+    let extrinsics = block.extrinsics().await?;
+    let timestamp = block_timestamp(block).await?;
+
+    let mut entry = FeeBlock {
+        block: block.number(),
+        timestamp,
+        paid: 0,
+        tips: 0,
+        to_treasury: 0,
+        burned: 0,
+        extrinsics: settled.len() as u32,
+        calls: Vec::with_capacity(settled.len()),
+    };
+
+    for fee in settled {
+        entry.paid += fee.fee;
+        entry.tips += fee.tip;
+        entry.to_treasury += fee.to_treasury;
+        entry.burned += fee.burned;
+
+        let call = extrinsics
+            .iter()
+            .nth(fee.extrinsic as usize)
+            .and_then(|ext| {
+                Some(format!(
+                    "{}.{}",
+                    ext.pallet_name().ok()?,
+                    ext.variant_name().ok()?
+                ))
+            })
+            .unwrap_or_else(|| "Unknown".to_string());
+        entry.calls.push((call, fee.fee));
+    }
+
+    state.fee_index.write().await.insert(entry);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee_block(block: u32, call: &str, paid: u128) -> FeeBlock {
+        FeeBlock {
+            block,
+            timestamp: block as u64,
+            paid,
+            tips: 0,
+            to_treasury: 0,
+            burned: 0,
+            extrinsics: 1,
+            calls: vec![(call.to_string(), paid)],
+        }
+    }
+
+    #[test]
+    fn per_call_totals_follow_the_retained_blocks() {
+        let mut index = FeeIndex::default();
+        for block in 0..MAX_BLOCKS as u32 + 2 {
+            let call = if block < 2 {
+                "Old.call"
+            } else {
+                "Balances.transfer"
+            };
+            index.insert(fee_block(block, call, 10));
+        }
+
+        let stats = index.per_call();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].count, MAX_BLOCKS as u64);
+    }
+
+    #[test]
+    fn fee_split_between_treasury_and_burn() {
+        let treasury = AccountId32([7; 32]);
+        let payer = AccountId32([1; 32]);
+        let fee_paid = || {
+            allfeat::Event::TransactionPayment(TransactionPaymentEvent::TransactionFeePaid {
+                who: payer.clone(),
+                actual_fee: 100,
+                tip: 5,
+            })
+        };
+        let deposit = |who: &AccountId32, amount| {
+            allfeat::Event::Balances(BalancesEvent::Deposit {
+                who: who.clone(),
+                amount,
+            })
+        };
+        let expected = Some(SettledFee {
+            extrinsic: 3,
+            fee: 100,
+            tip: 5,
+            to_treasury: 80,
+            burned: 20,
+        });
+
+        // A refund to the payer isn't part of the split
+        let mut fees = ExtrinsicFees::default();
+        fees.add(deposit(&payer, 40), &treasury);
+        fees.add(
+            allfeat::Event::Treasury(TreasuryEvent::Deposit { value: 80 }),
+            &treasury,
+        );
+        fees.add(deposit(&treasury, 80), &treasury);
+        fees.add(fee_paid(), &treasury);
+        assert_eq!(fees.settle(3), expected);
+
+        // Nor does the order of the events matter
+        let mut fees = ExtrinsicFees::default();
+        fees.add(fee_paid(), &treasury);
+        fees.add(deposit(&treasury, 80), &treasury);
+        fees.add(deposit(&payer, 40), &treasury);
+        assert_eq!(fees.settle(3), expected);
+
+        assert_eq!(ExtrinsicFees::default().settle(3), None);
+    }
+
+    #[test]
+    fn huge_buckets_are_capped() {
+        let mut index = FeeIndex::default();
+        index.insert(fee_block(u32::MAX - 5, "Balances.transfer", 10));
+
+        let buckets = index.buckets(u32::MAX, 500);
+        assert!(!buckets.is_empty());
+        assert!(buckets.iter().all(|b| b.from_block <= b.to_block));
+        assert_eq!(buckets.iter().map(|b| b.paid).sum::<u128>(), 10);
+    }
+}
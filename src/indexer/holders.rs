@@ -0,0 +1,330 @@
+//! Ranking of every account by balance, for the top holders page.
+//!
+//! `System::Account` is iterated in full at a finalized block every
+//! `HOLDERS_REFRESH_INTERVAL` blocks. The scan runs in its own task and the
+//! previous snapshot keeps being served until the new one is complete. Only
+//! one scan runs at a time, and it doesn't take one of the scan permits of
+//! the rate limiter, which would be held away from requests for the whole
+//! scan.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use super::{FinalizedBlock, IndexerError};
+use crate::state::AppState;
+use crate::substrate;
+use crate::{DistributionStats, HolderRanking, TopHolder, TopHoldersPage, format_ss58};
+use subxt::utils::AccountId32;
+
+/// Blocks between two scans of the accounts, one hour by default.
+pub const DEFAULT_REFRESH_INTERVAL: u32 = 600;
+
+#[derive(Clone, Debug)]
+pub struct HolderBalance {
+    pub address: String,
+    pub free: u128,
+    pub reserved: u128,
+    pub frozen: u128,
+}
+
+impl HolderBalance {
+    pub fn amount(&self, ranking: HolderRanking) -> u128 {
+        ranking.amount(self.free, self.reserved, self.frozen)
+    }
+}
+
+/// Every account with a non-zero balance at a given block.
+#[derive(Debug)]
+pub struct HoldersSnapshot {
+    pub block: u32,
+    pub total_issuance: u128,
+    /// Sorted by total balance, largest first
+    pub by_total: Vec<HolderBalance>,
+    /// Indices into `by_total`, sorted by transferable balance
+    pub by_transferable: Vec<usize>,
+    pub stats: DistributionStats,
+}
+
+impl HoldersSnapshot {
+    pub fn new(block: u32, total_issuance: u128, mut holders: Vec<HolderBalance>) -> Self {
+        holders.retain(|h| h.amount(HolderRanking::Total) > 0);
+        holders.sort_by_key(|h| std::cmp::Reverse(h.amount(HolderRanking::Total)));
+
+        let mut by_transferable: Vec<usize> = (0..holders.len()).collect();
+        by_transferable
+            .sort_by_key(|i| std::cmp::Reverse(holders[*i].amount(HolderRanking::Transferable)));
+
+        let totals: Vec<u128> = holders
+            .iter()
+            .map(|h| h.amount(HolderRanking::Total))
+            .collect();
+        let stats = DistributionStats {
+            holders: holders.len(),
+            gini: gini(&totals),
+            top10_share: share(totals.iter().take(10).sum(), total_issuance),
+            top100_share: share(totals.iter().take(100).sum(), total_issuance),
+        };
+
+        Self {
+            block,
+            total_issuance,
+            by_total: holders,
+            by_transferable,
+            stats,
+        }
+    }
+
+    pub fn page(&self, ranking: HolderRanking, offset: usize, limit: usize) -> TopHoldersPage {
+        let holder = |rank: usize, h: &HolderBalance| TopHolder {
+            rank: rank + 1,
+            address: h.address.clone(),
+            free: h.free,
+            reserved: h.reserved,
+            frozen: h.frozen,
+        };
+
+        let items = match ranking {
+            HolderRanking::Total => self
+                .by_total
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(limit)
+                .map(|(rank, h)| holder(rank, h))
+                .collect(),
+            HolderRanking::Transferable => self
+                .by_transferable
+                .iter()
+                .enumerate()
+                .skip(offset)
+                .take(limit)
+                .map(|(rank, i)| holder(rank, &self.by_total[*i]))
+                .collect(),
+        };
+
+        TopHoldersPage {
+            block: self.block,
+            total_issuance: self.total_issuance,
+            stats: self.stats.clone(),
+            items,
+            total: self.by_total.len(),
+        }
+    }
+}
+
+/// Gini coefficient of the given balances, sorted largest first. 0 when
+/// every account holds the same amount, close to 1 when one account holds
+/// everything.
+pub fn gini(sorted_desc: &[u128]) -> f64 {
+    let n = sorted_desc.len() as f64;
+    let sum: f64 = sorted_desc.iter().map(|b| *b as f64).sum();
+    if sorted_desc.is_empty() || sum == 0.0 {
+        return 0.0;
+    }
+
+    // Ranks are taken in ascending order of balance
+    let weighted: f64 = sorted_desc
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, b)| (i + 1) as f64 * *b as f64)
+        .sum();
+
+    (2.0 * weighted) / (n * sum) - (n + 1.0) / n
+}
+
+/// `part` as a percentage of `total`.
+pub fn share(part: u128, total: u128) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    part as f64 / total as f64 * 100.0
+}
+
+#[derive(Debug)]
+pub struct HolderIndex {
+    pub refresh_interval: u32,
+    pub snapshot: RwLock<Option<Arc<HoldersSnapshot>>>,
+    refreshing: AtomicBool,
+}
+
+impl HolderIndex {
+    /// Scans every `refresh_interval` blocks.
+    pub fn new(refresh_interval: u32) -> Self {
+        Self {
+            refresh_interval: refresh_interval.max(1),
+            snapshot: Default::default(),
+            refreshing: AtomicBool::new(false),
+        }
+    }
+
+    /// Marks a scan as running, unless one already is.
+    fn start_refresh(self: &Arc<Self>) -> Option<Refreshing> {
+        (!self.refreshing.swap(true, Ordering::AcqRel)).then(|| Refreshing(self.clone()))
+    }
+}
+
+/// Clears the running flag when the scan ends, failed or panicked.
+struct Refreshing(Arc<HolderIndex>);
+
+impl Drop for Refreshing {
+    fn drop(&mut self) {
+        self.0.refreshing.store(false, Ordering::Release);
+    }
+}
+
+/// Starts a new scan at `block` when the snapshot is older than the refresh
+/// interval and no scan is running. Returns immediately.
+pub async fn refresh_if_due(state: &AppState, block: &FinalizedBlock) {
+    let index = &state.holders;
+    let number = block.number();
+
+    let last = index.snapshot.read().await.as_ref().map(|s| s.block);
+    if matches!(last, Some(at) if number < at.saturating_add(index.refresh_interval)) {
+        return;
+    }
+    let Some(refreshing) = index.start_refresh() else {
+        return;
+    };
+
+    let state = state.clone();
+    let block_ref = block.reference();
+    tokio::spawn(async move {
+        let _refreshing = refreshing;
+        match scan(&state, block_ref, number).await {
+            Ok(snapshot) => {
+                info!(
+                    target: "indexer",
+                    "Ranked {} holders at #{number}",
+                    snapshot.by_total.len()
+                );
+                *state.holders.snapshot.write().await = Some(Arc::new(snapshot));
+            }
+            Err(e) => warn!(target: "indexer", "Holder scan failed at #{number}: {e}"),
+        }
+    });
+}
+
+async fn scan(
+    state: &AppState,
+    block_ref: subxt::blocks::BlockRef<subxt::utils::H256>,
+    number: u32,
+) -> Result<HoldersSnapshot, IndexerError> {
+    let storage = state.chain.client()?.storage().at(block_ref);
+
+    let total_issuance = storage
+        .fetch_or_default(&substrate::allfeat::storage().balances().total_issuance())
+        .await?;
+
+    let mut iter = storage
+        .iter(substrate::allfeat::storage().system().account_iter())
+        .await?;
+
+    let mut holders = Vec::new();
+    while let Some(kv) = iter.next().await {
+        let kv = kv?;
+        // Blake2_128Concat keys end with the account id itself
+        let Some(account) = kv
+            .key_bytes
+            .len()
+            .checked_sub(32)
+            .and_then(|start| <[u8; 32]>::try_from(&kv.key_bytes[start..]).ok())
+        else {
+            continue;
+        };
+
+        holders.push(HolderBalance {
+            address: format_ss58(&AccountId32(account)),
+            free: kv.value.data.free,
+            reserved: kv.value.data.reserved,
+            frozen: kv.value.data.frozen,
+        });
+    }
+
+    Ok(HoldersSnapshot::new(number, total_issuance, holders))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holder(name: &str, free: u128, reserved: u128, frozen: u128) -> HolderBalance {
+        HolderBalance {
+            address: name.to_string(),
+            free,
+            reserved,
+            frozen,
+        }
+    }
+
+    #[test]
+    fn one_refresh_at_a_time() {
+        let index = Arc::new(HolderIndex::new(10));
+        let refreshing = index.start_refresh();
+        assert!(refreshing.is_some());
+        assert!(index.start_refresh().is_none());
+
+        drop(refreshing);
+        assert!(index.start_refresh().is_some());
+    }
+
+    #[test]
+    fn equal_balances_have_no_inequality() {
+        assert_eq!(gini(&[]), 0.0);
+        assert_eq!(gini(&[0, 0]), 0.0);
+        assert!(gini(&[5, 5, 5, 5]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn single_holder_is_maximal_inequality() {
+        // (n - 1) / n for one holder out of n accounts
+        assert!((gini(&[100, 0, 0, 0]) - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn gini_of_known_distribution() {
+        // Mean absolute difference of {1, 2, 3} is 8/9, over twice the mean
+        assert!((gini(&[3, 2, 1]) - 2.0 / 9.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn snapshot_ranks_by_total_and_transferable() {
+        let snapshot = HoldersSnapshot::new(
+            42,
+            1_000,
+            vec![
+                holder("a", 100, 0, 0),
+                holder("b", 50, 200, 0),
+                holder("c", 0, 0, 0),
+                holder("d", 300, 0, 250),
+            ],
+        );
+
+        let total = snapshot.page(HolderRanking::Total, 0, 10);
+        assert_eq!(total.total, 3);
+        assert_eq!(
+            total
+                .items
+                .iter()
+                .map(|h| h.address.as_str())
+                .collect::<Vec<_>>(),
+            ["d", "b", "a"]
+        );
+
+        let transferable = snapshot.page(HolderRanking::Transferable, 1, 10);
+        assert_eq!(
+            transferable
+                .items
+                .iter()
+                .map(|h| (h.rank, h.address.as_str()))
+                .collect::<Vec<_>>(),
+            [(2, "d"), (3, "b")]
+        );
+
+        assert_eq!(snapshot.stats.holders, 3);
+        assert!((snapshot.stats.top10_share - 65.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,163 @@
+//! Background indexers fed by the finalized block stream.
+//!
+//! A single subscription to finalized blocks is shared by every indexer so
+//! the RPC node only has to serve one stream, whatever the number of views
+//! the explorer derives from it.
+
+use std::time::Duration;
+
+use subxt::blocks::Block;
+use subxt::{OnlineClient, SubstrateConfig};
+use tracing::{error, info, warn};
+
+use crate::state::AppState;
+use crate::substrate::ChainUnavailable;
+
+pub mod extrinsics;
+pub mod feed;
+pub mod fees;
+pub mod holders;
+pub mod payouts;
+pub mod sudo;
+pub mod tokenomics;
+
+/// Error type shared by the indexers, mirroring `connection::connect`.
+pub type IndexerError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A finalized block as handed to each indexer.
+pub type FinalizedBlock = Block<SubstrateConfig, OnlineClient<SubstrateConfig>>;
+
+/// Delay before re-subscribing when the finalized stream ends or errors.
+const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// Spawns the indexing task. Returns immediately.
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        // Walked back so the indexers are not empty until new activity happens
+        let backfill = state.config.indexer.backfill_blocks;
+        if backfill > 0 {
+            let state = state.clone();
+            tokio::spawn(async move {
+                if let Err(e) = run_backfill(&state, backfill).await {
+                    warn!(target: "indexer", "Backfill aborted: {e}");
+                }
+            });
+        }
+
+        loop {
+            let reason = match follow_finalized(&state).await {
+                Ok(()) => "Finalized block subscription ended".to_string(),
+                // Already reported by the connection task
+                Err(e) if e.is::<ChainUnavailable>() => e.to_string(),
+                Err(e) => {
+                    error!(target: "indexer", "Finalized block subscription failed: {e}");
+                    format!("Finalized block subscription failed: {e}")
+                }
+            };
+            state.telemetry.reconnected();
+            state
+                .block_feed
+                .publish(crate::BlockStreamEvent::Reconnecting { reason });
+            tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+        }
+    });
+}
+
+/// Follows the finalized blocks until the subscription ends or the
+/// connection moves to another client, whose blocks this subscription
+/// would never see.
+async fn follow_finalized(state: &AppState) -> Result<(), IndexerError> {
+    let mut connection = state.chain.watch();
+    let mut blocks_sub = connection.client()?.blocks().subscribe_finalized().await?;
+
+    info!(target: "indexer", "Following finalized blocks");
+
+    loop {
+        let block = tokio::select! {
+            block = blocks_sub.next() => block,
+            // Already reported by the connection task
+            _ = connection.changed() => return Err(ChainUnavailable.into()),
+        };
+        let Some(block) = block else {
+            break;
+        };
+        let block = block?;
+        state.telemetry.finalized(block.number());
+        index_block(state, &block).await;
+
+        feed::publish_block(state, &block).await;
+
+        // Only meaningful at the chain head, so not part of the backfill
+        let features = &state.config.features;
+        if features.tokenomics_alerts
+            && let Err(e) = tokenomics::check_block(state, &block).await
+        {
+            warn!(target: "indexer", "Tokenomics checks failed at #{}: {e}", block.number());
+        }
+        if features.holders {
+            holders::refresh_if_due(state, &block).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Walks parent hashes back from the current finalized head.
+async fn run_backfill(state: &AppState, depth: u32) -> Result<(), IndexerError> {
+    // Nothing to backfill from until a node answers
+    let client = state.chain.wait_for_client().await?;
+    let mut block = client.blocks().at_latest().await?;
+
+    info!(target: "indexer", "Backfilling {depth} blocks from #{}", block.number());
+
+    for _ in 0..depth {
+        if block.number() == 0 {
+            break;
+        }
+        let parent = block.header().parent_hash;
+        block = client.blocks().at(parent).await?;
+        index_block(state, &block).await;
+    }
+
+    info!(target: "indexer", "Backfill complete at #{}", block.number());
+    Ok(())
+}
+
+/// Hands a block to every indexer. Failures are logged per indexer so one
+/// broken decoder doesn't stall the others.
+async fn index_block(state: &AppState, block: &FinalizedBlock) {
+    if let Err(e) = extrinsics::index_block(state, block).await {
+        warn!(target: "indexer", "Extrinsic indexer failed at #{}: {e}", block.number());
+    }
+    if let Err(e) = sudo::index_block(state, block).await {
+        warn!(target: "indexer", "Sudo indexer failed at #{}: {e}", block.number());
+    }
+    if let Err(e) = fees::index_block(state, block).await {
+        warn!(target: "indexer", "Fee indexer failed at #{}: {e}", block.number());
+    }
+    if let Err(e) = payouts::index_block(state, block).await {
+        warn!(target: "indexer", "Payout indexer failed at #{}: {e}", block.number());
+    }
+}
+
+/// `Timestamp::Now`, as `twox128("Timestamp") ++ twox128("Now")`.
+pub const TIMESTAMP_NOW_STORAGE: &str =
+    "0xf0c365c3cf59d671eb72da0e7a4113c49f1f0515f462cdcf84e0f1d6045dfcbb";
+
+/// Reads `Timestamp::Now` at the given block, in milliseconds.
+///
+/// The timestamp pallet isn't part of the bundled metadata, so the value is
+/// read from its raw storage key.
+pub async fn block_timestamp(block: &FinalizedBlock) -> Result<u64, IndexerError> {
+    let key = crate::utils::decode_hex(TIMESTAMP_NOW_STORAGE).unwrap_or_default();
+
+    let now = block
+        .storage()
+        .fetch_raw(key)
+        .await?
+        .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+        .map(u64::from_le_bytes)
+        .unwrap_or(0);
+
+    Ok(now)
+}
@@ -0,0 +1,164 @@
+//! History of token allocation payouts, per epoch and per envelope.
+//!
+//! Releases are attributed to the epoch announced by the `EpochPayout` event
+//! of the same block, or to the on-chain `EpochIndex` when a batched payout
+//! continues over following blocks.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::{FinalizedBlock, IndexerError, block_timestamp};
+use crate::state::AppState;
+use crate::substrate;
+use crate::substrate::allfeat::runtime_types::pallet_token_allocation::EnvelopeId;
+use crate::substrate::allfeat::token_allocation::events::{EpochPayout, VestedReleased};
+use crate::{EnvelopePayout, EpochPayoutSummary, envelope_to_str};
+
+/// Epochs retained in the history.
+const MAX_EPOCHS: usize = 365;
+
+#[derive(Clone, Debug, Default)]
+pub struct EpochRecord {
+    pub first_block: u32,
+    pub last_block: u32,
+    pub timestamp: u64,
+    pub completed: bool,
+    pub allocations: u32,
+    pub per_envelope: HashMap<&'static str, u128>,
+}
+
+#[derive(Debug, Default)]
+pub struct PayoutIndex {
+    pub epochs: BTreeMap<u64, EpochRecord>,
+    /// Oldest block covered by the history, payout or not
+    pub indexed_since: Option<u32>,
+    /// Allocations never move between envelopes, cache the lookups
+    envelope_of: HashMap<u32, EnvelopeId>,
+}
+
+impl PayoutIndex {
+    fn covered(&mut self, block: u32) {
+        self.indexed_since = Some(self.indexed_since.map_or(block, |since| since.min(block)));
+    }
+
+    /// Most recent epochs first.
+    pub fn history(&self, limit: usize) -> Vec<EpochPayoutSummary> {
+        self.epochs
+            .iter()
+            .rev()
+            .take(limit)
+            .map(|(epoch, record)| {
+                let mut per_envelope: Vec<EnvelopePayout> = record
+                    .per_envelope
+                    .iter()
+                    .map(|(name, amount)| EnvelopePayout {
+                        envelope: name.to_string(),
+                        amount: *amount,
+                    })
+                    .collect();
+                per_envelope.sort_by_key(|p| std::cmp::Reverse(p.amount));
+
+                EpochPayoutSummary {
+                    epoch: *epoch,
+                    first_block: record.first_block,
+                    last_block: record.last_block,
+                    timestamp: record.timestamp,
+                    completed: record.completed,
+                    allocations: record.allocations,
+                    total: record.per_envelope.values().sum(),
+                    per_envelope,
+                }
+            })
+            .collect()
+    }
+}
+
+pub async fn index_block(state: &AppState, block: &FinalizedBlock) -> Result<(), IndexerError> {
+    let events = block.events().await?;
+
+    let payout = events.find_first::<EpochPayout>()?;
+    let releases = events
+        .find::<VestedReleased>()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if payout.is_none() && releases.is_empty() {
+        state.payout_index.write().await.covered(block.number());
+        return Ok(());
+    }
+
+    let storage = block.storage();
+    let epoch = match &payout {
+        Some(p) => p.epoch,
+        None => {
+            let query = substrate::allfeat::storage()
+                .token_allocation()
+                .epoch_index();
+            storage.fetch_or_default(&query).await?
+        }
+    };
+
+    // Resolve envelopes before taking the write lock
+    let mut envelopes = Vec::with_capacity(releases.len());
+    for VestedReleased(id, amount) in &releases {
+        let cached = state.payout_index.read().await.envelope_of.get(id).cloned();
+        let envelope = match cached {
+            Some(envelope) => Some(envelope),
+            None => {
+                let query = substrate::allfeat::storage()
+                    .token_allocation()
+                    .allocations(*id);
+                storage.fetch(&query).await?.map(|alloc| alloc.envelope)
+            }
+        };
+        envelopes.push((*id, envelope, *amount));
+    }
+
+    let timestamp = block_timestamp(block).await?;
+    let number = block.number();
+
+    let mut index = state.payout_index.write().await;
+    index.covered(number);
+
+    let record = index.epochs.entry(epoch).or_insert_with(|| EpochRecord {
+        first_block: number,
+        last_block: number,
+        timestamp,
+        ..Default::default()
+    });
+
+    // Backfill and live indexing may hand over the same block
+    if number >= record.first_block && number <= record.last_block && record.allocations > 0 {
+        return Ok(());
+    }
+
+    if number < record.first_block {
+        record.first_block = number;
+        record.timestamp = timestamp;
+    }
+    record.last_block = record.last_block.max(number);
+    if let Some(p) = &payout {
+        // A cursor means the payout continues in the next blocks
+        record.completed = p.cursor.is_none();
+    }
+
+    for (_, envelope, amount) in &envelopes {
+        let name = envelope.as_ref().map(envelope_to_str).unwrap_or("Unknown");
+        *record.per_envelope.entry(name).or_default() += amount;
+        record.allocations += 1;
+    }
+
+    for (id, envelope, _) in envelopes {
+        if let Some(envelope) = envelope {
+            index.envelope_of.insert(id, envelope);
+        }
+    }
+
+    if index.epochs.len() > MAX_EPOCHS {
+        while index.epochs.len() > MAX_EPOCHS {
+            index.epochs.pop_first();
+        }
+        // Blocks before the oldest retained epoch are no longer covered
+        index.indexed_since = index.epochs.values().next().map(|e| e.first_block);
+    }
+
+    Ok(())
+}
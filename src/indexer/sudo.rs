@@ -0,0 +1,344 @@
+//! Audit log of `pallet_sudo` activity.
+
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use subxt::Metadata;
+use subxt::blocks::ExtrinsicDetails;
+use subxt::events::Phase;
+use subxt::ext::scale_value::{Composite, ValueDef};
+use subxt::utils::MultiAddress;
+use subxt::{OnlineClient, SubstrateConfig};
+
+use super::{FinalizedBlock, IndexerError, block_timestamp};
+use crate::format_ss58;
+use crate::state::AppState;
+use crate::substrate::allfeat;
+use crate::substrate::allfeat::runtime_types::pallet_sudo::pallet::{
+    Call as SudoCall, Event as SudoEvent,
+};
+use crate::substrate::allfeat::runtime_types::sp_runtime::{DispatchError, ModuleError};
+use crate::{SudoActivity, SudoActivityKind};
+
+/// Upper bound on retained entries.
+const MAX_ENTRIES: usize = 10_000;
+
+/// Decoded call arguments longer than this are cut (e.g. `set_code` blobs).
+const MAX_ARGS_LEN: usize = 512;
+
+/// Number of entries exposed in the Atom feed.
+const FEED_ENTRIES: usize = 50;
+
+/// Sudo activity indexed since startup.
+#[derive(Debug, Default)]
+pub struct SudoIndex {
+    /// Newest first
+    pub entries: Vec<SudoActivity>,
+    /// Oldest block covered by the entries, sudo activity or not
+    pub indexed_since: Option<u32>,
+}
+
+impl SudoIndex {
+    fn insert(&mut self, block: u32, entries: Vec<SudoActivity>) {
+        for entry in entries {
+            let key = (entry.block, entry.event_index);
+            // Newest first; backfill and live indexing may overlap
+            match self
+                .entries
+                .binary_search_by(|e| key.cmp(&(e.block, e.event_index)))
+            {
+                Ok(_) => {}
+                Err(pos) => self.entries.insert(pos, entry),
+            }
+        }
+        self.indexed_since = Some(self.indexed_since.map_or(block, |since| since.min(block)));
+
+        if self.entries.len() > MAX_ENTRIES {
+            let dropped = self.entries.split_off(MAX_ENTRIES);
+            // Older blocks may have had activity that is no longer retained,
+            // and a block is only covered with all of its activity
+            if let Some(partial) = dropped.first().map(|e| e.block) {
+                self.entries.retain(|e| e.block != partial);
+                self.indexed_since = Some(self.entries.last().map_or(partial + 1, |e| e.block));
+            }
+        }
+    }
+}
+
+pub async fn index_block(state: &AppState, block: &FinalizedBlock) -> Result<(), IndexerError> {
+    let events = block.events().await?;
+
+    let mut sudo_events = Vec::new();
+    for event in events.iter() {
+        let event = event?;
+        if event.pallet_name() == "Sudo" {
+            sudo_events.push(event);
+        }
+    }
+
+    if sudo_events.is_empty() {
+        state.sudo_log.write().await.insert(block.number(), vec![]);
+        return Ok(());
+    }
+
+    let extrinsics = block.extrinsics().await?;
+    let timestamp = block_timestamp(block).await?;
+    let metadata = state.chain.client()?.metadata();
+
+    let mut entries = Vec::with_capacity(sudo_events.len());
+
+    for event in sudo_events {
+        let allfeat::Event::Sudo(sudo_event) = event.as_root_event::<allfeat::Event>()? else {
+            continue;
+        };
+
+        let extrinsic = match event.phase() {
+            Phase::ApplyExtrinsic(idx) => extrinsics.iter().nth(idx as usize),
+            _ => None,
+        };
+
+        let (call, call_args, sudo_as) = extrinsic.as_ref().map(describe_call).unwrap_or_default();
+
+        let (kind, account, result) = match sudo_event {
+            SudoEvent::Sudid { sudo_result } => (
+                SudoActivityKind::Sudid,
+                None,
+                Some(describe_result(sudo_result, &metadata)),
+            ),
+            SudoEvent::SudoAsDone { sudo_result } => (
+                SudoActivityKind::SudoAsDone,
+                sudo_as,
+                Some(describe_result(sudo_result, &metadata)),
+            ),
+            SudoEvent::KeyChanged { new, .. } => {
+                (SudoActivityKind::KeyChanged, Some(format_ss58(&new)), None)
+            }
+            SudoEvent::KeyRemoved => (SudoActivityKind::KeyRemoved, None, None),
+        };
+
+        entries.push(SudoActivity {
+            block: block.number(),
+            block_hash: format!("{:?}", block.hash()),
+            event_index: event.index(),
+            timestamp,
+            kind,
+            call,
+            call_args,
+            account,
+            result,
+        });
+    }
+
+    state.sudo_log.write().await.insert(block.number(), entries);
+
+    Ok(())
+}
+
+/// Returns the dispatched call name and arguments, plus the `sudo_as`
+/// target. Calls wrapped in `Sudo::*` are unwrapped to the inner call;
+/// anything else (utility batches, proxies...) is described as-is.
+fn describe_call(
+    extrinsic: &ExtrinsicDetails<SubstrateConfig, OnlineClient<SubstrateConfig>>,
+) -> (Option<String>, Option<String>, Option<String>) {
+    let (Ok(pallet), Ok(variant)) = (extrinsic.pallet_name(), extrinsic.variant_name()) else {
+        return (None, None, None);
+    };
+    let Ok(fields) = extrinsic.field_values() else {
+        return (Some(format!("{pallet}.{variant}")), None, None);
+    };
+
+    let field = |name: &str| match &fields {
+        Composite::Named(values) => values.iter().find(|(n, _)| n == name).map(|(_, v)| v),
+        Composite::Unnamed(_) => None,
+    };
+
+    let inner = (pallet == "Sudo")
+        .then(|| field("call"))
+        .flatten()
+        .and_then(|call| match &call.value {
+            // RuntimeCall::<Pallet>(<Call>::<variant> { .. })
+            ValueDef::Variant(outer) => match outer.values.values().next().map(|v| &v.value) {
+                Some(ValueDef::Variant(call)) => Some((
+                    format!("{}.{}", outer.name, call.name),
+                    truncate_args(call.values.to_string()),
+                )),
+                _ => None,
+            },
+            _ => None,
+        });
+
+    let sudo_as = match extrinsic.as_root_extrinsic::<allfeat::Call>() {
+        Ok(allfeat::Call::Sudo(SudoCall::sudo_as {
+            who: MultiAddress::Id(who),
+            ..
+        })) => Some(format_ss58(&who)),
+        _ => None,
+    };
+
+    match inner {
+        Some((call, args)) => (Some(call), Some(args), sudo_as),
+        None => (
+            Some(format!("{pallet}.{variant}")),
+            Some(truncate_args(fields.to_string())),
+            sudo_as,
+        ),
+    }
+}
+
+fn describe_result(result: Result<(), DispatchError>, metadata: &Metadata) -> Result<(), String> {
+    result.map_err(|err| match err {
+        DispatchError::Module(ModuleError { index, error }) => metadata
+            .pallet_by_index(index)
+            .and_then(|pallet| {
+                pallet
+                    .error_variant_by_index(error[0])
+                    .map(|variant| format!("{}.{}", pallet.name(), variant.name))
+            })
+            .unwrap_or_else(|| format!("Module error {index}:{}", error[0])),
+        other => format!("{other:?}"),
+    })
+}
+
+fn truncate_args(mut args: String) -> String {
+    if args.len() > MAX_ARGS_LEN {
+        let mut cut = MAX_ARGS_LEN;
+        while !args.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        args.truncate(cut);
+        args.push('…');
+    }
+    args
+}
+
+/// Atom feed of the latest sudo activity, served at `/sudo/feed.xml`.
+pub async fn feed(State(state): State<AppState>) -> impl IntoResponse {
+    let log = state.sudo_log.read().await;
+
+    let updated = log
+        .entries
+        .first()
+        .map(|e| rfc3339(e.timestamp))
+        .unwrap_or_else(|| rfc3339(0));
+
+    let mut xml = format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+<title>Allfeat • Sudo Activity</title>
+<id>urn:allfeat:sudo</id>
+<link href="/sudo"/>
+<updated>{updated}</updated>
+"#
+    );
+
+    for entry in log.entries.iter().take(FEED_ENTRIES) {
+        let title = match (&entry.kind, &entry.call) {
+            (SudoActivityKind::KeyChanged, _) => "Sudo key changed".to_string(),
+            (SudoActivityKind::KeyRemoved, _) => "Sudo key removed".to_string(),
+            (kind, Some(call)) => format!("{} {call}", kind.label()),
+            (kind, None) => kind.label().to_string(),
+        };
+
+        let outcome = match &entry.result {
+            Some(Ok(())) => "Success".to_string(),
+            Some(Err(e)) => format!("Failed: {e}"),
+            None => String::new(),
+        };
+
+        let summary = [
+            Some(format!("Block #{}", entry.block)),
+            entry.account.as_ref().map(|a| format!("Account: {a}")),
+            entry.call_args.as_ref().map(|a| format!("Arguments: {a}")),
+            (!outcome.is_empty()).then_some(outcome),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n");
+
+        xml.push_str(&format!(
+            r#"<entry>
+<title>{}</title>
+<id>urn:allfeat:sudo:{}-{}</id>
+<link href="/sudo"/>
+<updated>{}</updated>
+<summary>{}</summary>
+</entry>
+"#,
+            xml_escape(&title),
+            entry.block,
+            entry.event_index,
+            rfc3339(entry.timestamp),
+            xml_escape(&summary),
+        ));
+    }
+
+    xml.push_str("</feed>\n");
+
+    (
+        [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+        xml,
+    )
+}
+
+fn rfc3339(timestamp_ms: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms as i64)
+        .unwrap_or_default()
+        .to_rfc3339()
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(block: u32, event_index: u32) -> SudoActivity {
+        SudoActivity {
+            block,
+            block_hash: String::new(),
+            event_index,
+            timestamp: 0,
+            kind: SudoActivityKind::Sudid,
+            call: None,
+            call_args: None,
+            account: None,
+            result: Some(Ok(())),
+        }
+    }
+
+    #[test]
+    fn truncation_moves_indexed_since_to_the_oldest_kept_block() {
+        let mut index = SudoIndex::default();
+        for block in 1..=MAX_ENTRIES as u32 {
+            index.insert(block, vec![activity(block, 0)]);
+        }
+        assert_eq!(index.indexed_since, Some(1));
+
+        index.insert(
+            MAX_ENTRIES as u32 + 1,
+            vec![activity(MAX_ENTRIES as u32 + 1, 0)],
+        );
+        assert_eq!(index.entries.len(), MAX_ENTRIES);
+        assert_eq!(index.indexed_since, Some(2));
+    }
+
+    #[test]
+    fn partly_dropped_blocks_are_not_covered() {
+        let mut index = SudoIndex::default();
+        index.insert(1, vec![activity(1, 0), activity(1, 1)]);
+        for block in 2..MAX_ENTRIES as u32 {
+            index.insert(block, vec![activity(block, 0)]);
+        }
+
+        // Block 1 loses one of its two entries, so the other is dropped too
+        index.insert(MAX_ENTRIES as u32, vec![activity(MAX_ENTRIES as u32, 0)]);
+        assert_eq!(index.indexed_since, Some(2));
+        assert!(index.entries.iter().all(|e| e.block >= 2));
+    }
+}
@@ -0,0 +1,539 @@
+//! Watchdog over the token allocation pallet.
+//!
+//! Every finalized block is checked for an overdue epoch payout
+//! (`NextPayoutAt` left behind by the chain) or one stuck in progress. The pallet invariants, no
+//! envelope distributing more than its `total_cap` and no allocation
+//! releasing more than its `vested_total`, require iterating storage and are
+//! checked every `tokenomics.check_interval` blocks.
+//!
+//! Raised and resolved alerts are posted as JSON to the webhooks listed in
+//! `tokenomics.webhooks`, and the current state is served at `/health/tokenomics`.
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use super::{FinalizedBlock, IndexerError};
+use crate::config::Secret;
+use crate::state::AppState;
+use crate::substrate;
+use crate::substrate::allfeat::token_allocation::storage::types::allocations::Allocations;
+use crate::{ENVELOPES, format_ss58};
+
+/// Blocks tolerated past `NextPayoutAt` before a payout is reported overdue,
+/// and of a payout in progress before it is reported stalled.
+const DEFAULT_PAYOUT_GRACE_BLOCKS: u32 = 10;
+
+/// Blocks between two runs of the storage invariant checks.
+const DEFAULT_CHECK_INTERVAL: u32 = 100;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TokenomicsAlert {
+    PayoutOverdue {
+        next_payout_at: u32,
+        current_block: u32,
+    },
+    PayoutStalled {
+        started_at: u32,
+        current_block: u32,
+    },
+    EnvelopeOverDistributed {
+        envelope: String,
+        total_cap: u128,
+        distributed: u128,
+    },
+    AllocationOverReleased {
+        id: u32,
+        beneficiary: String,
+        vested_total: u128,
+        released: u128,
+    },
+}
+
+impl TokenomicsAlert {
+    /// Identifies an alert across checks, regardless of the values it reports.
+    fn key(&self) -> String {
+        match self {
+            Self::PayoutOverdue { next_payout_at, .. } => {
+                format!("payout_overdue:{next_payout_at}")
+            }
+            Self::PayoutStalled { started_at, .. } => format!("payout_stalled:{started_at}"),
+            Self::EnvelopeOverDistributed { envelope, .. } => {
+                format!("envelope_over_distributed:{envelope}")
+            }
+            Self::AllocationOverReleased { id, .. } => format!("allocation_over_released:{id}"),
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        match self {
+            Self::PayoutOverdue {
+                next_payout_at,
+                current_block,
+            } => format!(
+                "Epoch payout overdue: expected at #{next_payout_at}, chain is at #{current_block}"
+            ),
+            Self::PayoutStalled {
+                started_at,
+                current_block,
+            } => format!(
+                "Epoch payout stalled: in progress since #{started_at}, chain is at #{current_block}"
+            ),
+            Self::EnvelopeOverDistributed {
+                envelope,
+                total_cap,
+                distributed,
+            } => {
+                format!("Envelope {envelope} distributed {distributed} over its cap of {total_cap}")
+            }
+            Self::AllocationOverReleased {
+                id,
+                vested_total,
+                released,
+                ..
+            } => format!(
+                "Allocation {id} released {released} over its vested total of {vested_total}"
+            ),
+        }
+    }
+}
+
+/// Distribution state of an envelope at a given block.
+#[derive(Clone, Debug)]
+pub struct EnvelopeSnapshot {
+    pub envelope: String,
+    pub total_cap: u128,
+    pub distributed: u128,
+}
+
+/// Release state of an allocation at a given block.
+#[derive(Clone, Debug)]
+pub struct AllocationSnapshot {
+    pub id: u32,
+    pub beneficiary: String,
+    pub vested_total: u128,
+    pub released: u128,
+}
+
+/// `next_payout_at` is `None` (or 0) when no payout is scheduled, so it
+/// isn't overdue. A payout in progress, seen first at `payout_started_at`, is
+/// spread over several blocks and only reported once it outlasts the grace
+/// period.
+pub fn check_payout_schedule(
+    next_payout_at: Option<u32>,
+    payout_started_at: Option<u32>,
+    current_block: u32,
+    grace_blocks: u32,
+) -> Option<TokenomicsAlert> {
+    if let Some(started_at) = payout_started_at {
+        return (current_block > started_at.saturating_add(grace_blocks)).then_some(
+            TokenomicsAlert::PayoutStalled {
+                started_at,
+                current_block,
+            },
+        );
+    }
+    let next_payout_at = next_payout_at.filter(|at| *at > 0)?;
+
+    (current_block > next_payout_at.saturating_add(grace_blocks)).then_some(
+        TokenomicsAlert::PayoutOverdue {
+            next_payout_at,
+            current_block,
+        },
+    )
+}
+
+pub fn check_envelopes(envelopes: &[EnvelopeSnapshot]) -> Vec<TokenomicsAlert> {
+    envelopes
+        .iter()
+        .filter(|e| e.distributed > e.total_cap)
+        .map(|e| TokenomicsAlert::EnvelopeOverDistributed {
+            envelope: e.envelope.clone(),
+            total_cap: e.total_cap,
+            distributed: e.distributed,
+        })
+        .collect()
+}
+
+pub fn check_allocations(allocations: &[AllocationSnapshot]) -> Vec<TokenomicsAlert> {
+    allocations
+        .iter()
+        .filter(|a| a.released > a.vested_total)
+        .map(|a| TokenomicsAlert::AllocationOverReleased {
+            id: a.id,
+            beneficiary: a.beneficiary.clone(),
+            vested_total: a.vested_total,
+            released: a.released,
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertConfig {
+    #[serde(skip_serializing)]
+    pub webhooks: Vec<Secret>,
+    pub payout_grace_blocks: u32,
+    pub check_interval: u32,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            webhooks: vec![],
+            payout_grace_blocks: DEFAULT_PAYOUT_GRACE_BLOCKS,
+            check_interval: DEFAULT_CHECK_INTERVAL,
+        }
+    }
+}
+
+/// Outcome of the latest checks, as served by `/health/tokenomics`.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TokenomicsReport {
+    /// Last block checked for an overdue payout
+    pub checked_block: Option<u32>,
+    /// Last block the storage invariants were checked at
+    pub invariants_block: Option<u32>,
+    pub next_payout_at: Option<u32>,
+    /// First block the payout in progress was seen at
+    pub payout_started_at: Option<u32>,
+    pub alerts: Vec<TokenomicsAlert>,
+}
+
+#[derive(Debug)]
+pub struct TokenomicsMonitor {
+    pub config: AlertConfig,
+    pub report: RwLock<TokenomicsReport>,
+    http: reqwest::Client,
+}
+
+impl TokenomicsMonitor {
+    pub fn new(config: AlertConfig) -> Self {
+        Self {
+            config,
+            report: Default::default(),
+            http: reqwest::Client::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    status: &'static str,
+    block: u32,
+    summary: String,
+    alert: &'a TokenomicsAlert,
+}
+
+pub async fn check_block(state: &AppState, block: &FinalizedBlock) -> Result<(), IndexerError> {
+    let monitor = &state.tokenomics;
+    let number = block.number();
+    let storage = block.storage();
+    let queries = substrate::allfeat::storage().token_allocation();
+
+    let next_payout_at_query = queries.next_payout_at();
+    let payout_cursor_query = queries.payout_cursor();
+    let (next_payout_at, payout_cursor) = tokio::try_join!(
+        storage.fetch(&next_payout_at_query),
+        storage.fetch(&payout_cursor_query),
+    )?;
+    let next_payout_at = next_payout_at.filter(|at| *at > 0);
+    let payout_started_at = match payout_cursor {
+        Some(_) => Some(
+            monitor
+                .report
+                .read()
+                .await
+                .payout_started_at
+                .unwrap_or(number),
+        ),
+        None => None,
+    };
+    let overdue = check_payout_schedule(
+        next_payout_at,
+        payout_started_at,
+        number,
+        monitor.config.payout_grace_blocks,
+    );
+
+    let last_invariants = monitor.report.read().await.invariants_block;
+    let invariants = match last_invariants {
+        Some(at) if number < at.saturating_add(monitor.config.check_interval) => None,
+        _ => Some(check_invariants(block).await?),
+    };
+
+    let (raised, resolved) = {
+        let mut report = monitor.report.write().await;
+        let previous = std::mem::take(&mut report.alerts);
+
+        let mut alerts: Vec<TokenomicsAlert> = overdue.into_iter().collect();
+        match invariants {
+            Some(found) => {
+                alerts.extend(found);
+                report.invariants_block = Some(number);
+            }
+            // Keep the invariant alerts until the next run
+            None => alerts.extend(
+                previous
+                    .iter()
+                    .filter(|a| {
+                        !matches!(
+                            a,
+                            TokenomicsAlert::PayoutOverdue { .. }
+                                | TokenomicsAlert::PayoutStalled { .. }
+                        )
+                    })
+                    .cloned(),
+            ),
+        }
+
+        let raised: Vec<TokenomicsAlert> = alerts
+            .iter()
+            .filter(|a| previous.iter().all(|p| p.key() != a.key()))
+            .cloned()
+            .collect();
+        let resolved: Vec<TokenomicsAlert> = previous
+            .into_iter()
+            .filter(|p| alerts.iter().all(|a| a.key() != p.key()))
+            .collect();
+
+        report.checked_block = Some(number);
+        report.next_payout_at = next_payout_at;
+        report.payout_started_at = payout_started_at;
+        report.alerts = alerts;
+
+        (raised, resolved)
+    };
+
+    for alert in &raised {
+        warn!(target: "tokenomics", "{}", alert.summary());
+    }
+    for alert in &resolved {
+        info!(target: "tokenomics", "Resolved: {}", alert.summary());
+    }
+
+    if !monitor.config.webhooks.is_empty() && !(raised.is_empty() && resolved.is_empty()) {
+        let state = state.clone();
+        tokio::spawn(async move {
+            let events = raised
+                .iter()
+                .map(|a| ("firing", a))
+                .chain(resolved.iter().map(|a| ("resolved", a)));
+            for (status, alert) in events {
+                notify(&state.tokenomics, status, number, alert).await;
+            }
+        });
+    }
+
+    Ok(())
+}
+
+async fn check_invariants(block: &FinalizedBlock) -> Result<Vec<TokenomicsAlert>, IndexerError> {
+    let storage = block.storage();
+    let queries = substrate::allfeat::storage().token_allocation();
+
+    let mut envelopes = Vec::with_capacity(ENVELOPES.len());
+    for (id, name) in ENVELOPES {
+        let config_query = queries.envelopes(id.clone());
+        let distributed_query = queries.envelope_distributed(id.clone());
+        let (config, distributed) = tokio::try_join!(
+            storage.fetch(&config_query),
+            storage.fetch_or_default(&distributed_query),
+        )?;
+        if let Some(config) = config {
+            envelopes.push(EnvelopeSnapshot {
+                envelope: name.to_string(),
+                total_cap: config.total_cap,
+                distributed,
+            });
+        }
+    }
+
+    // Iterated dynamically: the static address doesn't decode map keys
+    let allocations_query = subxt::dynamic::storage(
+        "TokenAllocation",
+        "Allocations",
+        Vec::<subxt::dynamic::Value>::new(),
+    );
+    let mut iter = storage.iter(allocations_query).await?;
+    let mut allocations = Vec::new();
+    while let Some(kv) = iter.next().await {
+        let kv = kv?;
+        let Some(id) = kv.keys.first().and_then(|k| k.as_u128()) else {
+            continue;
+        };
+        let alloc = kv.value.as_type::<Allocations>()?;
+        allocations.push(AllocationSnapshot {
+            id: id as u32,
+            beneficiary: format_ss58(&alloc.beneficiary),
+            vested_total: alloc.vested_total,
+            released: alloc.released,
+        });
+    }
+
+    let mut alerts = check_envelopes(&envelopes);
+    alerts.extend(check_allocations(&allocations));
+    Ok(alerts)
+}
+
+async fn notify(
+    monitor: &TokenomicsMonitor,
+    status: &'static str,
+    block: u32,
+    alert: &TokenomicsAlert,
+) {
+    let payload = WebhookPayload {
+        status,
+        block,
+        summary: alert.summary(),
+        alert,
+    };
+
+    for (i, url) in monitor.config.webhooks.iter().enumerate() {
+        let res = monitor
+            .http
+            .post(&url.0)
+            .json(&payload)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+        // The URL is a secret, log its position in the list instead
+        if let Err(e) = res {
+            warn!(target: "tokenomics", "Webhook #{i} failed: {}", e.without_url());
+        }
+    }
+}
+
+/// Served at `/health/tokenomics`. Answers 503 while an alert is active.
+pub async fn health(State(state): State<AppState>) -> impl IntoResponse {
+    let report = state.tokenomics.report.read().await.clone();
+
+    let status = if report.alerts.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope(name: &str, total_cap: u128, distributed: u128) -> EnvelopeSnapshot {
+        EnvelopeSnapshot {
+            envelope: name.to_string(),
+            total_cap,
+            distributed,
+        }
+    }
+
+    fn allocation(id: u32, vested_total: u128, released: u128) -> AllocationSnapshot {
+        AllocationSnapshot {
+            id,
+            beneficiary: format!("beneficiary-{id}"),
+            vested_total,
+            released,
+        }
+    }
+
+    #[test]
+    fn envelopes_within_cap_pass() {
+        let fixture = [
+            envelope("Teams", 1_000, 0),
+            envelope("Airdrop", 1_000, 999),
+            envelope("Reserve", 1_000, 1_000),
+        ];
+
+        assert!(check_envelopes(&fixture).is_empty());
+    }
+
+    #[test]
+    fn envelope_over_cap_is_reported() {
+        let fixture = [
+            envelope("Teams", 1_000, 500),
+            envelope("Airdrop", 1_000, 1_001),
+            envelope("Listing", 0, 1),
+        ];
+
+        assert_eq!(
+            check_envelopes(&fixture),
+            vec![
+                TokenomicsAlert::EnvelopeOverDistributed {
+                    envelope: "Airdrop".to_string(),
+                    total_cap: 1_000,
+                    distributed: 1_001,
+                },
+                TokenomicsAlert::EnvelopeOverDistributed {
+                    envelope: "Listing".to_string(),
+                    total_cap: 0,
+                    distributed: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn allocations_within_vested_total_pass() {
+        let fixture = [
+            allocation(0, 500, 0),
+            allocation(1, 500, 250),
+            allocation(2, 500, 500),
+        ];
+
+        assert!(check_allocations(&fixture).is_empty());
+    }
+
+    #[test]
+    fn allocation_over_vested_total_is_reported() {
+        let fixture = [allocation(0, 500, 500), allocation(7, 500, 501)];
+
+        assert_eq!(
+            check_allocations(&fixture),
+            vec![TokenomicsAlert::AllocationOverReleased {
+                id: 7,
+                beneficiary: "beneficiary-7".to_string(),
+                vested_total: 500,
+                released: 501,
+            }]
+        );
+    }
+
+    #[test]
+    fn payout_overdue_after_grace_period() {
+        assert_eq!(check_payout_schedule(Some(100), None, 100, 10), None);
+        assert_eq!(check_payout_schedule(Some(100), None, 110, 10), None);
+        assert_eq!(
+            check_payout_schedule(Some(100), None, 111, 10),
+            Some(TokenomicsAlert::PayoutOverdue {
+                next_payout_at: 100,
+                current_block: 111,
+            })
+        );
+    }
+
+    #[test]
+    fn unscheduled_or_running_payouts_are_not_overdue() {
+        assert_eq!(check_payout_schedule(None, None, 1_000, 10), None);
+        assert_eq!(check_payout_schedule(Some(0), None, 1_000, 10), None);
+        assert_eq!(check_payout_schedule(Some(100), Some(995), 1_000, 10), None);
+    }
+
+    #[test]
+    fn payout_stalled_after_grace_period() {
+        assert_eq!(check_payout_schedule(Some(100), Some(990), 1_000, 10), None);
+        assert_eq!(
+            check_payout_schedule(Some(100), Some(989), 1_000, 10),
+            Some(TokenomicsAlert::PayoutStalled {
+                started_at: 989,
+                current_block: 1_000,
+            })
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use leptos::server_fn::codec::Json;
 use leptos::server_fn::codec::StreamingText;
 use leptos::server_fn::codec::TextStream;
 #[cfg(feature = "ssr")]
@@ -9,25 +10,42 @@ use leptos::server;
 use serde::Deserialize;
 use serde::Serialize;
 
+#[cfg(feature = "ssr")]
+pub mod indexer;
+#[cfg(feature = "ssr")]
+pub mod rate_limit;
 #[cfg(feature = "ssr")]
 pub mod state;
 #[cfg(feature = "ssr")]
 pub mod substrate;
+#[cfg(feature = "ssr")]
+pub mod telemetry;
 
+pub mod address_book;
 pub mod app;
 pub mod components;
+pub mod config;
+pub mod error;
+#[cfg(feature = "faucet")]
+pub mod faucet;
 mod pages;
+pub mod ss58;
+pub mod supply;
 pub mod utils;
 
+pub use error::AppError;
+
 #[cfg(feature = "hydrate")]
 #[wasm_bindgen::prelude::wasm_bindgen]
 pub fn hydrate() {
     use crate::app::*;
     console_error_panic_hook::set_once();
+    address_book::load_from_document();
+    config::load_from_document();
     leptos::mount::hydrate_body(App);
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Balances {
     pub free: u128,
     pub reserved: u128,
@@ -56,63 +74,551 @@ pub struct Allocation {
     pub start: u32,
 }
 
-#[server(output = StreamingText)]
-pub async fn get_block_number_stream() -> Result<TextStream, ServerFnError> {
-    use futures::StreamExt;
-    use tracing::error;
+/// Outcome for one account of a batched query. An error only affects the
+/// account it belongs to, the rest of the batch is still returned.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AccountResult<T> {
+    /// Account as given in the request
+    pub id: String,
+    pub result: Result<T, AppError>,
+}
 
-    let chain_api = get_chain_api().await?;
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HolderRanking {
+    /// Free and reserved balance
+    Total,
+    /// Free balance not frozen by locks or holds
+    Transferable,
+}
 
-    let blocks_sub = chain_api
-        .blocks()
-        .subscribe_finalized()
-        .await
-        .map_err(|e| ServerFnError::new(format!("Failed to subscribe to blocks: {e}")))?;
+impl HolderRanking {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Total => "Total",
+            Self::Transferable => "Transferable",
+        }
+    }
+
+    pub fn amount(&self, free: u128, reserved: u128, frozen: u128) -> u128 {
+        match self {
+            Self::Total => free.saturating_add(reserved),
+            Self::Transferable => free.saturating_sub(frozen),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TopHolder {
+    pub rank: usize,
+    pub address: String,
+    pub free: u128,
+    pub reserved: u128,
+    pub frozen: u128,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DistributionStats {
+    /// Accounts with a non-zero balance
+    pub holders: usize,
+    pub gini: f64,
+    /// Percentage of the total issuance held by the 10 largest accounts
+    pub top10_share: f64,
+    pub top100_share: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TopHoldersPage {
+    /// Block the balances were read at
+    pub block: u32,
+    pub total_issuance: u128,
+    pub stats: DistributionStats,
+    pub items: Vec<TopHolder>,
+    pub total: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnvelopeSupply {
+    pub envelope: String,
+    /// Teams or Reserve envelope, see [`supply::is_insider`]
+    pub insider: bool,
+    pub total_cap: u128,
+    /// `EnvelopeDistributed` of the envelope
+    pub distributed: u128,
+    /// Vested but not yet released, `vested_total - released` over the
+    /// envelope's allocations
+    pub locked_in_vesting: u128,
+}
+
+impl EnvelopeSupply {
+    pub fn undistributed(&self) -> u128 {
+        self.total_cap.saturating_sub(self.distributed)
+    }
+}
+
+/// Components of the circulating supply, all read at the same block.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SupplyBreakdown {
+    pub block: u32,
+    pub total_issuance: u128,
+    pub envelopes: Vec<EnvelopeSupply>,
+    /// Free balance of the treasury account
+    pub treasury: u128,
+    /// Reserved balances of every account, from the latest holder snapshot
+    pub reserved: Option<u128>,
+    /// Frozen balances of every account, from the latest holder snapshot
+    pub frozen: Option<u128>,
+    /// Block of the holder snapshot `reserved` and `frozen` come from
+    pub holders_at: Option<u32>,
+    /// Methodology `get_circulating_supply` uses when none is requested
+    pub methodology: supply::SupplyMethodology,
+}
+
+impl SupplyBreakdown {
+    pub fn distributed(&self) -> u128 {
+        self.envelopes.iter().map(|e| e.distributed).sum()
+    }
+
+    pub fn locked_in_vesting(&self) -> u128 {
+        self.envelopes.iter().map(|e| e.locked_in_vesting).sum()
+    }
+
+    pub fn undistributed(&self) -> u128 {
+        self.envelopes.iter().map(|e| e.undistributed()).sum()
+    }
+
+    /// Distributed tokens not locked in vesting, the `Standard` methodology.
+    pub fn circulating(&self) -> u128 {
+        self.distributed().saturating_sub(self.locked_in_vesting())
+    }
+
+    /// Issued tokens not accounted for by any envelope cap.
+    pub fn outside_envelopes(&self) -> u128 {
+        self.total_issuance
+            .saturating_sub(self.distributed())
+            .saturating_sub(self.undistributed())
+    }
+}
+
+/// Value a page can subscribe to with `subscribe_metrics`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum MetricKey {
+    TotalIssuance,
+    /// Under the configured methodology
+    CirculatingSupply,
+    /// Balances of an account
+    Balances(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MetricValue {
+    Amount(u128),
+    Balances(Balances),
+}
+
+impl MetricValue {
+    pub fn amount(&self) -> Option<u128> {
+        match self {
+            Self::Amount(amount) => Some(*amount),
+            _ => None,
+        }
+    }
+
+    pub fn balances(&self) -> Option<&Balances> {
+        match self {
+            Self::Balances(balances) => Some(balances),
+            _ => None,
+        }
+    }
+}
+
+/// Pushed when a finalized block changes a subscribed value.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MetricUpdate {
+    pub key: MetricKey,
+    pub block: u32,
+    pub value: MetricValue,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PayoutStatus {
+    pub current_block: u32,
+    pub next_payout_at: u32,
+    pub epoch_index: u64,
+    pub epoch_duration: u32,
+    /// Next allocation to pay while a payout is batched over several blocks
+    pub payout_cursor: Option<u32>,
+    pub total_allocations: u32,
+    pub max_payouts_per_block: u32,
+}
+
+impl PayoutStatus {
+    /// Whether an epoch payout is currently being processed in batches
+    pub fn in_progress(&self) -> bool {
+        self.payout_cursor.is_some()
+    }
+
+    pub fn blocks_until_next(&self) -> u32 {
+        self.next_payout_at.saturating_sub(self.current_block)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnvelopePayout {
+    pub envelope: String,
+    pub amount: u128,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EpochPayoutSummary {
+    pub epoch: u64,
+    pub first_block: u32,
+    pub last_block: u32,
+    /// Timestamp of the first payout block in milliseconds
+    pub timestamp: u64,
+    pub completed: bool,
+    /// Number of allocations released during the epoch payout
+    pub allocations: u32,
+    pub total: u128,
+    pub per_envelope: Vec<EnvelopePayout>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PayoutHistoryPage {
+    /// Most recent epochs first
+    pub epochs: Vec<EpochPayoutSummary>,
+    /// First block covered by the history
+    pub indexed_since: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SudoActivityKind {
+    Sudid,
+    SudoAsDone,
+    KeyChanged,
+    KeyRemoved,
+}
 
-    let stream = blocks_sub.map(|block_result| match block_result {
-        Ok(block) => {
-            let num = block.header().number;
-            Ok(num.to_string())
+impl SudoActivityKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SudoActivityKind::Sudid => "Sudo",
+            SudoActivityKind::SudoAsDone => "Sudo As",
+            SudoActivityKind::KeyChanged => "Key Changed",
+            SudoActivityKind::KeyRemoved => "Key Removed",
         }
-        Err(err) => {
-            error!("RPC Error: {err}");
-            Ok("Error".to_string())
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SudoActivity {
+    pub block: u32,
+    pub block_hash: String,
+    pub event_index: u32,
+    /// Block timestamp in milliseconds
+    pub timestamp: u64,
+    pub kind: SudoActivityKind,
+    /// Dispatched call as `Pallet.call`, unwrapped from the `Sudo::*` extrinsic
+    pub call: Option<String>,
+    pub call_args: Option<String>,
+    /// `sudo_as` origin or the new key on `KeyChanged`
+    pub account: Option<String>,
+    /// Dispatch outcome, `None` for key changes
+    pub result: Option<Result<(), String>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SudoActivityPage {
+    pub items: Vec<SudoActivity>,
+    pub total: usize,
+    /// First block covered by the log
+    pub indexed_since: Option<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FeeBucket {
+    pub from_block: u32,
+    pub to_block: u32,
+    /// Timestamp of the first fee-paying block in the bucket, in milliseconds
+    pub timestamp: u64,
+    pub paid: u128,
+    pub burned: u128,
+    pub to_treasury: u128,
+    pub tips: u128,
+    pub extrinsics: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CallFeeStats {
+    /// Extrinsic type as `Pallet.call`
+    pub call: String,
+    pub count: u64,
+    pub total: u128,
+    pub average: u128,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FeeAnalytics {
+    /// `NextFeeMultiplier` as a plain ratio (1.0 = no adjustment)
+    pub next_fee_multiplier: f64,
+    /// First block covered by the aggregates
+    pub indexed_since: Option<u32>,
+    pub buckets: Vec<FeeBucket>,
+    pub per_call: Vec<CallFeeStats>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FeeEstimate {
+    pub partial_fee: u128,
+    pub class: String,
+    pub ref_time: u64,
+    pub proof_size: u64,
+    pub length: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchKind {
+    Account,
+    Block,
+    Extrinsic,
+}
+
+impl SearchKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Account => "Account",
+            Self::Block => "Block",
+            Self::Extrinsic => "Extrinsic",
         }
-    });
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SearchSuggestion {
+    pub kind: SearchKind,
+    /// Known name, canonical address or block number
+    pub label: String,
+    /// Secondary text, e.g. the address behind a known name
+    pub detail: Option<String>,
+    pub href: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExtrinsicSummary {
+    pub index: u32,
+    pub hash: String,
+    /// Called function as `Pallet.call`
+    pub call: String,
+    pub signer: Option<String>,
+    /// `None` when no `ExtrinsicSuccess`/`ExtrinsicFailed` event was found
+    pub success: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BlockDetails {
+    pub number: u32,
+    pub hash: String,
+    pub parent_hash: String,
+    /// Milliseconds since the UNIX epoch
+    pub timestamp: u64,
+    pub event_count: u32,
+    pub extrinsics: Vec<ExtrinsicSummary>,
+}
+
+/// Finalized block as shown by the live views.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockSummary {
+    pub number: u32,
+    pub hash: String,
+    /// Milliseconds since the UNIX epoch
+    pub timestamp: u64,
+    pub extrinsic_count: u32,
+    pub event_count: u32,
+    /// Collator, when it could be resolved from the digest
+    pub author: Option<String>,
+    /// Blocks between the best block and this one when it was finalized
+    pub finality_lag: u32,
+}
+
+/// `Balances::Transfer` event of a finalized block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransferSummary {
+    pub block: u32,
+    /// Index of the event in the block
+    pub event_index: u32,
+    pub from: String,
+    pub to: String,
+    pub amount: u128,
+    /// Milliseconds since the UNIX epoch
+    pub timestamp: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BlockStreamEvent {
+    Block(BlockSummary),
+    /// Transfers of the block sent just before, oldest first
+    Transfers {
+        transfers: Vec<TransferSummary>,
+    },
+    /// The block was finalized but could not be read
+    Error {
+        number: u32,
+        message: String,
+    },
+    /// The node connection was lost, the explorer is resubscribing
+    Reconnecting {
+        reason: String,
+    },
+}
+
+/// Streams every finalized block, its transfers and the state of the node
+/// connection, one JSON-encoded `BlockStreamEvent` per line. The recent
+/// blocks and transfers are sent first.
+#[server(output = StreamingText)]
+pub async fn get_block_stream() -> Result<TextStream, ServerFnError> {
+    use tokio::sync::broadcast::error::RecvError;
+
+    let state = expect_context::<AppState>();
+    let (backlog, events) = state.block_feed.subscribe();
+
+    let stream =
+        futures::stream::unfold((backlog, events), |(mut backlog, mut events)| async move {
+            let event = match backlog.pop_front() {
+                Some(event) => event,
+                None => loop {
+                    match events.recv().await {
+                        Ok(event) => break event,
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return None,
+                    }
+                },
+            };
+
+            let line = serde_json::to_string(&event)
+                .map(|json| json + "\n")
+                .map_err(|e| ServerFnError::new(e.to_string()));
+            Some((line, (backlog, events)))
+        });
+
+    Ok(TextStream::new(stream))
+}
+
+/// Streams the subscribed values whenever a finalized block changes them.
+/// Each line is a JSON array of `MetricUpdate`, the first one carrying every
+/// value.
+#[server(input = Json, output = StreamingText)]
+pub async fn subscribe_metrics(keys: Vec<MetricKey>) -> Result<TextStream, ServerFnError> {
+    use std::collections::HashMap;
+    use tokio::sync::broadcast::error::RecvError;
+    use tracing::warn;
+
+    use crate::utils::decode_hex;
+
+    const MAX_KEYS: usize = 32;
+
+    if keys.len() > MAX_KEYS {
+        return Err(ServerFnError::new(format!(
+            "At most {MAX_KEYS} metrics can be subscribed at once"
+        )));
+    }
+
+    let state = expect_context::<AppState>();
+    let (_, heads) = state.block_feed.subscribe();
+    let last: HashMap<MetricKey, MetricValue> = HashMap::new();
+
+    let stream =
+        futures::stream::unfold((state, heads, last), move |(state, mut heads, mut last)| {
+            let keys = keys.clone();
+            async move {
+                loop {
+                    let summary = match heads.recv().await {
+                        Ok(BlockStreamEvent::Block(summary)) => summary,
+                        // Values are read at the block they are labelled with,
+                        // so blocks that couldn't be read are skipped
+                        Ok(
+                            BlockStreamEvent::Error { .. }
+                            | BlockStreamEvent::Transfers { .. }
+                            | BlockStreamEvent::Reconnecting { .. },
+                        ) => continue,
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return None,
+                    };
+                    let Some(hash) = decode_hex(&summary.hash)
+                        .filter(|b| b.len() == 32)
+                        .map(|b| H256::from_slice(&b))
+                    else {
+                        continue;
+                    };
+                    let block = BlockAt {
+                        number: summary.number,
+                        hash,
+                    };
+
+                    let mut updates = vec![];
+                    for key in &keys {
+                        match read_metric(&state, key, block).await {
+                            Ok(value) if last.get(key) != Some(&value) => {
+                                last.insert(key.clone(), value.clone());
+                                updates.push(MetricUpdate {
+                                    key: key.clone(),
+                                    block: block.number,
+                                    value,
+                                });
+                            }
+                            Ok(_) => {}
+                            Err(e) => warn!("Failed to read {key:?} at #{}: {e}", block.number),
+                        }
+                    }
+
+                    if !updates.is_empty() {
+                        let line = serde_json::to_string(&updates)
+                            .map(|json| json + "\n")
+                            .map_err(|e| ServerFnError::new(e.to_string()));
+                        return Some((line, (state, heads, last)));
+                    }
+                }
+            }
+        });
 
     Ok(TextStream::new(stream))
 }
 
 #[server]
-pub async fn get_allocations() -> Result<Vec<EnvelopeAllocation>, ServerFnError> {
+pub async fn get_allocations() -> Result<Vec<EnvelopeAllocation>, AppError> {
     use futures::future::try_join_all;
     use std::time::Duration;
 
-    const CACHE_TTL: Duration = Duration::from_secs(300); // 5 minutes
-
     let state = expect_context::<AppState>();
+    let cache_ttl = Duration::from_secs(state.config.cache.allocations_ttl_secs);
 
     // Check cache first
     {
         let cache = state.allocations_cache.read().await;
-        if let Some(cached) = &*cache {
-            if cached.cached_at.elapsed() < CACHE_TTL {
-                return Ok(cached.data.clone());
-            }
+        if let Some(cached) = &*cache
+            && cached.cached_at.elapsed() < cache_ttl
+        {
+            state.telemetry.allocations_cache.hit();
+            return Ok(cached.data.clone());
         }
     }
+    state.telemetry.allocations_cache.miss();
 
     // Cache miss or expired - fetch fresh data
-    let chain_api = get_chain_api().await?;
-
-    // Get block reference once and reuse for all queries (batching optimization)
-    let block = chain_api.blocks().at_latest().await?;
-    let block_ref = block.reference();
+    let backend = state.backend.as_ref();
+    let at = match backend.latest_block().await {
+        Ok(block) => block.hash,
+        // Outdated allocations beat none while the chain is unreachable
+        Err(e @ AppError::RpcUnavailable(_)) => {
+            let cache = state.allocations_cache.read().await;
+            return cache.as_ref().map(|c| c.data.clone()).ok_or(e);
+        }
+        Err(e) => return Err(e),
+    };
 
     // Parallelize all envelope queries using centralized ENVELOPES config
     let futures = ENVELOPES
         .iter()
-        .map(|(id, name)| get_alloc_config_of(&chain_api, &block_ref, id, name));
+        .map(|(id, name)| get_alloc_config_of(backend, at, id, name));
 
     let results = try_join_all(futures).await?;
 
@@ -129,141 +635,400 @@ pub async fn get_allocations() -> Result<Vec<EnvelopeAllocation>, ServerFnError>
 }
 
 #[server]
-pub async fn get_epoch_duration() -> Result<u32, ServerFnError> {
-    let chain_api = get_chain_api().await?;
+pub async fn get_epoch_duration() -> Result<u32, AppError> {
+    let state = expect_context::<AppState>();
+    state.backend.epoch_duration()
+}
 
-    let query = substrate::allfeat::constants()
-        .token_allocation()
-        .epoch_duration();
+#[server]
+pub async fn get_payout_status() -> Result<PayoutStatus, AppError> {
+    let state = expect_context::<AppState>();
 
-    Ok(chain_api.constants().at(&query)?)
+    let block = state.backend.latest_block().await?;
+    let payout = state.backend.payout_state(block.hash).await?;
+
+    Ok(PayoutStatus {
+        current_block: block.number,
+        next_payout_at: payout.next_payout_at,
+        epoch_index: payout.epoch_index,
+        epoch_duration: state.backend.epoch_duration()?,
+        payout_cursor: payout.payout_cursor,
+        total_allocations: payout.next_allocation_id,
+        max_payouts_per_block: state.backend.max_payouts_per_block()?,
+    })
 }
 
 #[server]
-pub async fn get_allocations_of(id: String) -> Result<Vec<Allocation>, ServerFnError> {
-    let chain_api = get_chain_api().await?;
-
-    let query = substrate::allfeat::storage()
-        .token_allocation()
-        .allocations_iter();
+pub async fn get_payout_history(limit: usize) -> Result<PayoutHistoryPage, AppError> {
+    const MAX_LIMIT: usize = 100;
 
-    // Get block reference to ensure consistency across queries
-    let block = chain_api.blocks().at_latest().await?;
-    let block_ref = block.reference();
-    let storage = chain_api.storage().at(block_ref.clone());
-    let mut allocs_iter = storage.iter(query).await?;
-
-    let mut allocs: Vec<Allocation> = vec![];
-
-    while let Some(Ok(kv)) = allocs_iter.next().await {
-        if format_ss58(&kv.value.beneficiary) == id {
-            allocs.push(Allocation {
-                envelope: get_alloc_config_of(
-                    &chain_api,
-                    &block_ref,
-                    &kv.value.envelope,
-                    envelope_to_str(&kv.value.envelope),
-                )
-                .await?,
-                total: kv.value.total,
-                upfront: kv.value.upfront,
-                released: kv.value.released,
-                vested_total: kv.value.vested_total,
-                start: kv.value.start,
-            });
-        }
-    }
+    let state = expect_context::<AppState>();
+    let index = state.payout_index.read().await;
 
-    Ok(allocs)
+    Ok(PayoutHistoryPage {
+        epochs: index.history(limit.min(MAX_LIMIT)),
+        indexed_since: index.indexed_since,
+    })
 }
 
 #[server]
-pub async fn get_total_issuance() -> Result<u128, ServerFnError> {
-    let chain_api = get_chain_api().await?;
+pub async fn get_allocations_of(id: String) -> Result<Vec<Allocation>, AppError> {
+    let state = expect_context::<AppState>();
+    let account_id = require_account(&id)?;
 
-    let query = substrate::allfeat::storage().balances().total_issuance();
+    // Get block reference to ensure consistency across queries
+    let at = state.backend.latest_block().await?.hash;
 
-    let total = chain_api
-        .storage()
-        .at_latest()
+    fetch_allocations(&state, at, &[Ok(account_id)])
         .await?
-        .fetch(&query)
+        .remove(0)
+}
+
+#[server]
+pub async fn get_total_issuance() -> Result<u128, AppError> {
+    let state = expect_context::<AppState>();
+
+    let at = state.backend.latest_block().await?.hash;
+    let total = state
+        .backend
+        .total_issuance(at)
         .await?
-        .ok_or_else(|| ServerFnError::new("Total issuance not found on chain"))?;
+        .ok_or_else(|| AppError::NotFound("Total issuance not found on chain".to_string()))?;
 
     Ok(total)
 }
 
+/// Circulating supply under the given methodology, or the configured one.
+#[server]
+pub async fn get_circulating_supply(
+    methodology: Option<supply::SupplyMethodology>,
+) -> Result<u128, AppError> {
+    let state = expect_context::<AppState>();
+    let breakdown = fetch_supply_breakdown(&state).await?;
+
+    methodology
+        .unwrap_or(state.supply_methodology)
+        .circulating(&breakdown)
+        .map_err(AppError::NotFound)
+}
+
+/// Every component behind the circulating supply, so it can be reconciled.
 #[server]
-pub async fn get_circulating_supply() -> Result<u128, ServerFnError> {
-    let chain_api = get_chain_api().await?;
+pub async fn get_supply_breakdown() -> Result<SupplyBreakdown, AppError> {
+    let state = expect_context::<AppState>();
+    fetch_supply_breakdown(&state).await
+}
+
+#[server]
+pub async fn get_balance_of(id: String) -> Result<Balances, AppError> {
+    let state = expect_context::<AppState>();
+
+    let account_id = require_account(&id)?;
+    let at = state.backend.latest_block().await?.hash;
+
+    fetch_balances(state.backend.as_ref(), at, &[Ok(account_id)])
+        .await
+        .remove(0)
+}
+
+/// Balances of several accounts, read at a single block.
+#[server]
+pub async fn get_balances_of_many(
+    ids: Vec<String>,
+) -> Result<Vec<AccountResult<Balances>>, AppError> {
+    check_batch_size(&ids)?;
+
+    let state = expect_context::<AppState>();
+    let at = state.backend.latest_block().await?.hash;
+
+    let accounts = ids.iter().map(|id| require_account(id)).collect::<Vec<_>>();
+    let balances = fetch_balances(state.backend.as_ref(), at, &accounts).await;
+
+    Ok(ids
+        .into_iter()
+        .zip(balances)
+        .map(|(id, result)| AccountResult { id, result })
+        .collect())
+}
+
+/// Allocations of several accounts, read at a single block.
+#[server]
+pub async fn get_allocations_of_many(
+    ids: Vec<String>,
+) -> Result<Vec<AccountResult<Vec<Allocation>>>, AppError> {
+    check_batch_size(&ids)?;
+
+    let state = expect_context::<AppState>();
+    let at = state.backend.latest_block().await?.hash;
+
+    let accounts = ids.iter().map(|id| require_account(id)).collect::<Vec<_>>();
+    let allocations = fetch_allocations(&state, at, &accounts).await?;
+
+    Ok(ids
+        .into_iter()
+        .zip(allocations)
+        .map(|(id, result)| AccountResult { id, result })
+        .collect())
+}
+
+/// Accounts ranked by balance, from the latest holder snapshot. `None`
+/// until the first scan of the accounts completes.
+#[server]
+pub async fn get_top_holders(
+    ranking: HolderRanking,
+    offset: usize,
+    limit: usize,
+) -> Result<Option<TopHoldersPage>, AppError> {
+    const MAX_LIMIT: usize = 100;
+
+    let state = expect_context::<AppState>();
+    let snapshot = state.holders.snapshot.read().await.clone();
+
+    Ok(snapshot.map(|s| s.page(ranking, offset, limit.min(MAX_LIMIT))))
+}
+
+#[server]
+pub async fn get_sudo_key() -> Result<Option<String>, AppError> {
+    let state = expect_context::<AppState>();
+
+    let at = state.backend.latest_block().await?.hash;
+    let key = state.backend.sudo_key(at).await?;
+
+    Ok(key.map(|account| format_ss58(&account)))
+}
+
+#[server]
+pub async fn get_sudo_activity(offset: usize, limit: usize) -> Result<SudoActivityPage, AppError> {
+    const MAX_LIMIT: usize = 100;
+
+    let state = expect_context::<AppState>();
+    let log = state.sudo_log.read().await;
+
+    Ok(SudoActivityPage {
+        items: log
+            .entries
+            .iter()
+            .skip(offset)
+            .take(limit.min(MAX_LIMIT))
+            .cloned()
+            .collect(),
+        total: log.entries.len(),
+        indexed_since: log.indexed_since,
+    })
+}
+
+#[server]
+pub async fn get_fee_analytics(bucket_blocks: u32, buckets: u32) -> Result<FeeAnalytics, AppError> {
+    const MAX_BUCKETS: u32 = 500;
+
+    let state = expect_context::<AppState>();
+
+    let at = state.backend.latest_block().await?.hash;
+    let multiplier = state
+        .backend
+        .next_fee_multiplier(at)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Fee multiplier not found on chain".to_string()))?;
+
+    let index = state.fee_index.read().await;
+
+    Ok(FeeAnalytics {
+        // FixedU128 has 18 decimals
+        next_fee_multiplier: multiplier as f64 / 1e18,
+        indexed_since: index.blocks.front().map(|b| b.block),
+        buckets: index.buckets(bucket_blocks, buckets.clamp(1, MAX_BUCKETS)),
+        per_call: index.per_call(),
+    })
+}
+
+#[server]
+pub async fn estimate_fee(extrinsic: String) -> Result<FeeEstimate, AppError> {
+    use subxt::ext::codec::{Compact, Decode, Encode};
+
+    use crate::utils::decode_hex;
+
+    let state = expect_context::<AppState>();
 
-    let distributed_query = substrate::allfeat::storage()
-        .token_allocation()
-        .envelope_distributed_iter();
-    let allocations_query = substrate::allfeat::storage()
-        .token_allocation()
-        .allocations_iter();
+    let mut bytes = decode_hex(&extrinsic)
+        .ok_or_else(|| AppError::InvalidInput("Invalid hex encoded extrinsic".to_string()))?;
+
+    // Accept extrinsics with or without their compact length prefix
+    let prefixed = Compact::<u32>::decode(&mut &bytes[..])
+        .map(|len| len.0 as usize + len.encoded_size() == bytes.len())
+        .unwrap_or(false);
+    if !prefixed {
+        let mut with_prefix = Compact(bytes.len() as u32).encode();
+        with_prefix.extend(bytes);
+        bytes = with_prefix;
+    }
 
-    // Use a single block reference for both iterators (consistency + fewer RPC calls)
-    let storage = chain_api.storage().at_latest().await?;
+    state.backend.query_fee_info(bytes).await
+}
 
-    let mut distributed_iter = storage.iter(distributed_query).await?;
+/// Resolves free text typed in the search box, most relevant first.
+///
+/// Accepts SS58 addresses of any network, hex public keys, known address
+/// names, block numbers and block or extrinsic hashes. Addresses are always
+/// suggested in their Allfeat (prefix 440) form.
+#[server]
+pub async fn search(query: String) -> Result<Vec<SearchSuggestion>, AppError> {
+    use crate::utils::{decode_hex, display_address, search_known_addresses};
 
-    let mut total_distributed: u128 = 0;
-    while let Some(Ok(kv)) = distributed_iter.next().await {
-        total_distributed += kv.value
+    const MAX_SUGGESTIONS: usize = 8;
+
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(vec![]);
     }
 
-    let mut allocations_iter = storage.iter(allocations_query).await?;
+    let state = expect_context::<AppState>();
+    let mut suggestions = Vec::new();
+
+    let account = |account: &AccountId32| {
+        let address = format_ss58(account);
+        SearchSuggestion {
+            kind: SearchKind::Account,
+            label: display_address(&address),
+            detail: Some(address.clone()).filter(|a| *a != display_address(a)),
+            href: format!("/accounts/{address}"),
+        }
+    };
+
+    if let Some(number) = parse_block_number(query) {
+        let hash = state.backend.block_hash(number).await?;
+        if hash.is_some() {
+            suggestions.push(SearchSuggestion {
+                kind: SearchKind::Block,
+                label: format!("#{}", crate::utils::blocks_to_str(number)),
+                detail: hash.map(|h| format!("{h:?}")),
+                href: format!("/blocks/{number}"),
+            });
+        }
+    } else if query.starts_with("0x") {
+        if let Some(bytes) = decode_hex(query).filter(|b| b.len() == 32) {
+            let hash = H256::from_slice(&bytes);
+
+            if state.backend.block_number(hash).await?.is_some() {
+                suggestions.push(SearchSuggestion {
+                    kind: SearchKind::Block,
+                    label: format!("{hash:?}"),
+                    detail: None,
+                    href: format!("/blocks/{hash:?}"),
+                });
+            }
 
-    let mut total_in_vesting: u128 = 0;
-    while let Some(Ok(kv)) = allocations_iter.next().await {
-        total_in_vesting += kv.value.vested_total.saturating_sub(kv.value.released)
+            let extrinsic = state.extrinsic_index.read().await.get(&hash);
+            if let Some((block, index)) = extrinsic {
+                suggestions.push(SearchSuggestion {
+                    kind: SearchKind::Extrinsic,
+                    label: format!("{block}-{index}"),
+                    detail: Some(format!("{hash:?}")),
+                    href: format!("/blocks/{block}#extrinsic-{index}"),
+                });
+            }
+
+            // A 32 bytes value is also a valid public key
+            let mut suggestion = account(&AccountId32(bytes.try_into().unwrap_or_default()));
+            suggestion
+                .detail
+                .get_or_insert_with(|| "Public key".to_string());
+            suggestions.push(suggestion);
+        }
+    } else if let Ok((_, account_id)) = crate::ss58::decode_account(query) {
+        suggestions.push(account(&AccountId32(account_id)));
     }
 
-    let distributed_less_in_vesting = total_distributed.saturating_sub(total_in_vesting);
+    for label in search_known_addresses(query) {
+        if let Ok(account_id) = AccountId32::from_str(&label.address) {
+            let suggestion = account(&account_id);
+            if suggestions.iter().all(|s| s.href != suggestion.href) {
+                suggestions.push(suggestion);
+            }
+        }
+    }
 
-    Ok(distributed_less_in_vesting)
+    suggestions.truncate(MAX_SUGGESTIONS);
+    Ok(suggestions)
 }
 
+/// Every label of the address book.
 #[server]
-pub async fn get_balance_of(id: String) -> Result<Balances, ServerFnError> {
-    let chain_api = get_chain_api().await?;
+pub async fn get_address_book() -> Result<Vec<address_book::AddressLabel>, AppError> {
+    Ok(address_book::labels())
+}
+
+/// Canonical Allfeat address of an account given in any supported format.
+#[server]
+pub async fn resolve_account(id: String) -> Result<String, AppError> {
+    require_account(&id).map(|account| format_ss58(&account))
+}
 
-    let account_id =
-        AccountId32::from_str(&id).map_err(|_| ServerFnError::new("Invalid address format"))?;
+/// Block by number or hash.
+#[server]
+pub async fn get_block(id: String) -> Result<BlockDetails, AppError> {
+    use crate::utils::decode_hex;
 
-    let query = substrate::allfeat::storage().system().account(account_id);
+    let state = expect_context::<AppState>();
 
-    let account_info = chain_api.storage().at_latest().await?.fetch(&query).await?;
+    let hash = match parse_block_number(&id) {
+        Some(number) => state.backend.block_hash(number).await?,
+        None => decode_hex(&id)
+            .filter(|b| b.len() == 32)
+            .map(|b| H256::from_slice(&b)),
+    }
+    .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
 
-    // Return zero balances if account doesn't exist on chain
-    match account_info {
-        Some(info) => Ok(Balances {
-            free: info.data.free,
-            reserved: info.data.reserved,
-            frozen: info.data.frozen,
-        }),
-        None => Ok(Balances {
-            free: 0,
-            reserved: 0,
-            frozen: 0,
-        }),
+    let block = state
+        .backend
+        .block(hash)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Block not found".to_string()))?;
+
+    let mut outcomes = std::collections::HashMap::new();
+    for event in &block.events {
+        if let Some(idx) = event.extrinsic
+            && event.pallet == "System"
+        {
+            match event.variant.as_str() {
+                "ExtrinsicSuccess" => outcomes.insert(idx, true),
+                "ExtrinsicFailed" => outcomes.insert(idx, false),
+                _ => None,
+            };
+        }
     }
+
+    let extrinsics = block
+        .extrinsics
+        .iter()
+        .map(|ext| ExtrinsicSummary {
+            index: ext.index,
+            hash: format!("{:?}", ext.hash),
+            call: ext.call.clone().unwrap_or_else(|| "Unknown".to_string()),
+            signer: ext.signer.as_ref().map(format_ss58),
+            success: outcomes.get(&ext.index).copied(),
+        })
+        .collect();
+
+    Ok(BlockDetails {
+        number: block.number,
+        hash: format!("{:?}", block.hash),
+        parent_hash: format!("{:?}", block.parent_hash),
+        timestamp: block.timestamp,
+        event_count: block.events.len() as u32,
+        extrinsics,
+    })
 }
 
 #[cfg(feature = "ssr")]
 mod ssr {
     pub use super::state::AppState;
     pub use super::state::CachedData;
-    pub use super::substrate::AllfeatClient;
+    pub use super::substrate::ChainBackend;
     pub use super::substrate::allfeat::runtime_types::pallet_token_allocation::EnvelopeId;
+    pub use super::substrate::backend::BlockAt;
     use super::*;
     pub use std::str::FromStr;
-    pub use subxt::OnlineClient;
-    pub use subxt::SubstrateConfig;
-    pub use subxt::utils::AccountId32;
+    pub use subxt::utils::{AccountId32, H256};
 
     /// Centralized envelope configuration - single source of truth
     pub const ENVELOPES: &[(EnvelopeId, &str)] = &[
@@ -284,43 +1049,311 @@ mod ssr {
 
     /// Encode an AccountId32 to SS58 format with the Allfeat prefix (440)
     pub fn format_ss58(account: &AccountId32) -> String {
-        use crate::utils::SS58_PREFIX;
-        use blake2::{Blake2b512, Digest};
+        crate::ss58::encode_account(crate::utils::SS58_PREFIX, account.as_ref())
+    }
 
-        const SS58_PREFIX_BYTES: &[u8] = b"SS58PRE";
+    /// Parses an account given as an SS58 address of any network, a 0x hex
+    /// public key or a known address name.
+    pub fn parse_account(input: &str) -> Option<AccountId32> {
+        use crate::utils::{decode_hex, search_known_addresses};
 
-        let public_key: &[u8; 32] = account.as_ref();
+        let input = input.trim();
 
-        // For prefix > 63, use two-byte encoding
-        let prefix_bytes = [
-            ((SS58_PREFIX & 0x00FC) as u8 >> 2) | 0x40,
-            ((SS58_PREFIX >> 8) as u8) | ((SS58_PREFIX & 0x0003) as u8) << 6,
-        ];
+        if input.starts_with("0x") {
+            let bytes: [u8; 32] = decode_hex(input)?.try_into().ok()?;
+            return Some(AccountId32(bytes));
+        }
 
-        // Compute checksum: blake2b-512 of (SS58PRE || prefix || pubkey)
-        let mut hasher = Blake2b512::new();
-        hasher.update(SS58_PREFIX_BYTES);
-        hasher.update(&prefix_bytes);
-        hasher.update(public_key);
-        let hash = hasher.finalize();
+        crate::ss58::decode_account(input)
+            .map(|(_, account)| AccountId32(account))
+            .ok()
+            .or_else(|| {
+                search_known_addresses(input)
+                    .into_iter()
+                    .find(|label| label.name.eq_ignore_ascii_case(input))
+                    .and_then(|label| AccountId32::from_str(&label.address).ok())
+            })
+    }
+
+    /// `parse_account`, failing with the input when it isn't an account.
+    pub fn require_account(input: &str) -> Result<AccountId32, AppError> {
+        parse_account(input).ok_or_else(|| AppError::InvalidAddress(input.to_string()))
+    }
 
-        // Build the full address: prefix (2 bytes) + pubkey (32 bytes) + checksum (2 bytes)
-        let mut address = Vec::with_capacity(36);
-        address.extend_from_slice(&prefix_bytes);
-        address.extend_from_slice(public_key);
-        address.extend_from_slice(&hash[0..2]);
+    /// Most accounts accepted by a single batched query.
+    pub const MAX_BATCH_ACCOUNTS: usize = 100;
 
-        bs58::encode(address).into_string()
+    /// Storage queries of a batch that may be in flight at the same time.
+    const MAX_CONCURRENT_FETCHES: usize = 16;
+
+    pub fn check_batch_size(ids: &[String]) -> Result<(), AppError> {
+        if ids.len() > MAX_BATCH_ACCOUNTS {
+            return Err(AppError::InvalidInput(format!(
+                "At most {MAX_BATCH_ACCOUNTS} accounts can be fetched at once"
+            )));
+        }
+        Ok(())
     }
 
-    pub async fn get_chain_api() -> Result<AllfeatClient, ServerFnError> {
-        use axum::extract::State;
-        use leptos_axum::extract_with_state;
+    /// Balances of each account, in order. Accounts that failed to parse keep
+    /// their error.
+    pub async fn fetch_balances(
+        backend: &dyn ChainBackend,
+        at: H256,
+        accounts: &[Result<AccountId32, AppError>],
+    ) -> Vec<Result<Balances, AppError>> {
+        use futures::StreamExt;
+
+        futures::stream::iter(accounts.iter().cloned())
+            .map(|account_id| async move {
+                // Accounts that don't exist on chain have zero balances
+                let info = backend.account(at, account_id?).await?;
+                let balances = info.unwrap_or(Balances {
+                    free: 0,
+                    reserved: 0,
+                    frozen: 0,
+                });
+                Ok::<_, AppError>(balances)
+            })
+            .buffered(MAX_CONCURRENT_FETCHES)
+            .collect()
+            .await
+    }
 
-        let state = expect_context::<AppState>();
-        let State(client): State<AppState> = extract_with_state(&state).await?;
+    /// Allocations of each account, in order, from a single pass over the
+    /// allocations storage. Accounts that failed to parse keep their error.
+    pub async fn fetch_allocations(
+        state: &AppState,
+        at: H256,
+        accounts: &[Result<AccountId32, AppError>],
+    ) -> Result<Vec<Result<Vec<Allocation>, AppError>>, AppError> {
+        use futures::StreamExt;
+        use std::collections::{HashMap, HashSet};
+
+        let backend = state.backend.as_ref();
+        // `AccountId32` isn't `Hash`, its raw bytes are
+        let wanted: HashSet<[u8; 32]> = accounts.iter().flatten().map(|id| id.0).collect();
+
+        let found = {
+            let _permit = state.rate_limiter.scan_permit().await?;
+            backend.allocations(at).await?
+        }
+        .into_iter()
+        .filter(|alloc| wanted.contains(&alloc.beneficiary.0))
+        .collect::<Vec<_>>();
+
+        // Each envelope config is fetched once for the whole batch
+        let mut envelope_ids = vec![];
+        for alloc in &found {
+            if !envelope_ids.contains(&alloc.envelope) {
+                envelope_ids.push(alloc.envelope.clone());
+            }
+        }
+        let envelopes: HashMap<&'static str, Result<EnvelopeAllocation, AppError>> =
+            futures::stream::iter(envelope_ids)
+                .map(|envelope| async move {
+                    let name = envelope_to_str(&envelope);
+                    let config = get_alloc_config_of(backend, at, &envelope, name).await;
+                    (name, config)
+                })
+                .buffer_unordered(MAX_CONCURRENT_FETCHES)
+                .collect()
+                .await;
+
+        let mut per_account: HashMap<[u8; 32], Result<Vec<Allocation>, AppError>> = HashMap::new();
+        for alloc in found {
+            let envelope = envelopes[envelope_to_str(&alloc.envelope)].clone();
+            let entry = per_account
+                .entry(alloc.beneficiary.0)
+                .or_insert_with(|| Ok(vec![]));
+
+            match envelope {
+                Ok(envelope) => {
+                    if let Ok(allocs) = entry {
+                        allocs.push(Allocation {
+                            envelope,
+                            total: alloc.total,
+                            upfront: alloc.upfront,
+                            released: alloc.released,
+                            vested_total: alloc.vested_total,
+                            start: alloc.start,
+                        });
+                    }
+                }
+                Err(e) => *entry = Err(e),
+            }
+        }
+
+        Ok(accounts
+            .iter()
+            .map(|account_id| match account_id {
+                Ok(account_id) => per_account
+                    .get(&account_id.0)
+                    .cloned()
+                    .unwrap_or(Ok(vec![])),
+                Err(e) => Err(e.clone()),
+            })
+            .collect())
+    }
 
-        Ok(client.client)
+    pub async fn fetch_supply_breakdown(state: &AppState) -> Result<SupplyBreakdown, AppError> {
+        let block = state.backend.latest_block().await?;
+        let envelopes = fetch_envelope_supply(state, block.hash).await?;
+        supply_breakdown_at(state, block, envelopes).await
+    }
+
+    /// Breakdown at `block`, around the envelope supply read at that block.
+    async fn supply_breakdown_at(
+        state: &AppState,
+        block: BlockAt,
+        envelopes: Vec<EnvelopeSupply>,
+    ) -> Result<SupplyBreakdown, AppError> {
+        let backend = state.backend.as_ref();
+
+        let treasury = AccountId32::from_str(crate::utils::TREASURY_ACCOUNT)
+            .map_err(|e| AppError::Internal(format!("Invalid treasury account: {e}")))?;
+
+        let (total_issuance, treasury) = tokio::try_join!(
+            backend.total_issuance(block.hash),
+            backend.account(block.hash, treasury),
+        )?;
+
+        let holders = state.holders.snapshot.read().await.clone();
+        let sum_of = |f: fn(&crate::indexer::holders::HolderBalance) -> u128| {
+            holders
+                .as_ref()
+                .map(|s| s.by_total.iter().map(f).sum::<u128>())
+        };
+
+        Ok(SupplyBreakdown {
+            block: block.number,
+            total_issuance: total_issuance.unwrap_or(0),
+            envelopes,
+            treasury: treasury.map(|balances| balances.free).unwrap_or(0),
+            reserved: sum_of(|h| h.reserved),
+            frozen: sum_of(|h| h.frozen),
+            holders_at: holders.as_ref().map(|s| s.block),
+            methodology: state.supply_methodology,
+        })
+    }
+
+    /// Envelope supply for the metrics. Scanning the allocations on every
+    /// block would be wasted, so it is read again only once a block changed
+    /// them, as reported by the block feed.
+    async fn cached_envelope_supply(
+        state: &AppState,
+        block: BlockAt,
+    ) -> Result<Vec<EnvelopeSupply>, AppError> {
+        {
+            let cache = state.envelope_supply.read().await;
+            if let Some((at, supply)) = &cache.supply
+                && *at >= cache.changed_at
+            {
+                return Ok(supply.clone());
+            }
+        }
+
+        let supply = fetch_envelope_supply(state, block.hash).await?;
+
+        let mut cache = state.envelope_supply.write().await;
+        // Unless a later block changed the allocations in the meantime
+        if block.number >= cache.changed_at {
+            cache.supply = Some((block.number, supply.clone()));
+        }
+        Ok(supply)
+    }
+
+    /// Value of a metric at a finalized block. Values are computed once per
+    /// block, however many streams subscribed to them.
+    pub async fn read_metric(
+        state: &AppState,
+        key: &MetricKey,
+        block: BlockAt,
+    ) -> Result<MetricValue, AppError> {
+        if let Some((at, value)) = state.metrics_cache.read().await.get(key)
+            && *at == block.number
+        {
+            return Ok(value.clone());
+        }
+
+        let backend = state.backend.as_ref();
+        let at = block.hash;
+        let value = match key {
+            MetricKey::TotalIssuance => {
+                MetricValue::Amount(backend.total_issuance(at).await?.unwrap_or(0))
+            }
+            MetricKey::CirculatingSupply => {
+                let envelopes = cached_envelope_supply(state, block).await?;
+                let breakdown = supply_breakdown_at(state, block, envelopes).await?;
+                let circulating = state
+                    .supply_methodology
+                    .circulating(&breakdown)
+                    .map_err(AppError::NotFound)?;
+                MetricValue::Amount(circulating)
+            }
+            MetricKey::Balances(id) => {
+                let balances = fetch_balances(backend, at, &[require_account(id)])
+                    .await
+                    .remove(0)?;
+                MetricValue::Balances(balances)
+            }
+        };
+
+        let mut cache = state.metrics_cache.write().await;
+        cache.retain(|_, (at, _)| *at >= block.number);
+        cache.insert(key.clone(), (block.number, value.clone()));
+
+        Ok(value)
+    }
+
+    /// Distribution and vesting state of each envelope, in `ENVELOPES` order.
+    pub async fn fetch_envelope_supply(
+        state: &AppState,
+        at: H256,
+    ) -> Result<Vec<EnvelopeSupply>, AppError> {
+        let backend = state.backend.as_ref();
+        let mut envelopes = Vec::with_capacity(ENVELOPES.len());
+        for (id, name) in ENVELOPES {
+            let (config, distributed) = tokio::try_join!(
+                backend.envelope(at, id.clone()),
+                backend.envelope_distributed(at, id.clone()),
+            )?;
+            envelopes.push(EnvelopeSupply {
+                envelope: name.to_string(),
+                insider: supply::is_insider(id),
+                total_cap: config.map(|c| c.total_cap).unwrap_or(0),
+                distributed: distributed.unwrap_or(0),
+                locked_in_vesting: 0,
+            });
+        }
+
+        let allocations = {
+            let _permit = state.rate_limiter.scan_permit().await?;
+            backend.allocations(at).await?
+        };
+        for alloc in allocations {
+            let name = envelope_to_str(&alloc.envelope);
+            if let Some(envelope) = envelopes.iter_mut().find(|e| e.envelope == name) {
+                envelope.locked_in_vesting += alloc.vested_total.saturating_sub(alloc.released);
+            }
+        }
+
+        Ok(envelopes)
+    }
+
+    /// Parses a block number, allowing a leading `#` and digit separators.
+    pub fn parse_block_number(input: &str) -> Option<u32> {
+        let digits: String = input
+            .trim()
+            .trim_start_matches('#')
+            .chars()
+            .filter(|c| !matches!(c, ' ' | ',' | '_'))
+            .collect();
+
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
     }
 
     pub fn envelope_to_str(envelope: &EnvelopeId) -> &'static str {
@@ -332,26 +1365,19 @@ mod ssr {
     }
 
     pub async fn get_alloc_config_of(
-        chain_api: &OnlineClient<SubstrateConfig>,
-        block_ref: &subxt::blocks::BlockRef<subxt::utils::H256>,
+        backend: &dyn ChainBackend,
+        at: H256,
         envelope: &EnvelopeId,
         name: &str,
-    ) -> Result<EnvelopeAllocation, ServerFnError> {
-        let query = substrate::allfeat::storage()
-            .token_allocation()
-            .envelopes(envelope.clone());
-        let query_distributed = substrate::allfeat::storage()
-            .token_allocation()
-            .envelope_distributed(envelope.clone());
-
-        // Reuse the same block reference for both queries
-        let storage = chain_api.storage().at(block_ref.clone());
-
-        let (res, res_distributed) =
-            tokio::try_join!(storage.fetch(&query), storage.fetch(&query_distributed))?;
+    ) -> Result<EnvelopeAllocation, AppError> {
+        // Both read at the same block
+        let (res, res_distributed) = tokio::try_join!(
+            backend.envelope(at, envelope.clone()),
+            backend.envelope_distributed(at, envelope.clone())
+        )?;
 
         let res =
-            res.ok_or_else(|| ServerFnError::new(format!("Envelope config not found for {name}")))?;
+            res.ok_or_else(|| AppError::NotFound(format!("Envelope config not found for {name}")))?;
         let res_distributed = res_distributed.unwrap_or(0);
 
         Ok(EnvelopeAllocation {
@@ -362,7 +1388,7 @@ mod ssr {
             cliff: res.cliff,
             vesting_duration: res.vesting_duration,
             distributed: res_distributed,
-            upfront_rate: res.upfront_rate.0,
+            upfront_rate: res.upfront_rate,
         })
     }
 }
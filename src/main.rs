@@ -2,43 +2,134 @@
 #[tokio::main]
 async fn main() {
     use axum::Router;
+    use axum::routing::get;
     use leptos::logging::log;
     use leptos::prelude::*;
     use leptos_axum::{LeptosRoutes, generate_route_list};
+    use token_app::address_book;
     use token_app::app::{App, shell};
+    use token_app::config::{self, Cli, Command, Config};
+    use token_app::indexer;
+    use token_app::indexer::holders::HolderIndex;
+    use token_app::indexer::tokenomics::TokenomicsMonitor;
+    use token_app::rate_limit::{self, RateLimiter};
     use token_app::state::AppState;
-    use tracing::error;
+    use token_app::substrate::recording::RpcRecording;
+    use token_app::substrate::{ChainConnection, connection, dev_seed};
+    use token_app::telemetry::{self, Telemetry};
     use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 
     dotenvy::dotenv().ok();
 
+    let config = match Cli::parse(std::env::args().skip(1)) {
+        Ok(cli) if cli.help => {
+            println!("{}", config::USAGE);
+            return;
+        }
+        Ok(cli) => Config::load(&cli).map(|config| (cli, config)),
+        Err(e) => Err(e),
+    };
+    let (command, config) = match config {
+        Ok((cli, config)) if cli.print_config => {
+            print!("{}", config.to_toml());
+            return;
+        }
+        Ok((cli, config)) => (cli.command, std::sync::Arc::new(config)),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
+    };
+    config::set_chain_settings(config.chain.clone());
+
     let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
     tracing_subscriber::registry()
         .with(fmt::layer())
         .with(filter)
         .init();
 
-    let client = match rpc::connect().await {
-        Ok(c) => c,
-        Err(e) => {
-            error!("CRITICAL: Failed to connect to RPC endpoint: {e}");
+    // Serves what it can until a node is reachable
+    let telemetry = std::sync::Arc::new(Telemetry::default());
+    let mut chain = ChainConnection::new(config.rpc.endpoints.clone(), telemetry.clone());
+    if let Some(path) = &config.rpc.record {
+        match RpcRecording::create(path) {
+            Ok(recording) => chain = chain.with_recording(recording),
+            Err(e) => {
+                eprintln!(
+                    "Failed to record the RPC traffic to {}: {e}",
+                    path.display()
+                );
+                std::process::exit(2);
+            }
+        }
+        log!("Recording the RPC traffic to {}", path.display());
+    }
+    let chain = std::sync::Arc::new(chain);
+    connection::spawn(chain.clone());
+
+    if let Some(Command::SeedDev(options)) = command {
+        if let Err(e) = dev_seed::run(&chain, &options).await {
+            eprintln!("Seeding failed: {e}");
             std::process::exit(1);
         }
+        return;
+    }
+
+    #[cfg(feature = "faucet")]
+    let faucet = match token_app::faucet::Faucet::new(config.faucet.clone()) {
+        Ok(faucet) => faucet.map(std::sync::Arc::new),
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(2);
+        }
     };
+    #[cfg(feature = "faucet")]
+    if let Some(faucet) = &faucet {
+        log!("Faucet enabled, sending from {}", faucet.address());
+    }
 
     let conf = get_configuration(None).unwrap();
-    let addr = conf.leptos_options.site_addr;
-    let leptos_options = conf.leptos_options;
+    let mut leptos_options = conf.leptos_options;
+    if let Some(site_addr) = config.server.site_addr {
+        leptos_options.site_addr = site_addr;
+    }
+    let addr = leptos_options.site_addr;
     // Generate the list of routes in your Leptos App
     let routes = generate_route_list(App);
 
+    let rate_limiter = std::sync::Arc::new(RateLimiter::from_config(&config.rate_limit));
+    rate_limiter.spawn_sweeper();
+
     let app_state = AppState {
         leptos_options: leptos_options.clone(),
-        client,
+        config: config.clone(),
+        backend: chain.clone(),
+        chain,
+        telemetry,
+        rate_limiter,
         allocations_cache: std::sync::Arc::new(tokio::sync::RwLock::new(None)),
+        sudo_log: Default::default(),
+        fee_index: Default::default(),
+        payout_index: Default::default(),
+        extrinsic_index: Default::default(),
+        tokenomics: std::sync::Arc::new(TokenomicsMonitor::new(config.tokenomics.clone())),
+        holders: std::sync::Arc::new(HolderIndex::new(config.cache.holders_refresh_blocks)),
+        block_feed: Default::default(),
+        metrics_cache: Default::default(),
+        envelope_supply: Default::default(),
+        supply_methodology: config.supply.methodology,
+        #[cfg(feature = "faucet")]
+        faucet,
     };
 
+    if config.features.indexer {
+        indexer::spawn(app_state.clone());
+    }
+    address_book::file::spawn(config.address_book.path.clone());
+
     let app = Router::new()
+        .route("/sudo/feed.xml", get(indexer::sudo::feed))
+        .route("/health/tokenomics", get(indexer::tokenomics::health))
         .leptos_routes_with_context(
             &app_state,
             routes,
@@ -52,44 +143,31 @@ async fn main() {
             },
         )
         .fallback(leptos_axum::file_and_error_handler::<AppState, _>(shell))
+        .layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            rate_limit::limit,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            telemetry::track_server_fns,
+        ))
+        // Added after the layers, so probes are neither limited nor timed
+        .route("/healthz", get(telemetry::healthz))
+        .route("/readyz", get(telemetry::readyz))
+        .route("/metrics", get(telemetry::metrics))
         .with_state(app_state);
 
     // run our app with hyper
     // `axum::Server` is a re-export of `hyper::Server`
     log!("listening on http://{}", &addr);
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app.into_make_service())
-        .await
-        .unwrap();
-}
-
-#[cfg(feature = "ssr")]
-mod rpc {
-    use std::time::Duration;
-    use subxt::OnlineClient;
-    use subxt::backend::rpc::reconnecting_rpc_client::{ExponentialBackoff, RpcClient};
-    use token_app::substrate::AllfeatClient;
-
-    pub async fn connect() -> Result<AllfeatClient, Box<dyn std::error::Error + Send + Sync>> {
-        let rpc_url = std::env::var("RPC_URL")
-            .unwrap_or_else(|_| "wss://mainnet.rpc.allfeat.org".to_string());
-
-        tracing::info!(target: "allfeat", "Connecting to RPC: {}", rpc_url);
-
-        let rpc = RpcClient::builder()
-            .retry_policy(
-                ExponentialBackoff::from_millis(100)
-                    .max_delay(Duration::from_secs(10))
-                    .take(5),
-            )
-            .build(rpc_url)
-            .await?;
-
-        let api = OnlineClient::from_rpc_client(rpc).await?;
-
-        tracing::info!(target: "allfeat", "Connected to Allfeat network");
-        Ok(api)
-    }
+    // Client addresses are needed by the rate limiter
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
 
 #[cfg(not(feature = "ssr"))]
@@ -1,7 +1,8 @@
 use crate::{
     Allocation,
-    components::{Card, fetchable_balance::FetchableData},
-    get_allocations_of, get_epoch_duration,
+    PayoutStatus,
+    components::{Card, fetchable_balance::FetchableData, payout_status::payout_eta},
+    get_allocations_of, get_payout_status,
     utils::{blocks_to_human_duration, blocks_to_str, format_balance},
 };
 use leptos::prelude::*;
@@ -61,7 +62,7 @@ fn AllocationItem(allocation: Allocation) -> impl IntoView {
     } else {
         vested_total as f64 / env.vesting_duration as f64
     };
-    let epoch_blocks = OnceResource::new(get_epoch_duration());
+    let payout_status = Resource::new(|| (), |_| get_payout_status());
 
     view! {
         <Card class="h-full flex flex-col">
@@ -114,14 +115,15 @@ fn AllocationItem(allocation: Allocation) -> impl IntoView {
                 </div>
 
                 // --- NEXT EPOCH PREDICTION ---
-                <FetchableData data=epoch_blocks render=move |n_blocks: u32| {
-                    let next_epoch: u128 = ((per_block * n_blocks as f64).floor()) as u128;
+                <FetchableData data=payout_status render=move |status: PayoutStatus| {
+                    let next_epoch: u128 = ((per_block * status.epoch_duration as f64).floor()) as u128;
+                    let eta = payout_eta(&status);
                     view! {
                          <div class="mt-auto pt-2 sm:pt-4">
                             <div class="rounded bg-black/40 border border-white/5 p-3 flex items-center justify-between">
                                 <div class="flex flex-col">
                                     <span class="text-[10px] uppercase text-neutral-500">"Next Epoch Release"</span>
-                                    <span class="text-[10px] sm:text-xs text-neutral-600">"Estimated ~24h"</span>
+                                    <span class="text-[10px] sm:text-xs text-neutral-600" title=format!("Block #{}", blocks_to_str(status.next_payout_at))>{ eta }</span>
                                 </div>
                                 <span class="text-sm font-mono font-bold text-emerald-400">
                                     "+"{ format_balance(next_epoch, true) }
@@ -1,5 +1,6 @@
 use crate::{
-    components::{Card, fetchable_balance::FetchableData},
+    MetricKey, MetricValue,
+    components::{Card, fetchable_balance::FetchableData, live::LiveValue},
     get_balance_of,
     utils::format_balance,
 };
@@ -7,7 +8,13 @@ use leptos::prelude::*;
 
 #[component]
 pub fn AccountBalances(id: String) -> impl IntoView {
-    let balance = OnceResource::new(get_balance_of(id));
+    let balance = Resource::new(|| (), {
+        let id = id.clone();
+        move |_| get_balance_of(id.clone())
+    });
+    let key = MetricKey::Balances(id);
+    // Each card moves its own copy into its children
+    let (free_key, reserved_key, frozen_key) = (key.clone(), key.clone(), key);
 
     view! {
         <section>
@@ -30,18 +37,23 @@ pub fn AccountBalances(id: String) -> impl IntoView {
                             <span class="flex h-2 w-2 rounded-full bg-emerald-500 shadow-[0_0_10px_rgba(16,185,129,0.6)]"></span>
                         </div>
 
-                        <FetchableData data=balance render=move |value| {
+                        <FetchableData data=balance render={ let key = free_key.clone(); move |value| {
                             view! {
                                 <div>
                                     <div class="text-3xl sm:text-5xl font-mono font-bold text-white tracking-tight break-all">
-                                        { format_balance(value.free, true) }
+                                        <LiveValue
+                                            key=key.clone()
+                                            initial=value.free
+                                            value=|v: &MetricValue| v.balances().map(|b| b.free)
+                                            render=|free| format_balance(free, true)
+                                        />
                                     </div>
                                     <p class="mt-2 text-xs sm:text-sm text-neutral-500">
                                         "Available for transfers and transaction fees."
                                     </p>
                                 </div>
                             }.into_any()
-                        } />
+                        }} />
                     </div>
                 </Card>
 
@@ -53,13 +65,18 @@ pub fn AccountBalances(id: String) -> impl IntoView {
                             <span class="text-[10px] sm:text-xs font-medium text-neutral-500 uppercase tracking-wider">"Locked"</span>
                             <svg class="text-rose-500/50 w-4 h-4" xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"><rect x="3" y="11" width="18" height="11" rx="2" ry="2"></rect><path d="M7 11V7a5 5 0 0 1 10 0v4"></path></svg>
                         </div>
-                         <FetchableData data=balance render=move |value| {
+                         <FetchableData data=balance render={ let key = reserved_key.clone(); move |value| {
                             view! {
                                 <div class="text-xl sm:text-2xl font-mono font-bold text-white truncate" title=format_balance(value.reserved, true)>
-                                    { format_balance(value.reserved, true) }
+                                    <LiveValue
+                                        key=key.clone()
+                                        initial=value.reserved
+                                        value=|v: &MetricValue| v.balances().map(|b| b.reserved)
+                                        render=|reserved| format_balance(reserved, true)
+                                    />
                                 </div>
                             }.into_any()
-                        } />
+                        }} />
                     </Card>
 
                     // Frozen
@@ -71,7 +88,12 @@ pub fn AccountBalances(id: String) -> impl IntoView {
                          <FetchableData data=balance render=move |value| {
                             view! {
                                 <div class="text-xl sm:text-2xl font-mono font-bold text-white truncate" title=format_balance(value.frozen, true)>
-                                    { format_balance(value.frozen, true) }
+                                    <LiveValue
+                                        key=frozen_key.clone()
+                                        initial=value.frozen
+                                        value=|v: &MetricValue| v.balances().map(|b| b.frozen)
+                                        render=|frozen| format_balance(frozen, true)
+                                    />
                                 </div>
                             }.into_any()
                         } />
@@ -0,0 +1,227 @@
+use crate::{
+    HolderRanking, TopHolder, TopHoldersPage,
+    components::{Card, simple_card_metrics::SimpleCardMetrics},
+    get_top_holders,
+    utils::{blocks_to_str, display_address, format_balance, shorten_address, ss58_identicon_svg},
+};
+use leptos::prelude::*;
+
+const PAGE_SIZE: usize = 25;
+
+fn percent_of(amount: u128, total: u128) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        amount as f64 / total as f64 * 100.0
+    }
+}
+
+#[component]
+pub fn TopHolders() -> impl IntoView {
+    let ranking = RwSignal::new(HolderRanking::Total);
+    let page = RwSignal::new(0usize);
+    let holders = Resource::new(
+        move || (ranking.get(), page.get()),
+        |(ranking, page)| get_top_holders(ranking, page * PAGE_SIZE, PAGE_SIZE),
+    );
+
+    let ranking_class = move |value: HolderRanking| {
+        if ranking.get() == value {
+            "rounded-full px-3 py-1 bg-white/10 text-white"
+        } else {
+            "rounded-full px-3 py-1 text-neutral-400 hover:text-neutral-200"
+        }
+    };
+    let select_ranking = move |value: HolderRanking| {
+        ranking.set(value);
+        page.set(0);
+    };
+
+    view! {
+        <div class="flex flex-col gap-6 sm:gap-12">
+
+            // --- HEADER ---
+            <header class="flex flex-col gap-3 sm:gap-4 pt-2 sm:pt-8">
+                <h1 class="text-3xl sm:text-4xl font-extrabold tracking-tight">
+                    <span class="bg-gradient-to-b from-white to-white/60 bg-clip-text text-transparent">
+                        "Top Holders"
+                    </span>
+                </h1>
+                <p class="text-neutral-400 max-w-2xl leading-relaxed text-sm sm:text-base">
+                    "Accounts ranked by balance and how concentrated the supply is. Balances come from a periodic snapshot of every account on chain."
+                </p>
+            </header>
+
+            <Suspense fallback=move || view! { <HoldersSkeleton /> }>
+                {move || holders.get().map(|res| match res {
+                    Ok(Some(data)) => {
+                        let has_more = (page.get() + 1) * PAGE_SIZE < data.total;
+                        view! {
+                            <DistributionOverview data=data.clone() />
+
+                            <section class="flex flex-col gap-4">
+                                <div class="flex flex-wrap items-center justify-between gap-3">
+                                    <div class="flex items-center gap-1 rounded-full border border-white/10 bg-white/[0.03] p-1 text-xs font-medium">
+                                        <button
+                                            class=move || ranking_class(HolderRanking::Total)
+                                            on:click=move |_| select_ranking(HolderRanking::Total)
+                                        >
+                                            { HolderRanking::Total.label() }
+                                        </button>
+                                        <button
+                                            class=move || ranking_class(HolderRanking::Transferable)
+                                            on:click=move |_| select_ranking(HolderRanking::Transferable)
+                                        >
+                                            { HolderRanking::Transferable.label() }
+                                        </button>
+                                    </div>
+                                    <span class="text-[10px] text-neutral-600 font-mono">
+                                        "Snapshot at block #" { blocks_to_str(data.block) }
+                                    </span>
+                                </div>
+
+                                <HoldersTable data=data.clone() ranking=ranking.get_untracked() />
+
+                                <div class="flex items-center justify-between text-xs text-neutral-500">
+                                    <span>{data.total} " holders"</span>
+                                    <div class="flex gap-2">
+                                        <button
+                                            class="rounded-full border border-white/10 px-3 py-1 hover:text-white disabled:opacity-30"
+                                            disabled=move || page.get() == 0
+                                            on:click=move |_| page.update(|p| *p = p.saturating_sub(1))
+                                        >
+                                            "Previous"
+                                        </button>
+                                        <button
+                                            class="rounded-full border border-white/10 px-3 py-1 hover:text-white disabled:opacity-30"
+                                            disabled=!has_more
+                                            on:click=move |_| page.update(|p| *p += 1)
+                                        >
+                                            "Next"
+                                        </button>
+                                    </div>
+                                </div>
+                            </section>
+                        }.into_any()
+                    }
+                    Ok(None) => view! {
+                        <div class="rounded-2xl border border-dashed border-white/10 bg-white/[0.02] p-6 sm:p-8 text-center">
+                            <p class="text-neutral-500 text-sm">"Accounts are being indexed, check back in a few minutes."</p>
+                        </div>
+                    }.into_any(),
+                    Err(e) => view! {
+                        <div class="rounded-2xl border border-dashed border-white/10 bg-white/[0.02] p-6 sm:p-8 text-center">
+                            <p class="text-neutral-500 text-sm">{ e.to_string() }</p>
+                        </div>
+                    }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn DistributionOverview(data: TopHoldersPage) -> impl IntoView {
+    let stats = data.stats;
+
+    view! {
+        <div class="grid gap-4 sm:gap-6 grid-cols-1 sm:grid-cols-2 lg:grid-cols-4">
+            <SimpleCardMetrics title="Holders" description="Accounts with a non-zero balance.">
+                <span class="text-2xl">{ stats.holders }</span>
+            </SimpleCardMetrics>
+            <SimpleCardMetrics title="Gini Coefficient" description="0 for an even distribution, 1 when one account holds everything.">
+                <span class="text-2xl">{ format!("{:.3}", stats.gini) }</span>
+            </SimpleCardMetrics>
+            <SimpleCardMetrics title="Top 10 Share" description="Of the total issuance.">
+                <span class="text-2xl">{ format!("{:.2}%", stats.top10_share) }</span>
+            </SimpleCardMetrics>
+            <SimpleCardMetrics title="Top 100 Share" description="Of the total issuance.">
+                <span class="text-2xl">{ format!("{:.2}%", stats.top100_share) }</span>
+            </SimpleCardMetrics>
+        </div>
+    }
+}
+
+#[component]
+fn HoldersTable(data: TopHoldersPage, ranking: HolderRanking) -> impl IntoView {
+    let total_issuance = data.total_issuance;
+
+    let rows = data
+        .items
+        .into_iter()
+        .map(|holder| view! { <HolderRow holder ranking total_issuance /> })
+        .collect::<Vec<_>>();
+
+    view! {
+        <Card padded=false>
+            <div class="overflow-x-auto">
+                <table class="w-full text-sm">
+                    <thead class="text-[10px] uppercase tracking-wider text-neutral-500">
+                        <tr class="border-b border-white/5">
+                            <th class="px-5 py-3 text-left font-medium">"#"</th>
+                            <th class="px-5 py-3 text-left font-medium">"Account"</th>
+                            <th class="px-5 py-3 text-right font-medium">{ ranking.label() }</th>
+                            <th class="px-5 py-3 text-right font-medium">"Reserved"</th>
+                            <th class="px-5 py-3 text-right font-medium">"% of Issuance"</th>
+                        </tr>
+                    </thead>
+                    <tbody class="font-mono">
+                        { rows }
+                    </tbody>
+                </table>
+            </div>
+        </Card>
+    }
+}
+
+#[component]
+fn HolderRow(holder: TopHolder, ranking: HolderRanking, total_issuance: u128) -> impl IntoView {
+    let amount = ranking.amount(holder.free, holder.reserved, holder.frozen);
+    let name = display_address(&holder.address);
+    let label = if name == holder.address {
+        shorten_address(&holder.address)
+    } else {
+        name
+    };
+
+    view! {
+        <tr class="border-b border-white/5 last:border-0">
+            <td class="px-5 py-3 text-neutral-500">{ holder.rank }</td>
+            <td class="px-5 py-3">
+                <a
+                    href=format!("/accounts/{}", holder.address)
+                    class="flex items-center gap-3 min-w-0 font-sans text-neutral-200 hover:text-emerald-400 transition-colors"
+                    title=holder.address.clone()
+                >
+                    <div inner_html=ss58_identicon_svg(&holder.address, 24) class="shrink-0" />
+                    <span class="truncate">{ label }</span>
+                </a>
+            </td>
+            <td class="px-5 py-3 text-right font-bold text-white whitespace-nowrap">
+                { format_balance(amount, true) }
+            </td>
+            <td class="px-5 py-3 text-right text-neutral-400 whitespace-nowrap">
+                { format_balance(holder.reserved, true) }
+            </td>
+            <td class="px-5 py-3 text-right text-neutral-400 whitespace-nowrap">
+                { format!("{:.4}%", percent_of(amount, total_issuance)) }
+            </td>
+        </tr>
+    }
+}
+
+#[component]
+fn HoldersSkeleton() -> impl IntoView {
+    view! {
+        <div class="flex flex-col gap-4">
+            { (0..3).map(|_| view! {
+                <Card class="h-20 animate-pulse">
+                    <div class="space-y-4 opacity-50">
+                        <div class="h-5 w-1/3 bg-white/10 rounded"></div>
+                        <div class="h-4 w-2/3 bg-white/10 rounded"></div>
+                    </div>
+                </Card>
+            }).collect::<Vec<_>>() }
+        </div>
+    }
+}
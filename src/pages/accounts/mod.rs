@@ -1,21 +1,62 @@
 use crate::{
-    components::toast::use_toast,
+    address_book::label_of,
+    components::{toast::use_toast, watchlist::WatchToggle},
+    resolve_account,
     pages::accounts::{allocations::AccountAllocations, balances::AccountBalances},
     utils::{get_known_address_name, ss58_identicon_svg},
 };
 use leptos::prelude::*;
-use leptos_router::hooks::use_params_map;
+use leptos_router::NavigateOptions;
+use leptos_router::hooks::{use_navigate, use_params_map};
 
 mod allocations;
 mod balances;
+mod holders;
+
+pub use holders::TopHolders;
 
 #[component]
 pub fn Account() -> impl IntoView {
-    let toast = use_toast();
     let params = use_params_map();
 
     let address = Memo::new(move |_| params.read().get("id").unwrap_or_default());
 
+    // Redirect hex keys, other networks' addresses and names to the Allfeat address
+    let canonical = LocalResource::new(move || resolve_account(address.get()));
+    let navigate = use_navigate();
+    Effect::new(move |_| {
+        if let Some(Ok(canonical)) = canonical.get()
+            && canonical != address.get_untracked()
+        {
+            navigate(
+                &format!("/accounts/{canonical}"),
+                NavigateOptions {
+                    replace: true,
+                    ..Default::default()
+                },
+            );
+        }
+    });
+
+    view! {
+        <div class="flex flex-col gap-8 sm:gap-12 fade-in">
+            <AccountHeader address />
+
+            {move || {
+                let id = address.get();
+                view! {
+                    <AccountBalances id=id.clone() />
+                    <AccountAllocations id=id />
+                }
+            }}
+        </div>
+    }
+}
+
+#[component]
+fn AccountHeader(address: Memo<String>) -> impl IntoView {
+    let toast = use_toast();
+
     // Memoize the identicon SVG to avoid recalculating on every render
     let identicon_svg = Memo::new(move |_| ss58_identicon_svg(&address.get(), 64));
 
@@ -27,33 +68,48 @@ pub fn Account() -> impl IntoView {
     };
 
     view! {
-        <div class="flex flex-col gap-8 sm:gap-12 fade-in">
+        // --- HEADER IDENTITY ---
+        <section class="relative pt-2 sm:pt-6">
+            <div class="flex flex-col sm:flex-row items-start sm:items-center gap-4 sm:gap-6">
 
-            // --- HEADER IDENTITY ---
-            <section class="relative pt-2 sm:pt-6">
-                <div class="flex flex-col sm:flex-row items-start sm:items-center gap-4 sm:gap-6">
-
-                    <div class="relative group shrink-0">
-                        <div class="absolute -inset-0.5 bg-gradient-to-br from-emerald-500 to-cyan-500 rounded-full opacity-30 blur group-hover:opacity-60 transition duration-500"></div>
-                        <div class="relative h-12 w-12 sm:h-16 sm:w-16 rounded-full bg-[#050505] ring-2 ring-white/10 flex items-center justify-center overflow-hidden">
-                             <div inner_html=move || identicon_svg.get() />
-                        </div>
+                <div class="relative group shrink-0">
+                    <div class="absolute -inset-0.5 bg-gradient-to-br from-emerald-500 to-cyan-500 rounded-full opacity-30 blur group-hover:opacity-60 transition duration-500"></div>
+                    <div class="relative h-12 w-12 sm:h-16 sm:w-16 rounded-full bg-[#050505] ring-2 ring-white/10 flex items-center justify-center overflow-hidden">
+                         <div inner_html=move || identicon_svg.get() />
                     </div>
+                </div>
 
-                    <div class="flex flex-col gap-1 min-w-0 w-full">
-                        <h1 class="text-[10px] sm:text-xs font-bold uppercase tracking-widest text-emerald-500 mb-0.5 sm:mb-1">
-                            "Account Details"
-                        </h1>
+                <div class="flex flex-col gap-1 min-w-0 w-full">
+                    <h1 class="text-[10px] sm:text-xs font-bold uppercase tracking-widest text-emerald-500 mb-0.5 sm:mb-1">
+                        "Account Details"
+                    </h1>
 
-                        // Show known name if available
-                        { move || get_known_address_name(&address.get()).map(|name| view! {
-                            <h2 class="text-xl sm:text-2xl font-bold text-white mb-1">
-                                {name}
+                    // Show the address book label if available
+                    { move || label_of(&address.get()).map(|label| view! {
+                        <div class="flex flex-wrap items-center gap-2 mb-1">
+                            { label.logo_url.map(|src| view! {
+                                <img src=src alt="" class="h-6 w-6 rounded-full object-cover" />
+                            })}
+                            <h2 class="text-xl sm:text-2xl font-bold text-white">
+                                { label.name }
                             </h2>
+                            <span class="rounded-md border border-white/10 bg-white/5 px-2 py-0.5 text-[10px] font-medium uppercase tracking-wider text-neutral-400">
+                                { label.category.label() }
+                            </span>
+                            { label.verified.then(|| view! {
+                                <span class="rounded-md border border-emerald-500/20 bg-emerald-500/10 px-2 py-0.5 text-[10px] font-medium uppercase tracking-wider text-emerald-400">
+                                    "Verified"
+                                </span>
+                            })}
+                        </div>
+                        { label.description.map(|description| view! {
+                            <p class="text-sm text-neutral-400 mb-1">{ description }</p>
                         })}
+                    }.into_any())}
 
+                    <div class="flex items-center gap-2 sm:gap-3 w-full">
                         <button
-                            class="group flex items-center gap-2 sm:gap-3 text-left transition-all active:scale-[0.98] w-full"
+                            class="group flex items-center gap-2 sm:gap-3 text-left transition-all active:scale-[0.98] min-w-0"
                             on:click=copy_to_clipboard
                             title="Click to copy"
                         >
@@ -71,17 +127,11 @@ pub fn Account() -> impl IntoView {
                                 <svg xmlns="http://www.w3.org/2000/svg" width="14" height="14" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"><rect x="9" y="9" width="13" height="13" rx="2" ry="2"></rect><path d="M5 15H4a2 2 0 0 1-2-2V4a2 2 0 0 1 2-2h9a2 2 0 0 1 2 2v1"></path></svg>
                             </div>
                         </button>
+                        <WatchToggle address=address />
                     </div>
                 </div>
-            </section>
-
-            {move || {
-                let id = address.get();
-                view! {
-                    <AccountBalances id=id.clone() />
-                    <AccountAllocations id=id />
-                }
-            }}
-        </div>
+            </div>
+        </section>
     }
+    .into_any()
 }
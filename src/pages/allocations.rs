@@ -1,6 +1,11 @@
 use crate::{
     EnvelopeAllocation,
-    components::{Card, fetchable_balance::FetchableData, toast::use_toast},
+    components::{
+        Card,
+        fetchable_balance::FetchableData,
+        payout_status::{PayoutHistory, PayoutStatusCard},
+        toast::use_toast,
+    },
     get_allocations,
     utils::{blocks_to_human_duration, blocks_to_str, display_address, format_balance, ss58_identicon_svg},
 };
@@ -8,7 +13,7 @@ use leptos::prelude::*;
 
 #[component]
 pub fn Allocations() -> impl IntoView {
-    let allocations = OnceResource::new(get_allocations());
+    let allocations = Resource::new(|| (), |_| get_allocations());
 
     view! {
         <div class="flex flex-col gap-6 sm:gap-12">
@@ -25,6 +30,8 @@ pub fn Allocations() -> impl IntoView {
                 </p>
             </header>
 
+            <PayoutStatusCard />
+
             // --- GRID ---
             <FetchableData
                 data=allocations
@@ -44,6 +51,17 @@ pub fn Allocations() -> impl IntoView {
                     }.into_any()
                 }}
             />
+
+            // --- PAYOUT HISTORY ---
+            <section>
+                <div class="flex items-center gap-2 mb-4 sm:mb-6">
+                    <div class="h-1 w-1 rounded-full bg-emerald-500"></div>
+                    <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">
+                        "Payout History"
+                    </h2>
+                </div>
+                <PayoutHistory />
+            </section>
         </div>
     }
 }
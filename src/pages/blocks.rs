@@ -0,0 +1,136 @@
+use crate::{
+    BlockDetails, ExtrinsicSummary,
+    components::Card,
+    get_block,
+    utils::{blocks_to_str, format_timestamp, get_known_address_name, shorten_address},
+};
+use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
+
+#[component]
+pub fn Block() -> impl IntoView {
+    let params = use_params_map();
+    let id = Memo::new(move |_| params.read().get("id").unwrap_or_default());
+    let block = Resource::new(move || id.get(), get_block);
+
+    view! {
+        <div class="flex flex-col gap-6 sm:gap-12">
+            <Suspense fallback=move || view! {
+                <Card class="h-40 animate-pulse">
+                    <div class="space-y-4 opacity-50">
+                        <div class="h-6 w-1/3 bg-white/10 rounded"></div>
+                        <div class="h-4 w-2/3 bg-white/10 rounded"></div>
+                    </div>
+                </Card>
+            }>
+                {move || block.get().map(|res| match res {
+                    Ok(block) => view! { <BlockView block /> }.into_any(),
+                    Err(e) => view! {
+                        <div class="rounded-2xl border border-dashed border-white/10 bg-white/[0.02] p-6 sm:p-8 text-center">
+                            <p class="text-neutral-500 text-sm">{ e.to_string() }</p>
+                        </div>
+                    }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn BlockView(block: BlockDetails) -> impl IntoView {
+    let number = block.number;
+    let extrinsic_count = block.extrinsics.len();
+
+    view! {
+        // --- HEADER ---
+        <header class="flex flex-col gap-3 sm:gap-4 pt-2 sm:pt-8">
+            <div class="flex items-center justify-between gap-4">
+                <h1 class="text-3xl sm:text-4xl font-extrabold tracking-tight">
+                    <span class="bg-gradient-to-b from-white to-white/60 bg-clip-text text-transparent">
+                        "Block #" { blocks_to_str(number) }
+                    </span>
+                </h1>
+                <div class="flex gap-2 text-xs">
+                    { (number > 0).then(|| view! {
+                        <a href=format!("/blocks/{}", number - 1) class="rounded-full border border-white/10 px-3 py-1 text-neutral-500 hover:text-white">"Previous"</a>
+                    })}
+                    <a href=format!("/blocks/{}", number + 1) class="rounded-full border border-white/10 px-3 py-1 text-neutral-500 hover:text-white">"Next"</a>
+                </div>
+            </div>
+            <p class="text-neutral-400 text-sm">{ format_timestamp(block.timestamp) }</p>
+        </header>
+
+        <Card>
+            <div class="grid gap-4 sm:grid-cols-2">
+                <div class="min-w-0">
+                    <div class="text-[10px] uppercase text-neutral-500 mb-1">"Hash"</div>
+                    <div class="font-mono text-xs text-neutral-200 break-all">{ block.hash }</div>
+                </div>
+                <div class="min-w-0">
+                    <div class="text-[10px] uppercase text-neutral-500 mb-1">"Parent Hash"</div>
+                    <a href=format!("/blocks/{}", block.parent_hash) class="font-mono text-xs text-neutral-200 break-all hover:text-emerald-400">
+                        { block.parent_hash.clone() }
+                    </a>
+                </div>
+                <div>
+                    <div class="text-[10px] uppercase text-neutral-500 mb-1">"Extrinsics"</div>
+                    <div class="font-mono text-neutral-200">{ extrinsic_count }</div>
+                </div>
+                <div>
+                    <div class="text-[10px] uppercase text-neutral-500 mb-1">"Events"</div>
+                    <div class="font-mono text-neutral-200">{ block.event_count }</div>
+                </div>
+            </div>
+        </Card>
+
+        <Card padded=false header=view! {
+            <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">"Extrinsics"</h2>
+        }.into_any()>
+            <div class="overflow-x-auto">
+                <table class="w-full text-sm">
+                    <thead class="text-[10px] uppercase tracking-wider text-neutral-500">
+                        <tr class="border-b border-white/5">
+                            <th class="px-5 py-3 text-left font-medium">"ID"</th>
+                            <th class="px-5 py-3 text-left font-medium">"Call"</th>
+                            <th class="px-5 py-3 text-left font-medium">"Signer"</th>
+                            <th class="px-5 py-3 text-left font-medium">"Hash"</th>
+                            <th class="px-5 py-3 text-right font-medium">"Result"</th>
+                        </tr>
+                    </thead>
+                    <tbody class="font-mono">
+                        { block.extrinsics.into_iter().map(|ext| view! { <ExtrinsicRow number ext /> }).collect::<Vec<_>>() }
+                    </tbody>
+                </table>
+            </div>
+        </Card>
+    }
+}
+
+#[component]
+fn ExtrinsicRow(number: u32, ext: ExtrinsicSummary) -> impl IntoView {
+    let (result, result_class) = match ext.success {
+        Some(true) => ("Success", "text-emerald-400"),
+        Some(false) => ("Failed", "text-rose-400"),
+        None => ("-", "text-neutral-500"),
+    };
+
+    view! {
+        // Anchor targeted by extrinsic hash searches
+        <tr id=format!("extrinsic-{}", ext.index) class="border-b border-white/5 last:border-0 target:bg-emerald-500/5">
+            <td class="px-5 py-3 text-neutral-400 whitespace-nowrap">{ number } "-" { ext.index }</td>
+            <td class="px-5 py-3 text-neutral-200">{ ext.call }</td>
+            <td class="px-5 py-3">
+                { match ext.signer {
+                    Some(signer) => view! {
+                        <a href=format!("/accounts/{}", signer) class="text-neutral-300 hover:text-emerald-400" title=signer.clone()>
+                            { get_known_address_name(&signer).unwrap_or_else(|| shorten_address(&signer)) }
+                        </a>
+                    }.into_any(),
+                    None => view! { <span class="text-neutral-600">"-"</span> }.into_any(),
+                }}
+            </td>
+            <td class="px-5 py-3 text-neutral-500" title=ext.hash.clone()>{ shorten_address(&ext.hash) }</td>
+            <td class=format!("px-5 py-3 text-right {}", result_class)>{ result }</td>
+        </tr>
+    }
+}
@@ -0,0 +1,223 @@
+use crate::{
+    components::{Card, fetchable_balance::FetchableData},
+    faucet::{CaptchaWidget, Drip, FaucetInfo, get_faucet_info, request_drip},
+    utils::{
+        blocks_to_human_duration, display_address_with_hint, format_age, format_balance,
+        secs_per_block,
+    },
+};
+use leptos::prelude::*;
+use web_sys::{FormData, SubmitEvent};
+
+#[component]
+pub fn Faucet() -> impl IntoView {
+    let info = Resource::new(|| (), |_| get_faucet_info());
+    // Drips sent from this page since it was loaded, newest first
+    let sent = RwSignal::new(Vec::<Drip>::new());
+
+    view! {
+        <div class="flex flex-col gap-6 sm:gap-12">
+
+            // --- HEADER ---
+            <header class="flex flex-col gap-3 sm:gap-4 pt-2 sm:pt-8">
+                <h1 class="text-3xl sm:text-4xl font-extrabold tracking-tight">
+                    <span class="bg-gradient-to-b from-white to-white/60 bg-clip-text text-transparent">
+                        "Testnet Faucet"
+                    </span>
+                </h1>
+                <p class="text-neutral-400 max-w-2xl leading-relaxed text-sm sm:text-base">
+                    "Test tokens to try the network with. They have no value, and the testnet may be reset at any time."
+                </p>
+            </header>
+
+            <FetchableData
+                data=info
+                render={move |info: FaucetInfo| view! {
+                    <DripForm info=info.clone() sent />
+                    <RecentDrips drips=info.recent sent />
+                }.into_any()}
+            />
+        </div>
+    }
+}
+
+fn cooldown_to_str(secs: u64) -> String {
+    blocks_to_human_duration((secs / secs_per_block()) as u32)
+}
+
+#[component]
+fn DripForm(info: FaucetInfo, sent: RwSignal<Vec<Drip>>) -> impl IntoView {
+    let address = RwSignal::new(String::new());
+    let form = NodeRef::<leptos::html::Form>::new();
+    let drip = Action::new(|(address, captcha): &(String, Option<String>)| {
+        request_drip(address.clone(), captcha.clone())
+    });
+
+    Effect::new(move |_| {
+        if let Some(Ok(res)) = drip.value().get() {
+            address.set(String::new());
+            sent.update(|sent| sent.insert(0, res));
+        }
+    });
+
+    let response_field = info.captcha.as_ref().map(|c| c.response_field.clone());
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        let id = address.get().trim().to_string();
+        if id.is_empty() {
+            return;
+        }
+        // Filled in by the captcha script, outside of the view
+        let captcha = response_field.as_ref().and_then(|field| {
+            let form = FormData::new_with_form(&form.get()?).ok()?;
+            form.get(field).as_string()
+        });
+        drip.dispatch((id, captcha));
+    };
+
+    let empty = info.available < info.amount;
+
+    view! {
+        <section>
+            <div class="flex items-center gap-2 mb-4 sm:mb-6">
+                <div class="h-1 w-1 rounded-full bg-emerald-500"></div>
+                <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">
+                    "Request Tokens"
+                </h2>
+            </div>
+
+            <Card>
+                <div class="grid grid-cols-2 sm:grid-cols-4 gap-4 mb-6">
+                    <div>
+                        <div class="text-[10px] uppercase text-neutral-500 mb-1">"Per Request"</div>
+                        <div class="font-mono font-bold text-emerald-400">{ format_balance(info.amount, true) }</div>
+                    </div>
+                    <div>
+                        <div class="text-[10px] uppercase text-neutral-500 mb-1">"Available"</div>
+                        <div class="font-mono text-neutral-200">{ format_balance(info.available, true) }</div>
+                    </div>
+                    <div>
+                        <div class="text-[10px] uppercase text-neutral-500 mb-1">"Per Address"</div>
+                        <div class="font-mono text-neutral-200">"Every " { cooldown_to_str(info.address_cooldown_secs) }</div>
+                    </div>
+                    <div>
+                        <div class="text-[10px] uppercase text-neutral-500 mb-1">"Per Client"</div>
+                        <div class="font-mono text-neutral-200">"Every " { cooldown_to_str(info.ip_cooldown_secs) }</div>
+                    </div>
+                </div>
+
+                <form node_ref=form on:submit=on_submit class="flex flex-col gap-4">
+                    <input
+                        type="text"
+                        spellcheck="false"
+                        placeholder="Address to fund"
+                        class="w-full rounded-xl border border-white/10 bg-black/40 px-3 py-2 font-mono text-sm text-white placeholder:text-neutral-600 focus:outline-none focus:border-emerald-500/50"
+                        prop:value=address
+                        on:input=move |ev| address.set(event_target_value(&ev))
+                    />
+                    { info.captcha.map(|captcha| view! { <Captcha captcha /> }) }
+                    <button
+                        type="submit"
+                        class="self-end rounded-full bg-white/5 px-4 py-2 text-sm text-neutral-300 transition-colors hover:bg-emerald-500 hover:text-white disabled:opacity-40"
+                        disabled=move || empty || drip.pending().get()
+                    >
+                        { move || if drip.pending().get() { "Sending..." } else { "Send tokens" } }
+                    </button>
+                </form>
+
+                { empty.then(|| view! {
+                    <p class="mt-4 text-sm text-amber-400">"The faucet is running low, come back later."</p>
+                }) }
+
+                { move || drip.value().get().map(|res| match res {
+                    Ok(done) => view! {
+                        <p class="mt-4 text-sm text-emerald-400">
+                            "Sent " { format_balance(done.amount, true) } " in block "
+                            <a href=format!("/blocks/{}", done.block_hash) class="font-mono underline hover:text-emerald-300">
+                                { format!("{}…", &done.block_hash[..10.min(done.block_hash.len())]) }
+                            </a>
+                        </p>
+                    }.into_any(),
+                    Err(e) => view! {
+                        <p class="mt-4 text-sm text-rose-400">{ e.to_string() }</p>
+                    }.into_any(),
+                })}
+
+                <p class="mt-4 text-xs text-neutral-600 font-mono truncate" title=info.address.clone()>
+                    "Sent from " { display_address_with_hint(&info.address) }
+                </p>
+            </Card>
+        </section>
+    }
+}
+
+/// Widget rendered by the captcha provider's script, which adds the token to
+/// the form once solved.
+#[component]
+fn Captcha(captcha: CaptchaWidget) -> impl IntoView {
+    view! {
+        <script src=captcha.script_url async defer></script>
+        <div class=captcha.widget_class data-sitekey=captcha.site_key></div>
+    }
+}
+
+#[component]
+fn RecentDrips(drips: Vec<Drip>, sent: RwSignal<Vec<Drip>>) -> impl IntoView {
+    let now = chrono::Utc::now().timestamp_millis() as u64;
+    let all = Signal::derive(move || {
+        let mut all = sent.get();
+        for drip in &drips {
+            if !all.iter().any(|d| d.extrinsic_hash == drip.extrinsic_hash) {
+                all.push(drip.clone());
+            }
+        }
+        all
+    });
+
+    view! {
+        <section>
+            <div class="flex items-center gap-2 mb-4 sm:mb-6">
+                <div class="h-1 w-1 rounded-full bg-emerald-500"></div>
+                <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">
+                    "Recent Drips"
+                </h2>
+            </div>
+
+            <Show
+                when=move || all.with(|all| !all.is_empty())
+                fallback=|| view! {
+                    <div class="rounded-2xl border border-dashed border-white/10 bg-white/[0.02] p-6 sm:p-8 text-center">
+                        <p class="text-neutral-500 text-sm">"No tokens sent yet."</p>
+                    </div>
+                }
+            >
+                <Card padded=false>
+                    <ul class="divide-y divide-white/5">
+                        <For
+                            each=move || all.get()
+                            key=|drip| drip.extrinsic_hash.clone()
+                            children=move |drip| view! {
+                                <li class="flex items-center justify-between gap-4 px-5 py-3 text-sm">
+                                    <a
+                                        href=format!("/accounts/{}", drip.address)
+                                        class="truncate font-mono text-neutral-200 hover:text-emerald-400 transition-colors"
+                                        title=drip.address.clone()
+                                    >
+                                        { display_address_with_hint(&drip.address) }
+                                    </a>
+                                    <span class="shrink-0 font-mono text-emerald-400">{ format_balance(drip.amount, true) }</span>
+                                    <a
+                                        href=format!("/blocks/{}", drip.block_hash)
+                                        class="shrink-0 text-xs text-neutral-500 hover:text-white"
+                                    >
+                                        { format_age(drip.timestamp_ms, now) }
+                                    </a>
+                                </li>
+                            }
+                        />
+                    </ul>
+                </Card>
+            </Show>
+        </section>
+    }
+}
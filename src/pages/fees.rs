@@ -0,0 +1,250 @@
+use crate::{
+    FeeAnalytics, FeeBucket,
+    components::{Card, fetchable_balance::FetchableData, simple_card_metrics::SimpleCardMetrics},
+    estimate_fee, get_fee_analytics,
+    utils::{blocks_to_str, format_balance, format_timestamp, secs_per_block},
+};
+use leptos::prelude::*;
+use web_sys::SubmitEvent;
+
+/// One bar per hour over the last two days.
+const BUCKETS: u32 = 48;
+
+#[component]
+pub fn Fees() -> impl IntoView {
+    let bucket_blocks = (3600 / secs_per_block()).max(1) as u32;
+    let analytics = Resource::new(|| (), move |_| get_fee_analytics(bucket_blocks, BUCKETS));
+
+    view! {
+        <div class="flex flex-col gap-6 sm:gap-12">
+
+            // --- HEADER ---
+            <header class="flex flex-col gap-3 sm:gap-4 pt-2 sm:pt-8">
+                <h1 class="text-3xl sm:text-4xl font-extrabold tracking-tight">
+                    <span class="bg-gradient-to-b from-white to-white/60 bg-clip-text text-transparent">
+                        "Transaction Fees"
+                    </span>
+                </h1>
+                <p class="text-neutral-400 max-w-2xl leading-relaxed text-sm sm:text-base">
+                    "Fees paid by extrinsics, how much of them is burned or sent to the treasury, and the current fee multiplier applied by the network."
+                </p>
+            </header>
+
+            <FetchableData
+                data=analytics
+                render={move |data: FeeAnalytics| view! { <FeeOverview data /> }.into_any()}
+            />
+
+            <FeeEstimator />
+        </div>
+    }
+}
+
+#[component]
+fn FeeOverview(data: FeeAnalytics) -> impl IntoView {
+    let paid: u128 = data.buckets.iter().map(|b| b.paid).sum();
+    let burned: u128 = data.buckets.iter().map(|b| b.burned).sum();
+    let to_treasury: u128 = data.buckets.iter().map(|b| b.to_treasury).sum();
+    let extrinsics: u32 = data.buckets.iter().map(|b| b.extrinsics).sum();
+
+    let per_call = data.per_call.clone();
+    // Fees are only known from the first indexed block on
+    let window = match data.indexed_since {
+        Some(since) if data.buckets.first().is_some_and(|b| since > b.from_block) => {
+            format!("Since block #{}.", blocks_to_str(since))
+        }
+        _ => format!("Over the last {BUCKETS} hours."),
+    };
+
+    view! {
+        <section class="flex flex-col gap-4 sm:gap-6">
+            <div class="grid gap-4 sm:gap-6 grid-cols-1 sm:grid-cols-2 lg:grid-cols-4">
+                <SimpleCardMetrics title="Fees Paid" description=window>
+                    <span class="text-2xl">{ format_balance(paid, true) }</span>
+                </SimpleCardMetrics>
+                <SimpleCardMetrics title="Fees Burned" description="Share of fees not redistributed.">
+                    <span class="text-2xl">{ format_balance(burned, true) }</span>
+                </SimpleCardMetrics>
+                <SimpleCardMetrics title="To Treasury" description="Share of fees deposited to the treasury.">
+                    <span class="text-2xl">{ format_balance(to_treasury, true) }</span>
+                </SimpleCardMetrics>
+                <SimpleCardMetrics title="Fee Multiplier" description="Applied to the weight fee of the next block.">
+                    <span class="text-2xl">{ format!("×{:.6}", data.next_fee_multiplier) }</span>
+                </SimpleCardMetrics>
+            </div>
+
+            <Card header=view! {
+                <div class="flex items-center justify-between">
+                    <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">"Fees per hour"</h2>
+                    <span class="text-xs text-neutral-500">{ extrinsics } " fee-paying extrinsics"</span>
+                </div>
+            }.into_any()>
+                <FeeChart buckets=data.buckets.clone() />
+                { data.indexed_since.map(|block| view! {
+                    <p class="mt-3 text-[10px] text-neutral-600 font-mono">
+                        "Indexed since block #" { blocks_to_str(block) }
+                    </p>
+                })}
+            </Card>
+
+            <Card padded=false header=view! {
+                <div class="flex items-center justify-between">
+                    <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">"Average fee per extrinsic"</h2>
+                    { data.indexed_since.map(|block| view! {
+                        <span class="text-xs text-neutral-500">"Since block #" { blocks_to_str(block) }</span>
+                    })}
+                </div>
+            }.into_any()>
+                { if per_call.is_empty() {
+                    view! {
+                        <p class="p-6 text-center text-sm text-neutral-500">"No fees indexed yet."</p>
+                    }.into_any()
+                } else {
+                    view! {
+                        <div class="overflow-x-auto">
+                            <table class="w-full text-sm">
+                                <thead class="text-[10px] uppercase tracking-wider text-neutral-500">
+                                    <tr class="border-b border-white/5">
+                                        <th class="px-5 py-3 text-left font-medium">"Extrinsic"</th>
+                                        <th class="px-5 py-3 text-right font-medium">"Count"</th>
+                                        <th class="px-5 py-3 text-right font-medium">"Average Fee"</th>
+                                        <th class="px-5 py-3 text-right font-medium">"Total"</th>
+                                    </tr>
+                                </thead>
+                                <tbody class="font-mono">
+                                    { per_call.into_iter().map(|stats| view! {
+                                        <tr class="border-b border-white/5 last:border-0">
+                                            <td class="px-5 py-3 text-neutral-200">{ stats.call }</td>
+                                            <td class="px-5 py-3 text-right text-neutral-400">{ stats.count }</td>
+                                            <td class="px-5 py-3 text-right text-white">{ format_balance(stats.average, true) }</td>
+                                            <td class="px-5 py-3 text-right text-neutral-400">{ format_balance(stats.total, true) }</td>
+                                        </tr>
+                                    }).collect::<Vec<_>>() }
+                                </tbody>
+                            </table>
+                        </div>
+                    }.into_any()
+                }}
+            </Card>
+        </section>
+    }
+}
+
+/// Bar chart of paid fees per bucket, with the burned share overlaid.
+#[component]
+fn FeeChart(buckets: Vec<FeeBucket>) -> impl IntoView {
+    let max = buckets.iter().map(|b| b.paid).max().unwrap_or(0);
+
+    if max == 0 {
+        return view! {
+            <p class="py-10 text-center text-sm text-neutral-500">"No fees paid in this period."</p>
+        }
+        .into_any();
+    }
+
+    view! {
+        <div class="flex flex-col gap-3">
+            <div class="flex h-40 items-end gap-[2px]">
+                { buckets.into_iter().map(|b| {
+                    let paid_pct = b.paid as f64 / max as f64 * 100.0;
+                    let burned_pct = if b.paid == 0 { 0.0 } else { b.burned as f64 / b.paid as f64 * 100.0 };
+                    let title = format!(
+                        "{}\nPaid: {}\nBurned: {}\nExtrinsics: {}",
+                        if b.timestamp > 0 { format_timestamp(b.timestamp) } else { format!("#{}", b.from_block) },
+                        format_balance(b.paid, true),
+                        format_balance(b.burned, true),
+                        b.extrinsics,
+                    );
+                    view! {
+                        <div class="flex-1 h-full flex items-end" title=title>
+                            <div
+                                class="w-full rounded-t-sm bg-emerald-500/30 flex items-end overflow-hidden"
+                                style=format!("height: {:.2}%;", paid_pct)
+                            >
+                                <div class="w-full bg-rose-500/60" style=format!("height: {:.2}%;", burned_pct)></div>
+                            </div>
+                        </div>
+                    }
+                }).collect::<Vec<_>>() }
+            </div>
+            <div class="flex gap-4 text-[10px] text-neutral-500">
+                <span class="flex items-center gap-1.5"><span class="h-2 w-2 rounded-sm bg-emerald-500/30"></span>"Paid"</span>
+                <span class="flex items-center gap-1.5"><span class="h-2 w-2 rounded-sm bg-rose-500/60"></span>"Burned"</span>
+            </div>
+        </div>
+    }
+    .into_any()
+}
+
+#[component]
+fn FeeEstimator() -> impl IntoView {
+    let extrinsic = RwSignal::new(String::new());
+    let estimate = Action::new(|hex: &String| estimate_fee(hex.clone()));
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        let hex = extrinsic.get().trim().to_string();
+        if !hex.is_empty() {
+            estimate.dispatch(hex);
+        }
+    };
+
+    view! {
+        <section>
+            <div class="flex items-center gap-2 mb-4 sm:mb-6">
+                <div class="h-1 w-1 rounded-full bg-emerald-500"></div>
+                <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">
+                    "Fee Estimator"
+                </h2>
+            </div>
+
+            <Card>
+                <form on:submit=on_submit class="flex flex-col gap-4">
+                    <textarea
+                        rows="4"
+                        spellcheck="false"
+                        placeholder="Paste a SCALE encoded extrinsic (0x...)"
+                        class="w-full rounded-xl border border-white/10 bg-black/40 p-3 font-mono text-xs text-white placeholder:text-neutral-600 focus:outline-none focus:border-emerald-500/50"
+                        prop:value=extrinsic
+                        on:input=move |ev| extrinsic.set(event_target_value(&ev))
+                    ></textarea>
+                    <button
+                        type="submit"
+                        class="self-end rounded-full bg-white/5 px-4 py-2 text-sm text-neutral-300 transition-colors hover:bg-emerald-500 hover:text-white disabled:opacity-40"
+                        disabled=move || estimate.pending().get()
+                    >
+                        { move || if estimate.pending().get() { "Estimating..." } else { "Estimate fee" } }
+                    </button>
+                </form>
+
+                { move || estimate.value().get().map(|res| match res {
+                    Ok(fee) => view! {
+                        <div class="mt-4 grid grid-cols-2 sm:grid-cols-4 gap-4 border-t border-dashed border-white/10 pt-4">
+                            <div>
+                                <div class="text-[10px] uppercase text-neutral-500 mb-1">"Partial Fee"</div>
+                                <div class="font-mono font-bold text-emerald-400">{ format_balance(fee.partial_fee, true) }</div>
+                            </div>
+                            <div>
+                                <div class="text-[10px] uppercase text-neutral-500 mb-1">"Class"</div>
+                                <div class="font-mono text-neutral-200">{ fee.class }</div>
+                            </div>
+                            <div>
+                                <div class="text-[10px] uppercase text-neutral-500 mb-1">"Weight"</div>
+                                <div class="font-mono text-neutral-200 truncate" title=format!("proof size: {}", fee.proof_size)>
+                                    { fee.ref_time }
+                                </div>
+                            </div>
+                            <div>
+                                <div class="text-[10px] uppercase text-neutral-500 mb-1">"Length"</div>
+                                <div class="font-mono text-neutral-200">{ fee.length } " bytes"</div>
+                            </div>
+                        </div>
+                    }.into_any(),
+                    Err(e) => view! {
+                        <p class="mt-4 text-sm text-rose-400">{ e.to_string() }</p>
+                    }.into_any(),
+                })}
+            </Card>
+        </section>
+    }
+}
@@ -1,7 +1,21 @@
 mod accounts;
 mod allocations;
+mod blocks;
+#[cfg(feature = "faucet")]
+mod faucet;
+mod fees;
 mod overview;
+mod sudo;
+mod supply;
+mod watchlist;
 
-pub use accounts::Account;
+pub use accounts::{Account, TopHolders};
 pub use allocations::Allocations;
+pub use blocks::Block;
+#[cfg(feature = "faucet")]
+pub use faucet::Faucet;
+pub use fees::Fees;
 pub use overview::Overview;
+pub use sudo::Sudo;
+pub use supply::Supply;
+pub use watchlist::Watchlist;
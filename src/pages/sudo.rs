@@ -0,0 +1,182 @@
+use crate::{
+    SudoActivity, SudoActivityKind,
+    components::{Card, fetchable_balance::ErrorNotice},
+    get_sudo_activity,
+    utils::{blocks_to_str, display_address_with_hint, format_timestamp},
+};
+use leptos::prelude::*;
+
+const PAGE_SIZE: usize = 25;
+
+#[component]
+pub fn Sudo() -> impl IntoView {
+    let page = RwSignal::new(0usize);
+    let activity = Resource::new(
+        move || page.get(),
+        |page| get_sudo_activity(page * PAGE_SIZE, PAGE_SIZE),
+    );
+
+    view! {
+        <div class="flex flex-col gap-6 sm:gap-12">
+
+            // --- HEADER ---
+            <header class="flex flex-col gap-3 sm:gap-4 pt-2 sm:pt-8">
+                <h1 class="text-3xl sm:text-4xl font-extrabold tracking-tight">
+                    <span class="bg-gradient-to-b from-white to-white/60 bg-clip-text text-transparent">
+                        "Sudo Activity"
+                    </span>
+                </h1>
+                <p class="text-neutral-400 max-w-2xl leading-relaxed text-sm sm:text-base">
+                    "Root calls dispatched through the sudo pallet and changes of the sudo key, with the decoded call and its dispatch result."
+                </p>
+                <a
+                    href="/sudo/feed.xml"
+                    target="_blank"
+                    rel="external"
+                    class="self-start rounded-full border border-white/10 bg-white/5 px-3 py-1 text-xs font-mono text-neutral-400 hover:text-emerald-400 hover:border-emerald-500/30 transition-colors"
+                >
+                    "Atom feed"
+                </a>
+            </header>
+
+            <Suspense fallback=move || view! { <ActivitySkeleton /> }>
+                {move || activity.get().map(|res| match res {
+                    Ok(page_data) if !page_data.items.is_empty() => {
+                        let total = page_data.total;
+                        let indexed_since = page_data.indexed_since;
+                        let has_older = (page.get() + 1) * PAGE_SIZE < total;
+                        view! {
+                            <div class="flex flex-col gap-4">
+                                <For
+                                    each=move || page_data.items.clone()
+                                    key=|e| (e.block, e.event_index)
+                                    children=move |entry| view! { <ActivityItem entry /> }
+                                />
+                            </div>
+
+                            <div class="flex items-center justify-between text-xs text-neutral-500">
+                                <span>
+                                    {total} " recorded events"
+                                    { indexed_since.map(|block| view! {
+                                        " since block #" { blocks_to_str(block) }
+                                    })}
+                                </span>
+                                <div class="flex gap-2">
+                                    <button
+                                        class="rounded-full border border-white/10 px-3 py-1 hover:text-white disabled:opacity-30"
+                                        disabled=move || page.get() == 0
+                                        on:click=move |_| page.update(|p| *p = p.saturating_sub(1))
+                                    >
+                                        "Newer"
+                                    </button>
+                                    <button
+                                        class="rounded-full border border-white/10 px-3 py-1 hover:text-white disabled:opacity-30"
+                                        disabled=!has_older
+                                        on:click=move |_| page.update(|p| *p += 1)
+                                    >
+                                        "Older"
+                                    </button>
+                                </div>
+                            </div>
+                        }.into_any()
+                    }
+                    Ok(page_data) => view! {
+                        <div class="rounded-2xl border border-dashed border-white/10 bg-white/[0.02] p-6 sm:p-8 text-center">
+                            <p class="text-neutral-500 text-sm">
+                                { match page_data.indexed_since {
+                                    Some(block) => format!("No sudo activity since block #{}.", blocks_to_str(block)),
+                                    None => "No sudo activity recorded yet.".to_string(),
+                                }}
+                            </p>
+                        </div>
+                    }.into_any(),
+                    Err(error) => view! {
+                        <div class="rounded-2xl border border-dashed border-white/10 bg-white/[0.02] p-6 sm:p-8 text-center">
+                            <ErrorNotice error on_retry=move || activity.refetch() />
+                        </div>
+                    }.into_any(),
+                })}
+            </Suspense>
+        </div>
+    }
+}
+
+#[component]
+fn ActivityItem(entry: SudoActivity) -> impl IntoView {
+    let (status, status_class) = match &entry.result {
+        Some(Ok(())) => (
+            "Success".to_string(),
+            "text-emerald-400 bg-emerald-500/10 border-emerald-500/20",
+        ),
+        Some(Err(e)) => (
+            format!("Failed: {e}"),
+            "text-rose-400 bg-rose-500/10 border-rose-500/20",
+        ),
+        None => (
+            "Key update".to_string(),
+            "text-amber-400 bg-amber-500/10 border-amber-500/20",
+        ),
+    };
+
+    let title = match (&entry.kind, &entry.call) {
+        (SudoActivityKind::KeyChanged | SudoActivityKind::KeyRemoved, _) | (_, None) => {
+            entry.kind.label().to_string()
+        }
+        (_, Some(call)) => call.clone(),
+    };
+
+    view! {
+        <Card>
+            <div class="flex flex-col gap-3">
+                <div class="flex flex-wrap items-center justify-between gap-2">
+                    <div class="flex items-center gap-2 min-w-0">
+                        <span class="text-[10px] font-bold uppercase tracking-wider text-emerald-500 shrink-0">
+                            { entry.kind.label() }
+                        </span>
+                        <span class="text-neutral-400 text-xs shrink-0">"•"</span>
+                        <h3 class="text-sm sm:text-base font-semibold font-mono text-white truncate">{ title }</h3>
+                    </div>
+                    <span class=format!("shrink-0 rounded-md border px-2 py-0.5 text-[10px] sm:text-xs font-medium {}", status_class)>
+                        { status }
+                    </span>
+                </div>
+
+                { entry.account.clone().map(|account| view! {
+                    <div class="text-xs text-neutral-400">
+                        { if entry.kind == SudoActivityKind::KeyChanged { "New key: " } else { "As: " } }
+                        <a href=format!("/accounts/{}", account) class="font-mono text-neutral-300 hover:text-emerald-400 break-all">
+                            { display_address_with_hint(&account) }
+                        </a>
+                    </div>
+                })}
+
+                { entry.call_args.clone().filter(|_| entry.call.is_some()).map(|args| view! {
+                    <pre class="rounded bg-black/40 border border-white/5 p-3 text-[11px] text-neutral-400 font-mono whitespace-pre-wrap break-all">
+                        { args }
+                    </pre>
+                })}
+
+                <div class="flex flex-wrap justify-between gap-2 text-[10px] text-neutral-500 font-mono">
+                    <span title=entry.block_hash.clone()>"Block #" { blocks_to_str(entry.block) }</span>
+                    <span>{ format_timestamp(entry.timestamp) }</span>
+                </div>
+            </div>
+        </Card>
+    }
+}
+
+#[component]
+fn ActivitySkeleton() -> impl IntoView {
+    view! {
+        <div class="flex flex-col gap-4">
+            { (0..3).map(|_| view! {
+                <Card class="h-28 animate-pulse">
+                    <div class="space-y-4 opacity-50">
+                        <div class="h-5 w-1/3 bg-white/10 rounded"></div>
+                        <div class="h-4 w-2/3 bg-white/10 rounded"></div>
+                    </div>
+                </Card>
+            }).collect::<Vec<_>>() }
+        </div>
+    }
+}
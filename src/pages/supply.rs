@@ -0,0 +1,227 @@
+use crate::{
+    SupplyBreakdown,
+    components::{
+        Card,
+        fetchable_balance::FetchableData,
+        supply_breakdown::{SupplyBar, percent_of_issuance},
+    },
+    get_supply_breakdown,
+    supply::SupplyMethodology,
+    utils::{blocks_to_str, format_balance},
+};
+use leptos::prelude::*;
+
+#[component]
+pub fn Supply() -> impl IntoView {
+    let breakdown = Resource::new(|| (), |_| get_supply_breakdown());
+
+    view! {
+        <div class="flex flex-col gap-6 sm:gap-12">
+
+            // --- HEADER ---
+            <header class="flex flex-col gap-3 sm:gap-4 pt-2 sm:pt-8">
+                <h1 class="text-3xl sm:text-4xl font-extrabold tracking-tight">
+                    <span class="bg-gradient-to-b from-white to-white/60 bg-clip-text text-transparent">
+                        "Supply Methodology"
+                    </span>
+                </h1>
+                <p class="text-neutral-400 max-w-2xl leading-relaxed text-sm sm:text-base">
+                    "How the circulating supply shown by the explorer is computed, with every on-chain value it is derived from so the figure can be reconciled independently."
+                </p>
+            </header>
+
+            <Card>
+                <div class="flex flex-col gap-3 text-sm text-neutral-400 leading-relaxed">
+                    <p>
+                        "Tokens enter circulation through the envelopes of the token allocation pallet. The amount an envelope has handed out is stored in "
+                        <code class="font-mono text-neutral-200">"TokenAllocation::EnvelopeDistributed"</code>
+                        ". Part of it is still vesting: for each allocation, "
+                        <code class="font-mono text-neutral-200">"vested_total - released"</code>
+                        " of "
+                        <code class="font-mono text-neutral-200">"TokenAllocation::Allocations"</code>
+                        " has not been released to its beneficiary yet."
+                    </p>
+                    <pre class="rounded bg-black/40 border border-white/5 p-3 text-xs text-emerald-400 font-mono whitespace-pre-wrap">
+                        "circulating = Σ EnvelopeDistributed − Σ (vested_total − released)"
+                    </pre>
+                    <p>
+                        "Envelope caps not distributed yet and any issuance outside the envelopes are not circulating. The treasury, reserved and frozen balances are part of this figure; the alternative methodologies below subtract some of them."
+                    </p>
+                </div>
+            </Card>
+
+            <FetchableData
+                data=breakdown
+                render={move |data: SupplyBreakdown| view! { <SupplyDetails data /> }.into_any()}
+            />
+        </div>
+    }
+}
+
+#[component]
+fn SupplyDetails(data: SupplyBreakdown) -> impl IntoView {
+    let row = |label: &'static str, amount: u128, data: &SupplyBreakdown| {
+        view! {
+            <tr class="border-b border-white/5 last:border-0">
+                <td class="px-5 py-3 font-sans text-neutral-300">{ label }</td>
+                <td class="px-5 py-3 text-right text-white whitespace-nowrap">{ format_balance(amount, true) }</td>
+                <td class="px-5 py-3 text-right text-neutral-500 whitespace-nowrap">
+                    { format!("{:.2}%", percent_of_issuance(amount, data)) }
+                </td>
+            </tr>
+        }
+    };
+
+    let components = vec![
+        row("Total Issuance", data.total_issuance, &data),
+        row("Distributed by Envelopes", data.distributed(), &data),
+        row("Locked in Vesting", data.locked_in_vesting(), &data),
+        row("Circulating Supply", data.circulating(), &data),
+        row("Undistributed Envelope Caps", data.undistributed(), &data),
+        row("Outside Envelopes", data.outside_envelopes(), &data),
+        row("Treasury (free)", data.treasury, &data),
+    ];
+
+    let envelopes = data
+        .envelopes
+        .iter()
+        .map(|e| {
+            view! {
+                <tr class="border-b border-white/5 last:border-0">
+                    <td class="px-5 py-3 font-sans text-neutral-300">{ e.envelope.clone() }</td>
+                    <td class="px-5 py-3 text-right text-neutral-400 whitespace-nowrap">{ format_balance(e.total_cap, true) }</td>
+                    <td class="px-5 py-3 text-right text-white whitespace-nowrap">{ format_balance(e.distributed, true) }</td>
+                    <td class="px-5 py-3 text-right text-amber-400/80 whitespace-nowrap">{ format_balance(e.locked_in_vesting, true) }</td>
+                    <td class="px-5 py-3 text-right text-neutral-400 whitespace-nowrap">{ format_balance(e.undistributed(), true) }</td>
+                </tr>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let snapshot_row = |label: &'static str, amount: Option<u128>, data: &SupplyBreakdown| {
+        match amount {
+            Some(amount) => row(label, amount, data).into_any(),
+            None => view! {
+                <tr class="border-b border-white/5 last:border-0">
+                    <td class="px-5 py-3 font-sans text-neutral-300">{ label }</td>
+                    <td colspan="2" class="px-5 py-3 text-right text-neutral-500">"Not indexed yet"</td>
+                </tr>
+            }
+            .into_any(),
+        }
+    };
+    let snapshot_rows = vec![
+        snapshot_row("Reserved Balances", data.reserved, &data),
+        snapshot_row("Frozen Balances", data.frozen, &data),
+    ];
+
+    let methodologies = SupplyMethodology::ALL
+        .into_iter()
+        .map(|methodology| {
+            let value = match methodology.circulating(&data) {
+                Ok(amount) => format_balance(amount, true),
+                Err(e) => e,
+            };
+            let is_default = methodology == data.methodology;
+            view! {
+                <tr class="border-b border-white/5 last:border-0">
+                    <td class="px-5 py-3 font-sans">
+                        <div class="flex items-center gap-2">
+                            <span class="text-neutral-200">{ methodology.label() }</span>
+                            { is_default.then(|| view! {
+                                <span class="rounded-md border border-emerald-500/20 bg-emerald-500/10 px-2 py-0.5 text-[10px] font-medium uppercase tracking-wider text-emerald-400">
+                                    "Default"
+                                </span>
+                            })}
+                        </div>
+                        <p class="mt-1 text-xs text-neutral-500">{ methodology.description() }</p>
+                        <code class="text-[10px] text-neutral-600">{ methodology.id() }</code>
+                    </td>
+                    <td class="px-5 py-3 text-right text-white whitespace-nowrap align-top">{ value }</td>
+                </tr>
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let holders_at = data.holders_at;
+    let block = data.block;
+
+    view! {
+        <section class="flex flex-col gap-4 sm:gap-6">
+            <Card>
+                <SupplyBar data=data.clone() />
+            </Card>
+
+            <Card padded=false header=view! {
+                <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">"Components"</h2>
+            }.into_any()>
+                <div class="overflow-x-auto">
+                    <table class="w-full text-sm">
+                        <thead class="text-[10px] uppercase tracking-wider text-neutral-500">
+                            <tr class="border-b border-white/5">
+                                <th class="px-5 py-3 text-left font-medium">"Component"</th>
+                                <th class="px-5 py-3 text-right font-medium">"Amount"</th>
+                                <th class="px-5 py-3 text-right font-medium">"% of Issuance"</th>
+                            </tr>
+                        </thead>
+                        <tbody class="font-mono">
+                            { components }
+                            { snapshot_rows }
+                        </tbody>
+                    </table>
+                </div>
+                { holders_at.map(|at| view! {
+                    <p class="px-5 pb-4 text-[10px] text-neutral-600 font-mono">
+                        "Reserved and frozen balances from the account snapshot at block #" { blocks_to_str(at) }
+                    </p>
+                })}
+            </Card>
+
+            <Card padded=false header=view! {
+                <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">"Methodologies"</h2>
+            }.into_any()>
+                <div class="overflow-x-auto">
+                    <table class="w-full text-sm">
+                        <thead class="text-[10px] uppercase tracking-wider text-neutral-500">
+                            <tr class="border-b border-white/5">
+                                <th class="px-5 py-3 text-left font-medium">"Methodology"</th>
+                                <th class="px-5 py-3 text-right font-medium">"Circulating Supply"</th>
+                            </tr>
+                        </thead>
+                        <tbody class="font-mono">
+                            { methodologies }
+                        </tbody>
+                    </table>
+                </div>
+                <p class="px-5 pb-4 text-[10px] text-neutral-600 font-mono">
+                    "Select one with the methodology parameter of get_circulating_supply, or set CIRCULATING_SUPPLY_METHODOLOGY to change the default."
+                </p>
+            </Card>
+
+            <Card padded=false header=view! {
+                <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">"Per Envelope"</h2>
+            }.into_any()>
+                <div class="overflow-x-auto">
+                    <table class="w-full text-sm">
+                        <thead class="text-[10px] uppercase tracking-wider text-neutral-500">
+                            <tr class="border-b border-white/5">
+                                <th class="px-5 py-3 text-left font-medium">"Envelope"</th>
+                                <th class="px-5 py-3 text-right font-medium">"Cap"</th>
+                                <th class="px-5 py-3 text-right font-medium">"Distributed"</th>
+                                <th class="px-5 py-3 text-right font-medium">"Locked in Vesting"</th>
+                                <th class="px-5 py-3 text-right font-medium">"Undistributed"</th>
+                            </tr>
+                        </thead>
+                        <tbody class="font-mono">
+                            { envelopes }
+                        </tbody>
+                    </table>
+                </div>
+            </Card>
+
+            <p class="text-[10px] text-neutral-600 font-mono">
+                "All values read at block #" { blocks_to_str(block) }
+            </p>
+        </section>
+    }
+}
@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+
+use crate::{
+    AccountResult, Allocation, AppError, Balances,
+    components::{
+        Card,
+        simple_card_metrics::SimpleCardMetrics,
+        toast::use_toast,
+        watchlist::{WatchedAccount, use_watchlist},
+    },
+    get_allocations_of_many, get_balances_of_many, resolve_account,
+    utils::{format_balance, get_known_address_name, shorten_address, ss58_identicon_svg},
+};
+use leptos::prelude::*;
+use web_sys::SubmitEvent;
+
+/// Balances and allocations of one watched account.
+#[derive(Clone, Debug)]
+struct Holdings {
+    balances: Balances,
+    allocations: Vec<Allocation>,
+}
+
+/// Holdings by address, an account failing on either query carrying the error.
+fn holdings_by_address(
+    balances: Vec<AccountResult<Balances>>,
+    allocations: Vec<AccountResult<Vec<Allocation>>>,
+) -> HashMap<String, Result<Holdings, AppError>> {
+    balances
+        .into_iter()
+        .zip(allocations)
+        .map(|(balances, allocations)| {
+            let holdings = balances.result.and_then(|balances| {
+                Ok(Holdings {
+                    balances,
+                    allocations: allocations.result?,
+                })
+            });
+            (balances.id, holdings)
+        })
+        .collect()
+}
+
+/// Balance held by the account itself, free and reserved.
+fn balance_of(holdings: &Holdings) -> u128 {
+    holdings.balances.free + holdings.balances.reserved
+}
+
+/// Allocated tokens not released to the account yet.
+fn unreleased_of(holdings: &Holdings) -> u128 {
+    holdings
+        .allocations
+        .iter()
+        .map(|a| a.total.saturating_sub(a.released))
+        .sum()
+}
+
+/// Percent-encodes `data` for a `data:` URL.
+fn encode_data_url(data: &str) -> String {
+    let mut url = String::from("data:application/json;charset=utf-8,");
+    for byte in data.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            url.push(byte as char);
+        } else {
+            url.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    url
+}
+
+#[component]
+pub fn Watchlist() -> impl IntoView {
+    let watchlist = use_watchlist();
+
+    // Labels are private, only addresses are sent to the server
+    let addresses = Memo::new(move |_| {
+        watchlist.accounts.with(|accounts| {
+            accounts
+                .iter()
+                .map(|a| a.address.clone())
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let holdings = LocalResource::new(move || {
+        let ids = addresses.get();
+        async move {
+            if ids.is_empty() {
+                return Ok(HashMap::new());
+            }
+            // Per-account errors are kept, only a failing batch fails the page
+            let (balances, allocations) = futures::try_join!(
+                get_balances_of_many(ids.clone()),
+                get_allocations_of_many(ids)
+            )?;
+            Ok::<_, AppError>(holdings_by_address(balances, allocations))
+        }
+    });
+
+    view! {
+        <div class="flex flex-col gap-6 sm:gap-12">
+
+            // --- HEADER ---
+            <header class="flex flex-col gap-3 sm:gap-4 pt-2 sm:pt-8">
+                <h1 class="text-3xl sm:text-4xl font-extrabold tracking-tight">
+                    <span class="bg-gradient-to-b from-white to-white/60 bg-clip-text text-transparent">
+                        "Watchlist"
+                    </span>
+                </h1>
+                <p class="text-neutral-400 max-w-2xl leading-relaxed text-sm sm:text-base">
+                    "Accounts you follow, with your own labels. The list is stored in this browser only; export it to move it to another device."
+                </p>
+            </header>
+
+            <Transition fallback=move || view! {
+                <Card class="h-40 animate-pulse">
+                    <div class="space-y-4 opacity-50">
+                        <div class="h-6 w-1/3 bg-white/10 rounded"></div>
+                        <div class="h-4 w-2/3 bg-white/10 rounded"></div>
+                    </div>
+                </Card>
+            }>
+                {move || {
+                    if !watchlist.loaded.get() {
+                        return None;
+                    }
+                    holdings.get().map(|res| match res {
+                        Ok(holdings) => view! { <WatchlistView holdings /> }.into_any(),
+                        Err(e) => view! {
+                            <div class="rounded-2xl border border-dashed border-white/10 bg-white/[0.02] p-6 sm:p-8 text-center">
+                                <p class="text-neutral-500 text-sm">{ e.to_string() }</p>
+                            </div>
+                        }.into_any(),
+                    })
+                }}
+            </Transition>
+
+            <AddAccount />
+            <ImportExport />
+        </div>
+    }
+    .into_any()
+}
+
+#[component]
+fn WatchlistView(holdings: HashMap<String, Result<Holdings, AppError>>) -> impl IntoView {
+    let watchlist = use_watchlist();
+    let accounts = watchlist.accounts.get_untracked();
+
+    if accounts.is_empty() {
+        return view! {
+            <div class="rounded-2xl border border-dashed border-white/10 bg-white/[0.02] p-6 sm:p-8 text-center">
+                <p class="text-neutral-500 text-sm">
+                    "No account watched yet. Use the star next to an address, or add one below."
+                </p>
+            </div>
+        }
+        .into_any();
+    }
+
+    let balance: u128 = holdings.values().flatten().map(balance_of).sum();
+    let unreleased: u128 = holdings.values().flatten().map(unreleased_of).sum();
+
+    let rows = accounts
+        .into_iter()
+        .map(|account| {
+            let holdings = holdings.get(&account.address).cloned();
+            view! { <WatchlistRow account holdings /> }
+        })
+        .collect::<Vec<_>>();
+
+    view! {
+        <section class="flex flex-col gap-4 sm:gap-6">
+            <div class="grid gap-4 sm:gap-6 grid-cols-1 sm:grid-cols-3">
+                <SimpleCardMetrics title="Total Holdings" description="Balances and unreleased allocations of every watched account.">
+                    <span class="text-2xl">{ format_balance(balance + unreleased, true) }</span>
+                </SimpleCardMetrics>
+                <SimpleCardMetrics title="Balances" description="Free and reserved balances.">
+                    <span class="text-2xl">{ format_balance(balance, true) }</span>
+                </SimpleCardMetrics>
+                <SimpleCardMetrics title="Unreleased Allocations" description="Allocated tokens still to be released.">
+                    <span class="text-2xl">{ format_balance(unreleased, true) }</span>
+                </SimpleCardMetrics>
+            </div>
+
+            <Card padded=false>
+                <div class="overflow-x-auto">
+                    <table class="w-full text-sm">
+                        <thead class="text-[10px] uppercase tracking-wider text-neutral-500">
+                            <tr class="border-b border-white/5">
+                                <th class="px-5 py-3 text-left font-medium">"Account"</th>
+                                <th class="px-5 py-3 text-right font-medium">"Transferable"</th>
+                                <th class="px-5 py-3 text-right font-medium">"Reserved"</th>
+                                <th class="px-5 py-3 text-right font-medium">"Unreleased"</th>
+                                <th class="px-5 py-3 text-right font-medium">"Total"</th>
+                                <th class="px-5 py-3"></th>
+                            </tr>
+                        </thead>
+                        <tbody class="font-mono">
+                            { rows }
+                        </tbody>
+                    </table>
+                </div>
+            </Card>
+        </section>
+    }
+    .into_any()
+}
+
+#[component]
+fn WatchlistRow(
+    account: WatchedAccount,
+    holdings: Option<Result<Holdings, AppError>>,
+) -> impl IntoView {
+    let watchlist = use_watchlist();
+    let error = holdings
+        .as_ref()
+        .and_then(|h| h.as_ref().err())
+        .map(|e| e.to_string());
+    let holdings = holdings.and_then(Result::ok);
+    let address = account.address.clone();
+    let placeholder = get_known_address_name(&address).unwrap_or_else(|| "Add a label".to_string());
+
+    let amount = move |value: Option<u128>| match value {
+        Some(value) => format_balance(value, true),
+        None => "-".to_string(),
+    };
+
+    view! {
+        <tr class="border-b border-white/5 last:border-0">
+            <td class="px-5 py-3">
+                <div class="flex items-center gap-3 min-w-0">
+                    <div inner_html=ss58_identicon_svg(&address, 28) class="shrink-0" />
+                    <div class="flex flex-col min-w-0">
+                        <input
+                            type="text"
+                            placeholder=placeholder
+                            class="bg-transparent font-sans text-sm text-white placeholder:text-neutral-500 focus:outline-none focus:text-emerald-50"
+                            prop:value=account.label.unwrap_or_default()
+                            on:change={
+                                let address = address.clone();
+                                move |ev| watchlist.set_label(&address, &event_target_value(&ev))
+                            }
+                        />
+                        <a href=format!("/accounts/{}", address) class="text-[10px] text-neutral-500 hover:text-emerald-400" title=address.clone()>
+                            { shorten_address(&address) }
+                        </a>
+                        { error.map(|e| view! { <span class="text-[10px] text-rose-400 font-sans">{ e }</span> }) }
+                    </div>
+                </div>
+            </td>
+            <td class="px-5 py-3 text-right text-neutral-200 whitespace-nowrap">
+                { amount(holdings.as_ref().map(|h| h.balances.free)) }
+            </td>
+            <td class="px-5 py-3 text-right text-neutral-400 whitespace-nowrap">
+                { amount(holdings.as_ref().map(|h| h.balances.reserved)) }
+            </td>
+            <td class="px-5 py-3 text-right text-neutral-400 whitespace-nowrap">
+                { amount(holdings.as_ref().map(unreleased_of)) }
+            </td>
+            <td class="px-5 py-3 text-right font-bold text-white whitespace-nowrap">
+                { amount(holdings.as_ref().map(|h| balance_of(h) + unreleased_of(h))) }
+            </td>
+            <td class="px-5 py-3 text-right">
+                <button
+                    class="text-xs text-neutral-500 hover:text-rose-400 transition-colors"
+                    on:click=move |_| watchlist.remove(&address)
+                >
+                    "Remove"
+                </button>
+            </td>
+        </tr>
+    }
+}
+
+#[component]
+fn AddAccount() -> impl IntoView {
+    let toast = use_toast();
+    let watchlist = use_watchlist();
+    let input = RwSignal::new(String::new());
+
+    // Resolves names, public keys and other networks' addresses
+    let resolve = Action::new(|id: &String| resolve_account(id.clone()));
+
+    Effect::new(move |_| {
+        let Some(res) = resolve.value().get() else {
+            return;
+        };
+        let res = res
+            .map_err(|e| e.to_string())
+            .and_then(|address| watchlist.add(&address));
+        match res {
+            Ok(true) => input.set(String::new()),
+            Ok(false) => (toast.add_toast)("Account already watched".to_string()),
+            Err(e) => (toast.add_toast)(e),
+        }
+    });
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        let id = input.get().trim().to_string();
+        if !id.is_empty() {
+            resolve.dispatch(id);
+        }
+    };
+
+    view! {
+        <section>
+            <div class="flex items-center gap-2 mb-4 sm:mb-6">
+                <div class="h-1 w-1 rounded-full bg-emerald-500"></div>
+                <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">
+                    "Add Account"
+                </h2>
+            </div>
+
+            <Card>
+                <form on:submit=on_submit class="flex flex-col sm:flex-row gap-3">
+                    <input
+                        type="text"
+                        spellcheck="false"
+                        placeholder="Address, public key or name"
+                        class="flex-1 rounded-xl border border-white/10 bg-black/40 px-3 py-2 font-mono text-sm text-white placeholder:text-neutral-600 focus:outline-none focus:border-emerald-500/50"
+                        prop:value=input
+                        on:input=move |ev| input.set(event_target_value(&ev))
+                    />
+                    <button
+                        type="submit"
+                        class="rounded-full bg-white/5 px-4 py-2 text-sm text-neutral-300 transition-colors hover:bg-emerald-500 hover:text-white disabled:opacity-40"
+                        disabled=move || resolve.pending().get()
+                    >
+                        "Watch"
+                    </button>
+                </form>
+            </Card>
+        </section>
+    }
+}
+
+#[component]
+fn ImportExport() -> impl IntoView {
+    let toast = use_toast();
+    let watchlist = use_watchlist();
+    let json = RwSignal::new(String::new());
+    let copy_toast = toast.clone();
+
+    let on_import = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        match watchlist.import(&json.get()) {
+            Ok(count) => {
+                json.set(String::new());
+                (toast.add_toast)(format!("Imported {count} accounts"));
+            }
+            Err(e) => (toast.add_toast)(e),
+        }
+    };
+
+    let copy_export = move |_| {
+        let _ = window()
+            .navigator()
+            .clipboard()
+            .write_text(&watchlist.export());
+        (copy_toast.add_toast)("Watchlist copied to clipboard".to_string());
+    };
+
+    view! {
+        <section>
+            <div class="flex items-center gap-2 mb-4 sm:mb-6">
+                <div class="h-1 w-1 rounded-full bg-emerald-500"></div>
+                <h2 class="text-sm font-mono uppercase tracking-wider text-neutral-400">
+                    "Import / Export"
+                </h2>
+            </div>
+
+            <Card>
+                <form on:submit=on_import class="flex flex-col gap-4">
+                    <textarea
+                        rows="4"
+                        spellcheck="false"
+                        placeholder="Paste an exported watchlist (JSON)"
+                        class="w-full rounded-xl border border-white/10 bg-black/40 p-3 font-mono text-xs text-white placeholder:text-neutral-600 focus:outline-none focus:border-emerald-500/50"
+                        prop:value=json
+                        on:input=move |ev| json.set(event_target_value(&ev))
+                    ></textarea>
+                    <div class="flex flex-wrap justify-end gap-2">
+                        <a
+                            href=move || encode_data_url(&watchlist.export())
+                            download="watchlist.json"
+                            class="rounded-full bg-white/5 px-4 py-2 text-sm text-neutral-300 transition-colors hover:bg-white/10 hover:text-white"
+                        >
+                            "Download"
+                        </a>
+                        <button
+                            type="button"
+                            class="rounded-full bg-white/5 px-4 py-2 text-sm text-neutral-300 transition-colors hover:bg-white/10 hover:text-white"
+                            on:click=copy_export
+                        >
+                            "Copy"
+                        </button>
+                        <button
+                            type="submit"
+                            class="rounded-full bg-white/5 px-4 py-2 text-sm text-neutral-300 transition-colors hover:bg-emerald-500 hover:text-white disabled:opacity-40"
+                            disabled=move || json.with(|j| j.trim().is_empty())
+                        >
+                            "Import"
+                        </button>
+                    </div>
+                </form>
+            </Card>
+        </section>
+    }
+}
@@ -0,0 +1,527 @@
+//! Rate limiting of the server functions and page renders.
+//!
+//! Requests are sorted into classes, each with a token bucket per client IP
+//! and optionally one shared by all clients. Storage scans also wait for one
+//! of a few permits, whether they serve an API call or a page render, so a
+//! burst from many clients can't pile up scans against the node. Rejected calls get a 429 carrying
+//! `AppError::RateLimited`, which the server function client decodes as is.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::AppError;
+use crate::state::AppState;
+use crate::telemetry::server_fn_name;
+
+/// Server functions scanning storage or querying many accounts.
+const EXPENSIVE_FNS: [&str; 6] = [
+    "get_allocations_of",
+    "get_allocations_of_many",
+    "get_balances_of_many",
+    "get_circulating_supply",
+    "get_supply_breakdown",
+    "get_payout_history",
+];
+
+/// Server functions holding a stream open.
+const STREAM_FNS: [&str; 2] = ["get_block_stream", "subscribe_metrics"];
+
+/// Clients tracked at once. New clients are rejected while the map is full,
+/// until the sweeper drops idle buckets.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// How often buckets of idle clients are dropped.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long a scan waits for a permit before being rejected.
+const SCAN_PERMIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+const DEFAULT_MAX_CONCURRENT_SCANS: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Class {
+    /// Server-side rendered pages, which call server functions directly
+    Page,
+    Standard,
+    Expensive,
+    Stream,
+}
+
+impl Class {
+    fn of(path: &str) -> Option<Self> {
+        if let Some(name) = server_fn_name(path) {
+            return Some(if EXPENSIVE_FNS.contains(&name) {
+                Self::Expensive
+            } else if STREAM_FNS.contains(&name) {
+                Self::Stream
+            } else {
+                Self::Standard
+            });
+        }
+        // Static files
+        if path.starts_with("/pkg/") || path.rsplit('/').next().is_some_and(|f| f.contains('.')) {
+            return None;
+        }
+        Some(Self::Page)
+    }
+}
+
+/// `requests` allowed per `period`, in bursts of at most `requests`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quota {
+    pub requests: u32,
+    pub period: Duration,
+}
+
+impl Quota {
+    pub fn new(requests: u32, period: Duration) -> Self {
+        Self { requests, period }
+    }
+}
+
+/// Parses `<requests>/<seconds>`, or `off` for no limit.
+fn parse_quota(value: &str) -> Result<Option<Quota>, String> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("off") {
+        return Ok(None);
+    }
+
+    let (requests, secs) = value
+        .split_once('/')
+        .ok_or_else(|| format!("Invalid quota {value:?}, expected <requests>/<seconds> or off"))?;
+    let requests = u32::from_str(requests.trim())
+        .map_err(|e| format!("Invalid request count in quota {value:?}: {e}"))?;
+    let secs = u64::from_str(secs.trim())
+        .map_err(|e| format!("Invalid period in quota {value:?}: {e}"))?;
+    if requests == 0 || secs == 0 {
+        return Err(format!("Invalid quota {value:?}, use off to disable it"));
+    }
+
+    Ok(Some(Quota::new(requests, Duration::from_secs(secs))))
+}
+
+/// A quota as written in the configuration.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct QuotaSetting(pub Option<Quota>);
+
+impl TryFrom<String> for QuotaSetting {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_quota(&value).map(Self)
+    }
+}
+
+impl From<QuotaSetting> for String {
+    fn from(setting: QuotaSetting) -> Self {
+        match setting.0 {
+            Some(quota) => format!("{}/{}", quota.requests, quota.period.as_secs()),
+            None => "off".to_string(),
+        }
+    }
+}
+
+/// Quotas of each class, per client and for all clients (`_global`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub page: QuotaSetting,
+    pub page_global: QuotaSetting,
+    pub standard: QuotaSetting,
+    pub standard_global: QuotaSetting,
+    /// Storage scans and batched queries
+    pub expensive: QuotaSetting,
+    pub expensive_global: QuotaSetting,
+    pub stream: QuotaSetting,
+    pub stream_global: QuotaSetting,
+    /// Proxies in front of the explorer, the client IP being read from
+    /// `X-Forwarded-For` as seen by the outermost one
+    pub trusted_proxy_hops: usize,
+    /// Storage scans running at the same time
+    pub max_concurrent_scans: usize,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        let minute = |requests| QuotaSetting(Some(Quota::new(requests, Duration::from_secs(60))));
+        let off = QuotaSetting(None);
+
+        Self {
+            page: minute(120),
+            page_global: off,
+            standard: minute(300),
+            standard_global: off,
+            expensive: minute(20),
+            expensive_global: minute(200),
+            stream: minute(10),
+            stream_global: off,
+            trusted_proxy_hops: 0,
+            max_concurrent_scans: DEFAULT_MAX_CONCURRENT_SCANS,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn full(quota: &Quota, now: Instant) -> Self {
+        Self {
+            tokens: quota.requests as f64,
+            updated: now,
+        }
+    }
+
+    /// Refills the bucket, and tells how long until a token is available if
+    /// there is none.
+    fn refill(&mut self, quota: &Quota, now: Instant) -> Result<(), Duration> {
+        let rate = quota.requests as f64 / quota.period.as_secs_f64();
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(quota.requests as f64);
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    quotas: HashMap<Class, (Option<Quota>, Option<Quota>)>,
+    /// Proxies in front of the explorer, each appending to `X-Forwarded-For`
+    trusted_proxy_hops: usize,
+    clients: Mutex<HashMap<(Class, IpAddr), Bucket>>,
+    global: Mutex<HashMap<Class, Bucket>>,
+    scans: Semaphore,
+}
+
+impl RateLimiter {
+    pub fn new(
+        quotas: HashMap<Class, (Option<Quota>, Option<Quota>)>,
+        trusted_proxy_hops: usize,
+        max_concurrent_scans: usize,
+    ) -> Self {
+        Self {
+            quotas,
+            trusted_proxy_hops,
+            clients: Default::default(),
+            global: Default::default(),
+            scans: Semaphore::new(max_concurrent_scans),
+        }
+    }
+
+    pub fn from_config(config: &RateLimitConfig) -> Self {
+        let quotas = HashMap::from([
+            (Class::Page, (config.page.0, config.page_global.0)),
+            (
+                Class::Standard,
+                (config.standard.0, config.standard_global.0),
+            ),
+            (
+                Class::Expensive,
+                (config.expensive.0, config.expensive_global.0),
+            ),
+            (Class::Stream, (config.stream.0, config.stream_global.0)),
+        ]);
+
+        Self::new(
+            quotas,
+            config.trusted_proxy_hops,
+            config.max_concurrent_scans,
+        )
+    }
+
+    /// Address of the client, read from `X-Forwarded-For` behind trusted
+    /// proxies. Entries added before the last trusted proxy are ignored,
+    /// clients being free to send any.
+    pub fn client_ip(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Option<IpAddr> {
+        if self.trusted_proxy_hops == 0 {
+            return peer;
+        }
+
+        let forwarded: Vec<_> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect();
+
+        forwarded
+            .len()
+            .checked_sub(self.trusted_proxy_hops)
+            .and_then(|i| forwarded[i].parse().ok())
+            .or(peer)
+    }
+
+    /// Takes a token for the client and the class, or tells how long to wait.
+    /// Tokens are only taken when both the client and the global bucket
+    /// have one, so calls rejected by either don't use up the other.
+    fn check(&self, class: Class, ip: Option<IpAddr>, now: Instant) -> Result<(), Duration> {
+        let Some((client, global)) = self.quotas.get(&class) else {
+            return Ok(());
+        };
+
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        let mut globals = self.global.lock().unwrap_or_else(|e| e.into_inner());
+
+        let client = match (client, ip) {
+            (Some(quota), Some(ip)) => {
+                if clients.len() >= MAX_TRACKED_CLIENTS && !clients.contains_key(&(class, ip)) {
+                    return Err(SWEEP_INTERVAL);
+                }
+                let bucket = clients
+                    .entry((class, ip))
+                    .or_insert_with(|| Bucket::full(quota, now));
+                Some((bucket, quota))
+            }
+            _ => None,
+        };
+        let global = global.as_ref().map(|quota| {
+            let bucket = globals
+                .entry(class)
+                .or_insert_with(|| Bucket::full(quota, now));
+            (bucket, quota)
+        });
+
+        let mut buckets = client.into_iter().chain(global).collect::<Vec<_>>();
+        let wait = buckets
+            .iter_mut()
+            .filter_map(|(bucket, quota)| bucket.refill(quota, now).err())
+            .max();
+        if let Some(wait) = wait {
+            return Err(wait);
+        }
+
+        for (bucket, _) in buckets {
+            bucket.tokens -= 1.0;
+        }
+        Ok(())
+    }
+
+    /// Drops the buckets of clients idle for a whole period, which are full
+    /// again anyway.
+    fn sweep(&self, now: Instant) {
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        clients.retain(|(class, _), bucket| {
+            let period = self.quotas.get(class).and_then(|q| q.0).map(|q| q.period);
+            period.is_some_and(|p| now.saturating_duration_since(bucket.updated) < p)
+        });
+    }
+
+    /// Spawns a task sweeping idle clients every [`SWEEP_INTERVAL`].
+    pub fn spawn_sweeper(self: &Arc<Self>) {
+        let limiter = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                let Some(limiter) = limiter.upgrade() else {
+                    return;
+                };
+                limiter.sweep(Instant::now());
+            }
+        });
+    }
+
+    /// Permit to scan storage, to hold for the whole scan.
+    pub async fn scan_permit(&self) -> Result<SemaphorePermit<'_>, AppError> {
+        match tokio::time::timeout(SCAN_PERMIT_TIMEOUT, self.scans.acquire()).await {
+            Ok(Ok(permit)) => Ok(permit),
+            _ => Err(AppError::RateLimited {
+                retry_after_secs: 1,
+            }),
+        }
+    }
+}
+
+fn too_many_requests(retry_after: Duration) -> Response {
+    let retry_after_secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let body =
+        serde_json::to_string(&AppError::RateLimited { retry_after_secs }).unwrap_or_default();
+
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+            (header::RETRY_AFTER, HeaderValue::from(retry_after_secs)),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// Middleware applying the quotas.
+pub async fn limit(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let Some(class) = Class::of(request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let limiter = &state.rate_limiter;
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let ip = limiter.client_ip(request.headers(), peer);
+
+    if let Err(retry_after) = limiter.check(class, ip, Instant::now()) {
+        return too_many_requests(retry_after);
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(class: Class, client: Option<Quota>, global: Option<Quota>) -> RateLimiter {
+        RateLimiter::new(HashMap::from([(class, (client, global))]), 1, 1)
+    }
+
+    #[test]
+    fn parses_quotas() {
+        assert_eq!(
+            parse_quota("20/60"),
+            Ok(Some(Quota::new(20, Duration::from_secs(60))))
+        );
+        assert_eq!(parse_quota("off"), Ok(None));
+        assert!(parse_quota("20").is_err());
+        assert!(parse_quota("0/60").is_err());
+    }
+
+    #[test]
+    fn classifies_requests() {
+        assert_eq!(
+            Class::of("/api/get_balances_of_many1234"),
+            Some(Class::Expensive)
+        );
+        assert_eq!(Class::of("/api/get_block_stream99"), Some(Class::Stream));
+        assert_eq!(Class::of("/api/get_block42"), Some(Class::Standard));
+        assert_eq!(Class::of("/accounts/abc"), Some(Class::Page));
+        assert_eq!(Class::of("/pkg/token-app.wasm"), None);
+        assert_eq!(Class::of("/favicon.ico"), None);
+    }
+
+    #[test]
+    fn refills_over_time() {
+        let quota = Quota::new(2, Duration::from_secs(10));
+        let limiter = limiter(Class::Expensive, Some(quota), None);
+        let ip = Some(IpAddr::from([10, 0, 0, 1]));
+        let now = Instant::now();
+
+        assert!(limiter.check(Class::Expensive, ip, now).is_ok());
+        assert!(limiter.check(Class::Expensive, ip, now).is_ok());
+        let wait = limiter.check(Class::Expensive, ip, now).unwrap_err();
+        assert_eq!(wait.as_secs_f64().round(), 5.0);
+
+        // Other clients have their own bucket
+        assert!(
+            limiter
+                .check(Class::Expensive, Some(IpAddr::from([10, 0, 0, 2])), now)
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check(Class::Expensive, ip, now + Duration::from_secs(6))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn global_quota_is_shared() {
+        let quota = Quota::new(1, Duration::from_secs(60));
+        let limiter = limiter(Class::Standard, None, Some(quota));
+        let now = Instant::now();
+
+        assert!(
+            limiter
+                .check(Class::Standard, Some(IpAddr::from([10, 0, 0, 1])), now)
+                .is_ok()
+        );
+        assert!(
+            limiter
+                .check(Class::Standard, Some(IpAddr::from([10, 0, 0, 2])), now)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn rejected_calls_keep_the_client_quota() {
+        let client = Quota::new(2, Duration::from_secs(600));
+        let global = Quota::new(1, Duration::from_secs(10));
+        let limiter = limiter(Class::Expensive, Some(client), Some(global));
+        let ip = Some(IpAddr::from([10, 0, 0, 1]));
+        let now = Instant::now();
+
+        assert!(limiter.check(Class::Expensive, ip, now).is_ok());
+        for _ in 0..3 {
+            assert!(limiter.check(Class::Expensive, ip, now).is_err());
+        }
+
+        // Once the global bucket refilled, the client still has its token
+        let later = now + Duration::from_secs(10);
+        assert!(limiter.check(Class::Expensive, ip, later).is_ok());
+    }
+
+    #[test]
+    fn full_client_map_rejects_new_clients_until_swept() {
+        let quota = Quota::new(10, Duration::from_secs(60));
+        let limiter = limiter(Class::Standard, Some(quota), None);
+        let now = Instant::now();
+
+        for i in 0..MAX_TRACKED_CLIENTS as u32 {
+            let ip = Some(IpAddr::from(i.to_be_bytes()));
+            assert!(limiter.check(Class::Standard, ip, now).is_ok());
+        }
+
+        // Tracked clients keep their bucket, new ones wait for a sweep
+        let known = Some(IpAddr::from(0u32.to_be_bytes()));
+        let new = Some(IpAddr::from([10, 0, 0, 1]));
+        assert!(limiter.check(Class::Standard, known, now).is_ok());
+        assert!(limiter.check(Class::Standard, new, now).is_err());
+
+        let later = now + Duration::from_secs(61);
+        assert!(limiter.check(Class::Standard, known, later).is_ok());
+        limiter.sweep(later);
+        assert_eq!(limiter.clients.lock().unwrap().len(), 1);
+        assert!(limiter.check(Class::Standard, new, later).is_ok());
+    }
+
+    #[test]
+    fn only_trusts_entries_added_by_proxies() {
+        let limiter = limiter(Class::Page, None, None);
+        let peer = Some(IpAddr::from([192, 168, 0, 1]));
+        let mut headers = HeaderMap::new();
+        assert_eq!(limiter.client_ip(&headers, peer), peer);
+
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("6.6.6.6, 1.2.3.4"),
+        );
+        assert_eq!(
+            limiter.client_ip(&headers, peer),
+            Some(IpAddr::from([1, 2, 3, 4]))
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! SS58 address codec, shared by the server and the browser.
+//!
+//! Implements the format described at
+//! <https://docs.substrate.io/reference/address-formats/>: a one or two byte
+//! network prefix, the payload, and a blake2b-512 checksum of
+//! `"SS58PRE" || prefix || payload`, the whole being base58 encoded.
+
+use blake2::{Blake2b512, Digest};
+
+/// Highest prefix that fits in the two-byte encoding.
+pub const MAX_PREFIX: u16 = 16_383;
+
+const CHECKSUM_PREIMAGE: &[u8] = b"SS58PRE";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Ss58Error {
+    /// The string contains characters outside the base58 alphabet
+    BadBase58,
+    /// The decoded data doesn't match any supported payload length
+    BadLength(usize),
+    /// The decoded data is shorter than an account address and isn't a valid
+    /// shorter one, most likely a truncated address
+    TooShort(usize),
+    /// The first byte doesn't start a valid prefix, or the prefix is too large
+    InvalidPrefix(u16),
+    InvalidChecksum,
+    /// Payloads are 1, 2, 4, 8, 32 or 33 bytes long
+    UnsupportedPayload(usize),
+    /// The payload isn't a 32 bytes account id
+    NotAnAccount(usize),
+}
+
+impl std::fmt::Display for Ss58Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadBase58 => write!(f, "Address contains invalid characters"),
+            Self::BadLength(len) => write!(f, "Address has an invalid length ({len} bytes)"),
+            Self::TooShort(_) => write!(f, "Address too short, check it was copied in full"),
+            Self::InvalidPrefix(prefix) => write!(f, "Invalid network prefix ({prefix})"),
+            Self::InvalidChecksum => write!(f, "Address checksum doesn't match, check for typos"),
+            Self::UnsupportedPayload(len) => {
+                write!(f, "Unsupported payload length ({len} bytes)")
+            }
+            Self::NotAnAccount(len) => {
+                write!(f, "Not an account address ({len} bytes payload)")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Ss58Error {}
+
+/// Number of checksum bytes for a payload length.
+fn checksum_len(payload_len: usize) -> Result<usize, Ss58Error> {
+    match payload_len {
+        1 | 2 | 4 | 8 => Ok(1),
+        32 | 33 => Ok(2),
+        len => Err(Ss58Error::UnsupportedPayload(len)),
+    }
+}
+
+fn checksum(data: &[u8]) -> [u8; 64] {
+    let mut hasher = Blake2b512::new();
+    hasher.update(CHECKSUM_PREIMAGE);
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+fn encode_prefix(prefix: u16) -> Result<Vec<u8>, Ss58Error> {
+    match prefix {
+        0..=63 => Ok(vec![prefix as u8]),
+        64..=MAX_PREFIX => Ok(vec![
+            ((prefix & 0b0000_0000_1111_1100) as u8 >> 2) | 0b0100_0000,
+            ((prefix >> 8) as u8) | (((prefix & 0b0000_0000_0000_0011) as u8) << 6),
+        ]),
+        _ => Err(Ss58Error::InvalidPrefix(prefix)),
+    }
+}
+
+/// Returns the prefix and its length in bytes.
+fn decode_prefix(data: &[u8]) -> Result<(u16, usize), Ss58Error> {
+    match data {
+        [first @ 0..=63, ..] => Ok((*first as u16, 1)),
+        [first @ 64..=127, second, ..] => {
+            let lower = (first << 2) | (second >> 6);
+            let upper = second & 0b0011_1111;
+            Ok((lower as u16 | ((upper as u16) << 8), 2))
+        }
+        [first, ..] => Err(Ss58Error::InvalidPrefix(*first as u16)),
+        [] => Err(Ss58Error::BadLength(0)),
+    }
+}
+
+pub fn encode(prefix: u16, payload: &[u8]) -> Result<String, Ss58Error> {
+    let checksum_len = checksum_len(payload.len())?;
+
+    let mut data = encode_prefix(prefix)?;
+    data.extend_from_slice(payload);
+    let hash = checksum(&data);
+    data.extend_from_slice(&hash[..checksum_len]);
+
+    Ok(bs58::encode(data).into_string())
+}
+
+/// Decodes an address into its prefix and payload, verifying the checksum.
+pub fn decode(address: &str) -> Result<(u16, Vec<u8>), Ss58Error> {
+    let data = bs58::decode(address.trim())
+        .into_vec()
+        .map_err(|_| Ss58Error::BadBase58)?;
+
+    if data.is_empty() {
+        return Err(Ss58Error::TooShort(0));
+    }
+    let (prefix, prefix_len) = decode_prefix(&data)?;
+    // Shorter payloads exist, but a short input failing to decode as one is
+    // rather a truncated account address
+    let truncated = data.len() < prefix_len + 32 + 2;
+
+    let (payload_len, checksum_len) = match data.len().checked_sub(prefix_len) {
+        Some(2) => (1, 1),
+        Some(3) => (2, 1),
+        Some(5) => (4, 1),
+        Some(9) => (8, 1),
+        Some(34) => (32, 2),
+        Some(35) => (33, 2),
+        _ if truncated => return Err(Ss58Error::TooShort(data.len())),
+        _ => return Err(Ss58Error::BadLength(data.len())),
+    };
+
+    let body = prefix_len + payload_len;
+    if checksum(&data[..body])[..checksum_len] != data[body..body + checksum_len] {
+        return Err(if truncated {
+            Ss58Error::TooShort(data.len())
+        } else {
+            Ss58Error::InvalidChecksum
+        });
+    }
+
+    Ok((prefix, data[prefix_len..body].to_vec()))
+}
+
+pub fn encode_account(prefix: u16, account: &[u8; 32]) -> String {
+    // 32 bytes payloads and prefixes of at most two bytes always encode
+    encode(prefix, account).expect("valid SS58 prefix")
+}
+
+/// Decodes an account address of any network into its prefix and public key.
+pub fn decode_account(address: &str) -> Result<(u16, [u8; 32]), Ss58Error> {
+    let (prefix, payload) = decode(address)?;
+    let account = payload
+        .try_into()
+        .map_err(|p: Vec<u8>| Ss58Error::NotAnAccount(p.len()))?;
+    Ok((prefix, account))
+}
+
+/// Re-encodes an account address of any network with the Allfeat prefix.
+pub fn to_allfeat(address: &str) -> Result<String, Ss58Error> {
+    let (_, account) = decode_account(address)?;
+    Ok(encode_account(crate::utils::SS58_PREFIX, &account))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `//Alice` development account
+    const ALICE: [u8; 32] = [
+        0xd4, 0x35, 0x93, 0xc7, 0x15, 0xfd, 0xd3, 0x1c, 0x61, 0x14, 0x1a, 0xbd, 0x04, 0xa9, 0x9f,
+        0xd6, 0x82, 0x2c, 0x85, 0x58, 0x85, 0x4c, 0xcd, 0xe3, 0x9a, 0x56, 0x84, 0xe7, 0xa5, 0x6d,
+        0xa2, 0x7d,
+    ];
+
+    /// Alice on networks of the SS58 registry
+    const ALICE_VECTORS: &[(u16, &str)] = &[
+        (0, "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"),
+        (2, "HNZata7iMYWmk5RvZRTiAsSDhV8366zq2YGb3tLH5Upf74F"),
+        (42, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"),
+        (440, "qSz84FFpCxyRLmeoAQfTHiHgDJ8FGWnoaUzHTvnZdRGDTX21W"),
+        (
+            MAX_PREFIX,
+            "yNa8JpqfFB3q8A29rCwSgxvdU94ufJw2yKKxDgznS5m1PoFvn",
+        ),
+    ];
+
+    #[test]
+    fn encodes_registry_vectors() {
+        for (prefix, address) in ALICE_VECTORS {
+            assert_eq!(encode_account(*prefix, &ALICE), *address, "prefix {prefix}");
+        }
+    }
+
+    #[test]
+    fn decodes_registry_vectors() {
+        for (prefix, address) in ALICE_VECTORS {
+            assert_eq!(decode_account(address), Ok((*prefix, ALICE)));
+        }
+    }
+
+    #[test]
+    fn reencodes_to_allfeat() {
+        for (_, address) in ALICE_VECTORS {
+            assert_eq!(
+                to_allfeat(address).as_deref(),
+                Ok("qSz84FFpCxyRLmeoAQfTHiHgDJ8FGWnoaUzHTvnZdRGDTX21W")
+            );
+        }
+    }
+
+    #[test]
+    fn short_payloads() {
+        let vectors: &[(&[u8], &str)] = &[
+            (&[1], "F7NZ"),
+            (&1234u16.to_le_bytes(), "26V2KK"),
+            (&123_456u32.to_le_bytes(), "N3NsLKV9"),
+            (&1u64.to_le_bytes(), "3MsZArixcLytrf"),
+        ];
+
+        for (payload, address) in vectors {
+            assert_eq!(encode(42, payload).as_deref(), Ok(*address));
+            assert_eq!(decode(address), Ok((42, payload.to_vec())));
+        }
+    }
+
+    #[test]
+    fn public_key_payload() {
+        let mut payload = vec![0x02];
+        payload.extend_from_slice(&ALICE);
+
+        let address = "KW7itot55zZFQ4soCM2AqjCLMCq2k9cFLzD1ya4jcetmcd4f5";
+        assert_eq!(encode(42, &payload).as_deref(), Ok(address));
+        assert_eq!(decode(address), Ok((42, payload)));
+        assert_eq!(decode_account(address), Err(Ss58Error::NotAnAccount(33)));
+    }
+
+    #[test]
+    fn rejects_invalid_addresses() {
+        // Last character changed
+        assert_eq!(
+            decode("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ"),
+            Err(Ss58Error::InvalidChecksum)
+        );
+        // `0` isn't part of the base58 alphabet
+        assert_eq!(
+            decode("0GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"),
+            Err(Ss58Error::BadBase58)
+        );
+        // Truncated, whether or not the length is one of a shorter payload
+        assert!(matches!(
+            decode("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKut"),
+            Err(Ss58Error::TooShort(_))
+        ));
+        assert!(matches!(
+            decode("5GrwvaEF5zXb"),
+            Err(Ss58Error::TooShort(_))
+        ));
+        assert_eq!(decode(""), Err(Ss58Error::TooShort(0)));
+    }
+
+    #[test]
+    fn rejects_invalid_inputs() {
+        assert_eq!(
+            encode(MAX_PREFIX + 1, &ALICE),
+            Err(Ss58Error::InvalidPrefix(MAX_PREFIX + 1))
+        );
+        assert_eq!(encode(42, &[0; 16]), Err(Ss58Error::UnsupportedPayload(16)));
+    }
+}
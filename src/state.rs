@@ -1,11 +1,40 @@
 use axum::extract::FromRef;
 use leptos::config::LeptosOptions;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::RwLock;
 
-use super::substrate::AllfeatClient;
-use crate::EnvelopeAllocation;
+use super::indexer::extrinsics::ExtrinsicIndex;
+use super::indexer::feed::BlockFeed;
+use super::indexer::fees::FeeIndex;
+use super::indexer::holders::HolderIndex;
+use super::indexer::payouts::PayoutIndex;
+use super::indexer::sudo::SudoIndex;
+use super::indexer::tokenomics::TokenomicsMonitor;
+use super::rate_limit::RateLimiter;
+use super::substrate::{ChainBackend, ChainConnection};
+use super::telemetry::Telemetry;
+use crate::config::Config;
+#[cfg(feature = "faucet")]
+use crate::faucet::Faucet;
+use crate::supply::SupplyMethodology;
+use crate::{EnvelopeAllocation, EnvelopeSupply, MetricKey, MetricValue};
+
+/// Settings read at startup
+pub type ConfigState = Arc<Config>;
+
+/// Connection to the RPC nodes, swapped on failover
+pub type ChainState = Arc<ChainConnection>;
+
+/// Chain queries of the server functions, answered by the node in use
+pub type BackendState = Arc<dyn ChainBackend>;
+
+/// Probes and Prometheus metrics of the explorer
+pub type TelemetryState = Arc<Telemetry>;
+
+/// Request quotas per client and endpoint class
+pub type RateLimiterState = Arc<RateLimiter>;
 
 /// Cached data with timestamp for TTL validation
 #[derive(Clone, Debug)]
@@ -17,19 +46,93 @@ pub struct CachedData<T> {
 /// Type alias for the allocations cache
 pub type AllocationsCache = Arc<RwLock<Option<CachedData<Vec<EnvelopeAllocation>>>>>;
 
+/// Indexed sudo activity
+pub type SudoLog = Arc<RwLock<SudoIndex>>;
+
+/// Aggregated transaction fees
+pub type FeeIndexState = Arc<RwLock<FeeIndex>>;
+
+/// Token allocation payouts per epoch
+pub type PayoutIndexState = Arc<RwLock<PayoutIndex>>;
+
+/// Recent extrinsic hashes and where they were included
+pub type ExtrinsicIndexState = Arc<RwLock<ExtrinsicIndex>>;
+
+/// Accounts ranked by balance, refreshed periodically
+pub type HolderIndexState = Arc<HolderIndex>;
+
+/// Finalized blocks and node connection state, as followed by the indexer
+pub type BlockFeedState = Arc<BlockFeed>;
+
+/// Latest value of each subscribed metric and the block it was read at
+pub type MetricsCache = Arc<RwLock<HashMap<MetricKey, (u32, MetricValue)>>>;
+
+/// Envelope supply read for the metrics, kept until a block changes the
+/// token allocations.
+#[derive(Debug, Default)]
+pub struct EnvelopeSupplyCache {
+    /// Latest block with `TokenAllocation` events
+    pub changed_at: u32,
+    /// Supply and the block it was read at
+    pub supply: Option<(u32, Vec<EnvelopeSupply>)>,
+}
+
+pub type EnvelopeSupplyState = Arc<RwLock<EnvelopeSupplyCache>>;
+
+/// Token allocation watchdog and its latest report
+pub type TokenomicsState = Arc<TokenomicsMonitor>;
+
+/// Testnet faucet, `None` when no seed is configured
+#[cfg(feature = "faucet")]
+pub type FaucetState = Option<Arc<Faucet>>;
+
 #[derive(Clone, FromRef)]
 pub struct AppState {
     pub leptos_options: LeptosOptions,
-    pub client: AllfeatClient,
+    pub config: ConfigState,
+    pub chain: ChainState,
+    pub backend: BackendState,
+    pub telemetry: TelemetryState,
+    pub rate_limiter: RateLimiterState,
     pub allocations_cache: AllocationsCache,
+    pub sudo_log: SudoLog,
+    pub fee_index: FeeIndexState,
+    pub payout_index: PayoutIndexState,
+    pub extrinsic_index: ExtrinsicIndexState,
+    pub tokenomics: TokenomicsState,
+    pub holders: HolderIndexState,
+    pub block_feed: BlockFeedState,
+    pub metrics_cache: MetricsCache,
+    pub envelope_supply: EnvelopeSupplyState,
+    /// Default of `get_circulating_supply`, from `supply.methodology`
+    pub supply_methodology: SupplyMethodology,
+    #[cfg(feature = "faucet")]
+    pub faucet: FaucetState,
 }
 
 impl std::fmt::Debug for AppState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("AppState")
+        let mut debug = f.debug_struct("AppState");
+        debug
             .field("leptos_options", &self.leptos_options)
-            .field("client", &self.client)
+            .field("config", &self.config)
+            .field("chain", &self.chain.endpoint())
+            .field("backend", &"<dyn ChainBackend>")
+            .field("telemetry", &"<Mutex>")
+            .field("rate_limiter", &"<Mutex>")
             .field("allocations_cache", &"<RwLock>")
-            .finish()
+            .field("sudo_log", &"<RwLock>")
+            .field("fee_index", &"<RwLock>")
+            .field("payout_index", &"<RwLock>")
+            .field("extrinsic_index", &"<RwLock>")
+            .field("tokenomics", &self.tokenomics.config)
+            .field("holders", &self.holders.refresh_interval)
+            .field("block_feed", &"<broadcast>")
+            .field("metrics_cache", &"<RwLock>")
+            .field("envelope_supply", &"<RwLock>")
+            .field("supply_methodology", &self.supply_methodology);
+        #[cfg(feature = "faucet")]
+        debug.field("faucet", &self.faucet.as_ref().map(|f| f.address()));
+        debug.finish()
     }
 }
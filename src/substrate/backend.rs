@@ -0,0 +1,379 @@
+//! Chain queries the server functions rely on.
+//!
+//! Server functions only reach the chain through [`ChainBackend`], answered
+//! by the node in use through [`ChainConnection`], or by a
+//! [`FixtureChain`](super::fixture::FixtureChain) in tests. Queries of a
+//! request take the block they are read at, so they stay consistent with
+//! each other.
+
+use futures::future::BoxFuture;
+use subxt::ext::codec::Decode;
+use subxt::utils::{AccountId32, H256, MultiAddress};
+
+use super::ChainConnection;
+use super::allfeat;
+use super::allfeat::runtime_types::pallet_token_allocation::EnvelopeId;
+use crate::{AppError, Balances, FeeEstimate};
+
+/// `Sudo::Key`, as `twox128("Sudo") ++ twox128("Key")`. Neither this nor
+/// `NextFeeMultiplier` is in the bundled metadata, so both are read raw.
+pub const SUDO_KEY_STORAGE: &str =
+    "0x5c0d1176a568c1f92944340dbfed9e9c530ebca703c85910e7164cb7d1c9e47b";
+
+/// `TransactionPayment::NextFeeMultiplier`, a `FixedU128`.
+pub const NEXT_FEE_MULTIPLIER_STORAGE: &str =
+    "0x3f1467a096bcd71a5b6a0c8155e208103f2edf3bdf381debe331ab7446addfdc";
+
+pub type BackendFuture<'a, T> = BoxFuture<'a, Result<T, AppError>>;
+
+/// Block a request is read at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockAt {
+    pub number: u32,
+    pub hash: H256,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnvelopeConfig {
+    pub total_cap: u128,
+    /// Percentage released at allocation time
+    pub upfront_rate: u8,
+    pub cliff: u32,
+    pub vesting_duration: u32,
+    pub unique_beneficiary: Option<AccountId32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllocationRecord {
+    pub envelope: EnvelopeId,
+    pub beneficiary: AccountId32,
+    pub total: u128,
+    pub upfront: u128,
+    pub vested_total: u128,
+    pub released: u128,
+    pub start: u32,
+}
+
+/// Token allocation payout storage.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PayoutState {
+    pub next_payout_at: u32,
+    pub epoch_index: u64,
+    pub payout_cursor: Option<u32>,
+    pub next_allocation_id: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtrinsicData {
+    pub index: u32,
+    pub hash: H256,
+    /// Called function as `Pallet.call`, `None` when it couldn't be decoded
+    pub call: Option<String>,
+    pub signer: Option<AccountId32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventData {
+    pub pallet: String,
+    pub variant: String,
+    /// Index of the extrinsic that emitted it, if any
+    pub extrinsic: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockData {
+    pub number: u32,
+    pub hash: H256,
+    pub parent_hash: H256,
+    /// Milliseconds since the UNIX epoch
+    pub timestamp: u64,
+    pub extrinsics: Vec<ExtrinsicData>,
+    pub events: Vec<EventData>,
+}
+
+pub trait ChainBackend: Send + Sync {
+    fn latest_block(&self) -> BackendFuture<'_, BlockAt>;
+
+    fn block_hash(&self, number: u32) -> BackendFuture<'_, Option<H256>>;
+
+    fn block_number(&self, hash: H256) -> BackendFuture<'_, Option<u32>>;
+
+    fn block(&self, hash: H256) -> BackendFuture<'_, Option<BlockData>>;
+
+    fn total_issuance(&self, at: H256) -> BackendFuture<'_, Option<u128>>;
+
+    /// `None` for accounts that don't exist on chain.
+    fn account(&self, at: H256, account: AccountId32) -> BackendFuture<'_, Option<Balances>>;
+
+    fn envelope(&self, at: H256, id: EnvelopeId) -> BackendFuture<'_, Option<EnvelopeConfig>>;
+
+    fn envelope_distributed(&self, at: H256, id: EnvelopeId) -> BackendFuture<'_, Option<u128>>;
+
+    /// Every allocation, in storage order.
+    fn allocations(&self, at: H256) -> BackendFuture<'_, Vec<AllocationRecord>>;
+
+    fn payout_state(&self, at: H256) -> BackendFuture<'_, PayoutState>;
+
+    fn epoch_duration(&self) -> Result<u32, AppError>;
+
+    fn max_payouts_per_block(&self) -> Result<u32, AppError>;
+
+    fn sudo_key(&self, at: H256) -> BackendFuture<'_, Option<AccountId32>>;
+
+    /// `TransactionPayment::NextFeeMultiplier` as a raw `FixedU128`.
+    fn next_fee_multiplier(&self, at: H256) -> BackendFuture<'_, Option<u128>>;
+
+    /// Fee of an extrinsic given with its compact length prefix, at the
+    /// latest block.
+    fn query_fee_info(&self, extrinsic: Vec<u8>) -> BackendFuture<'_, FeeEstimate>;
+}
+
+impl ChainBackend for ChainConnection {
+    fn latest_block(&self) -> BackendFuture<'_, BlockAt> {
+        Box::pin(async move {
+            let block = self.client()?.blocks().at_latest().await?;
+            Ok(BlockAt {
+                number: block.number(),
+                hash: block.hash(),
+            })
+        })
+    }
+
+    fn block_hash(&self, number: u32) -> BackendFuture<'_, Option<H256>> {
+        Box::pin(async move {
+            Ok(self
+                .rpc()?
+                .chain_get_block_hash(Some(number.into()))
+                .await?)
+        })
+    }
+
+    fn block_number(&self, hash: H256) -> BackendFuture<'_, Option<u32>> {
+        Box::pin(async move {
+            let header = self.rpc()?.chain_get_header(Some(hash)).await?;
+            Ok(header.map(|header| header.number))
+        })
+    }
+
+    fn block(&self, hash: H256) -> BackendFuture<'_, Option<BlockData>> {
+        use subxt::events::Phase;
+
+        Box::pin(async move {
+            let Ok(block) = self.client()?.blocks().at(hash).await else {
+                return Ok(None);
+            };
+
+            let (extrinsics, events) = tokio::try_join!(block.extrinsics(), block.events())?;
+            let timestamp = crate::indexer::block_timestamp(&block)
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+
+            let events = events
+                .iter()
+                .map(|event| {
+                    let event = event?;
+                    Ok(EventData {
+                        pallet: event.pallet_name().to_string(),
+                        variant: event.variant_name().to_string(),
+                        extrinsic: match event.phase() {
+                            Phase::ApplyExtrinsic(index) => Some(index),
+                            _ => None,
+                        },
+                    })
+                })
+                .collect::<Result<Vec<_>, AppError>>()?;
+
+            let extrinsics = extrinsics
+                .iter()
+                .map(|ext| ExtrinsicData {
+                    index: ext.index(),
+                    hash: ext.hash(),
+                    call: match (ext.pallet_name(), ext.variant_name()) {
+                        (Ok(pallet), Ok(call)) => Some(format!("{pallet}.{call}")),
+                        _ => None,
+                    },
+                    signer: ext.address_bytes().and_then(|mut bytes| {
+                        match MultiAddress::<AccountId32, ()>::decode(&mut bytes) {
+                            Ok(MultiAddress::Id(account)) => Some(account),
+                            _ => None,
+                        }
+                    }),
+                })
+                .collect();
+
+            Ok(Some(BlockData {
+                number: block.number(),
+                hash: block.hash(),
+                parent_hash: block.header().parent_hash,
+                timestamp,
+                extrinsics,
+                events,
+            }))
+        })
+    }
+
+    fn total_issuance(&self, at: H256) -> BackendFuture<'_, Option<u128>> {
+        Box::pin(async move {
+            let query = allfeat::storage().balances().total_issuance();
+            Ok(self.client()?.storage().at(at).fetch(&query).await?)
+        })
+    }
+
+    fn account(&self, at: H256, account: AccountId32) -> BackendFuture<'_, Option<Balances>> {
+        Box::pin(async move {
+            let query = allfeat::storage().system().account(account);
+            let info = self.client()?.storage().at(at).fetch(&query).await?;
+
+            Ok(info.map(|info| Balances {
+                free: info.data.free,
+                reserved: info.data.reserved,
+                frozen: info.data.frozen,
+            }))
+        })
+    }
+
+    fn envelope(&self, at: H256, id: EnvelopeId) -> BackendFuture<'_, Option<EnvelopeConfig>> {
+        Box::pin(async move {
+            let query = allfeat::storage().token_allocation().envelopes(id);
+            let config = self.client()?.storage().at(at).fetch(&query).await?;
+
+            Ok(config.map(|config| EnvelopeConfig {
+                total_cap: config.total_cap,
+                upfront_rate: config.upfront_rate.0,
+                cliff: config.cliff,
+                vesting_duration: config.vesting_duration,
+                unique_beneficiary: config.unique_beneficiary,
+            }))
+        })
+    }
+
+    fn envelope_distributed(&self, at: H256, id: EnvelopeId) -> BackendFuture<'_, Option<u128>> {
+        Box::pin(async move {
+            let query = allfeat::storage()
+                .token_allocation()
+                .envelope_distributed(id);
+            Ok(self.client()?.storage().at(at).fetch(&query).await?)
+        })
+    }
+
+    fn allocations(&self, at: H256) -> BackendFuture<'_, Vec<AllocationRecord>> {
+        Box::pin(async move {
+            let query = allfeat::storage().token_allocation().allocations_iter();
+            let mut allocs_iter = self.client()?.storage().at(at).iter(query).await?;
+
+            let mut allocations = vec![];
+            while let Some(kv) = allocs_iter.next().await {
+                let alloc = kv?.value;
+                allocations.push(AllocationRecord {
+                    envelope: alloc.envelope,
+                    beneficiary: alloc.beneficiary,
+                    total: alloc.total,
+                    upfront: alloc.upfront,
+                    vested_total: alloc.vested_total,
+                    released: alloc.released,
+                    start: alloc.start,
+                });
+            }
+
+            Ok(allocations)
+        })
+    }
+
+    fn payout_state(&self, at: H256) -> BackendFuture<'_, PayoutState> {
+        Box::pin(async move {
+            let storage = self.client()?.storage().at(at);
+            let queries = allfeat::storage().token_allocation();
+
+            let next_payout_at_query = queries.next_payout_at();
+            let epoch_index_query = queries.epoch_index();
+            let payout_cursor_query = queries.payout_cursor();
+            let next_allocation_id_query = queries.next_allocation_id();
+
+            let (next_payout_at, epoch_index, payout_cursor, next_allocation_id) = tokio::try_join!(
+                storage.fetch_or_default(&next_payout_at_query),
+                storage.fetch_or_default(&epoch_index_query),
+                storage.fetch(&payout_cursor_query),
+                storage.fetch_or_default(&next_allocation_id_query),
+            )?;
+
+            Ok(PayoutState {
+                next_payout_at,
+                epoch_index,
+                payout_cursor,
+                next_allocation_id,
+            })
+        })
+    }
+
+    fn epoch_duration(&self) -> Result<u32, AppError> {
+        let query = allfeat::constants().token_allocation().epoch_duration();
+        Ok(self.client()?.constants().at(&query)?)
+    }
+
+    fn max_payouts_per_block(&self) -> Result<u32, AppError> {
+        let query = allfeat::constants()
+            .token_allocation()
+            .max_payouts_per_block();
+        Ok(self.client()?.constants().at(&query)?)
+    }
+
+    fn sudo_key(&self, at: H256) -> BackendFuture<'_, Option<AccountId32>> {
+        Box::pin(async move {
+            let key = crate::utils::decode_hex(SUDO_KEY_STORAGE).unwrap_or_default();
+
+            self.client()?
+                .storage()
+                .at(at)
+                .fetch_raw(key)
+                .await?
+                .map(|bytes| AccountId32::decode(&mut &bytes[..]))
+                .transpose()
+                .map_err(|e| AppError::Decode(format!("Failed to decode sudo key: {e}")))
+        })
+    }
+
+    fn next_fee_multiplier(&self, at: H256) -> BackendFuture<'_, Option<u128>> {
+        Box::pin(async move {
+            let key = crate::utils::decode_hex(NEXT_FEE_MULTIPLIER_STORAGE).unwrap_or_default();
+
+            let multiplier = self.client()?.storage().at(at).fetch_raw(key).await?;
+            Ok(multiplier
+                .and_then(|bytes| <[u8; 16]>::try_from(bytes).ok())
+                .map(u128::from_le_bytes))
+        })
+    }
+
+    fn query_fee_info(&self, extrinsic: Vec<u8>) -> BackendFuture<'_, FeeEstimate> {
+        use allfeat::runtime_types::frame_support::dispatch::DispatchClass;
+        use subxt::utils::UncheckedExtrinsic;
+
+        Box::pin(async move {
+            let length = extrinsic.len() as u32;
+            let query = allfeat::apis()
+                .transaction_payment_api()
+                .query_info(UncheckedExtrinsic::new(extrinsic), length);
+
+            let info = self
+                .client()?
+                .runtime_api()
+                .at_latest()
+                .await?
+                .call(query)
+                .await
+                .map_err(|e| AppError::InvalidInput(format!("Fee estimation failed: {e}")))?;
+
+            Ok(FeeEstimate {
+                partial_fee: info.partial_fee,
+                class: match info.class {
+                    DispatchClass::Normal => "Normal",
+                    DispatchClass::Operational => "Operational",
+                    DispatchClass::Mandatory => "Mandatory",
+                }
+                .to_string(),
+                ref_time: info.weight.ref_time,
+                proof_size: info.weight.proof_size,
+                length,
+            })
+        })
+    }
+}
@@ -0,0 +1,321 @@
+//! Connection to the RPC nodes, kept alive in the background.
+//!
+//! The explorer starts whether or not a node answers: pages that only need
+//! the indexes keep working and the others fail with `ChainUnavailable`
+//! until a node is reached. Endpoints are tried in turn, and the client is
+//! swapped for the next healthy one when its node stops answering.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use subxt::OnlineClient;
+use subxt::backend::rpc::RpcClient as SubxtRpcClient;
+use subxt::backend::rpc::reconnecting_rpc_client::{ExponentialBackoff, RpcClient};
+use subxt::backend::rpc::{RawRpcFuture, RawRpcSubscription, RawValue, RpcClientT};
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use super::recording::{Recorder, RpcRecording};
+use super::{AllfeatClient, AllfeatRpc};
+use crate::telemetry::Telemetry;
+
+const DEFAULT_ENDPOINT: &str = "wss://mainnet.rpc.allfeat.org";
+
+/// Delay between two health checks of the current endpoint.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Time a node has to answer a health check.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Failed health checks in a row before moving to the next endpoint.
+const MAX_FAILED_CHECKS: u32 = 3;
+
+/// Wait after every endpoint failed before trying them all again.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RpcConfig {
+    /// Tried in turn, from the first one
+    pub endpoints: Vec<String>,
+    /// Records the JSON-RPC traffic to this file, replayable with
+    /// `mock://<file>` when it ends in `.jsonl`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record: Option<PathBuf>,
+}
+
+impl Default for RpcConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: vec![DEFAULT_ENDPOINT.to_string()],
+            record: None,
+        }
+    }
+}
+
+/// No RPC endpoint is currently reachable.
+#[derive(Clone, Copy, Debug)]
+pub struct ChainUnavailable;
+
+impl std::fmt::Display for ChainUnavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("The chain is unreachable")
+    }
+}
+
+impl std::error::Error for ChainUnavailable {}
+
+#[derive(Clone, Debug)]
+struct Connected {
+    endpoint: String,
+    client: AllfeatClient,
+    rpc: AllfeatRpc,
+}
+
+#[derive(Debug)]
+pub struct ChainConnection {
+    pub endpoints: Vec<String>,
+    current: watch::Sender<Option<Connected>>,
+    telemetry: Arc<Telemetry>,
+    recording: Option<Arc<RpcRecording>>,
+}
+
+impl ChainConnection {
+    pub fn new(endpoints: Vec<String>, telemetry: Arc<Telemetry>) -> Self {
+        Self {
+            endpoints,
+            current: watch::channel(None).0,
+            telemetry,
+            recording: None,
+        }
+    }
+
+    /// Records the traffic with every endpoint.
+    pub fn with_recording(mut self, recording: RpcRecording) -> Self {
+        self.recording = Some(Arc::new(recording));
+        self
+    }
+
+    fn current(&self) -> Option<Connected> {
+        self.current.borrow().clone()
+    }
+
+    pub fn client(&self) -> Result<AllfeatClient, ChainUnavailable> {
+        self.current().map(|c| c.client).ok_or(ChainUnavailable)
+    }
+
+    pub fn rpc(&self) -> Result<AllfeatRpc, ChainUnavailable> {
+        self.current().map(|c| c.rpc).ok_or(ChainUnavailable)
+    }
+
+    /// Endpoint in use, `None` while no node is reachable.
+    pub fn endpoint(&self) -> Option<String> {
+        self.current().map(|c| c.endpoint)
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.current.borrow().is_some()
+    }
+
+    pub fn watch(&self) -> ClientWatch {
+        ClientWatch(self.current.subscribe())
+    }
+
+    /// Waits until a node is reachable.
+    pub async fn wait_for_client(&self) -> Result<AllfeatClient, ChainUnavailable> {
+        let mut current = self.current.subscribe();
+        let connected = current
+            .wait_for(Option::is_some)
+            .await
+            .map_err(|_| ChainUnavailable)?;
+
+        connected
+            .as_ref()
+            .map(|c| c.client.clone())
+            .ok_or(ChainUnavailable)
+    }
+}
+
+/// Tells when the client in use changes, e.g. on failover, so subscriptions
+/// opened with the previous one are not followed forever.
+pub struct ClientWatch(watch::Receiver<Option<Connected>>);
+
+impl ClientWatch {
+    /// Client in use, the changes being watched from it on.
+    pub fn client(&mut self) -> Result<AllfeatClient, ChainUnavailable> {
+        self.0
+            .borrow_and_update()
+            .as_ref()
+            .map(|c| c.client.clone())
+            .ok_or(ChainUnavailable)
+    }
+
+    /// Returns once the client was replaced or dropped.
+    pub async fn changed(&mut self) {
+        if self.0.changed().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+/// Spawns the task connecting to the endpoints and failing over between
+/// them. Returns immediately.
+pub fn spawn(chain: Arc<ChainConnection>) {
+    tokio::spawn(async move {
+        // Next endpoint to try, so failovers go round the list
+        let mut next = 0;
+
+        loop {
+            let count = chain.endpoints.len();
+            let connected = 'connect: {
+                for i in 0..count {
+                    let index = (next + i) % count;
+                    let endpoint = &chain.endpoints[index];
+                    match connect(endpoint, &chain).await {
+                        Ok(connected) => {
+                            next = (index + 1) % count;
+                            break 'connect Some(connected);
+                        }
+                        Err(e) => warn!(target: "allfeat", "Failed to connect to {endpoint}: {e}"),
+                    }
+                }
+                None
+            };
+
+            let Some(connected) = connected else {
+                warn!(target: "allfeat", "No RPC endpoint reachable, retrying in {RECONNECT_DELAY:?}");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            };
+
+            let rpc = connected.rpc.clone();
+            let endpoint = connected.endpoint.clone();
+            chain.current.send_replace(Some(connected));
+
+            monitor(&rpc).await;
+
+            warn!(target: "allfeat", "RPC endpoint {endpoint} is unhealthy, failing over");
+            chain.telemetry.failed_over();
+            chain.current.send_replace(None);
+        }
+    });
+}
+
+/// Returns once the node stopped answering health checks.
+async fn monitor(rpc: &AllfeatRpc) {
+    let mut failed = 0;
+
+    while failed < MAX_FAILED_CHECKS {
+        tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+
+        match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, rpc.system_health()).await {
+            Ok(Ok(_)) => failed = 0,
+            Ok(Err(e)) => {
+                failed += 1;
+                warn!(target: "allfeat", "Health check failed ({failed}/{MAX_FAILED_CHECKS}): {e}");
+            }
+            Err(_) => {
+                failed += 1;
+                warn!(target: "allfeat", "Health check timed out ({failed}/{MAX_FAILED_CHECKS})");
+            }
+        }
+    }
+}
+
+async fn connect(
+    endpoint: &str,
+    chain: &ChainConnection,
+) -> Result<Connected, Box<dyn std::error::Error + Send + Sync>> {
+    info!(target: "allfeat", "Connecting to RPC: {}", endpoint);
+
+    let rpc = rpc_client(endpoint, chain).await?;
+    let client = OnlineClient::from_rpc_client(rpc.clone()).await?;
+
+    info!(target: "allfeat", "Connected to Allfeat network through {}", endpoint);
+    Ok(Connected {
+        endpoint: endpoint.to_string(),
+        client,
+        rpc: AllfeatRpc::new(rpc),
+    })
+}
+
+async fn rpc_client(
+    endpoint: &str,
+    chain: &ChainConnection,
+) -> Result<SubxtRpcClient, Box<dyn std::error::Error + Send + Sync>> {
+    #[cfg(feature = "mock-chain")]
+    if let Some(path) = endpoint.strip_prefix(super::mock::SCHEME) {
+        return Ok(wrap(super::mock::MockRpc::load(path)?, chain));
+    }
+
+    // Rides over short node restarts, longer outages are left to the failover
+    let rpc = RpcClient::builder()
+        .retry_policy(
+            ExponentialBackoff::from_millis(100)
+                .max_delay(Duration::from_secs(2))
+                .take(5),
+        )
+        .build(endpoint.to_string())
+        .await?;
+
+    Ok(wrap(rpc, chain))
+}
+
+/// Client timing, and recording when asked to, the calls made through `rpc`.
+fn wrap<R: RpcClientT>(rpc: R, chain: &ChainConnection) -> SubxtRpcClient {
+    let telemetry = chain.telemetry.clone();
+    match &chain.recording {
+        Some(recording) => SubxtRpcClient::new(Instrumented {
+            inner: Recorder {
+                inner: rpc,
+                recording: recording.clone(),
+            },
+            telemetry,
+        }),
+        None => SubxtRpcClient::new(Instrumented {
+            inner: rpc,
+            telemetry,
+        }),
+    }
+}
+
+/// Counts and times the calls made to the node.
+struct Instrumented<R> {
+    inner: R,
+    telemetry: Arc<Telemetry>,
+}
+
+impl<R: RpcClientT> RpcClientT for Instrumented<R> {
+    fn request_raw<'a>(
+        &'a self,
+        method: &'a str,
+        params: Option<Box<RawValue>>,
+    ) -> RawRpcFuture<'a, Box<RawValue>> {
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = self.inner.request_raw(method, params).await;
+            self.telemetry
+                .rpc
+                .record(method, started.elapsed(), result.is_err());
+            result
+        })
+    }
+
+    fn subscribe_raw<'a>(
+        &'a self,
+        sub: &'a str,
+        params: Option<Box<RawValue>>,
+        unsub: &'a str,
+    ) -> RawRpcFuture<'a, RawRpcSubscription> {
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = self.inner.subscribe_raw(sub, params, unsub).await;
+            self.telemetry
+                .rpc
+                .record(sub, started.elapsed(), result.is_err());
+            result
+        })
+    }
+}
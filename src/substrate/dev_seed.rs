@@ -0,0 +1,486 @@
+//! `token-app seed-dev`: fills a development chain with token economy data,
+//! so every page has something to show locally.
+//!
+//! Signed with the dev keys, and dispatched as root through the sudo key:
+//! envelopes missing from the chain are written with varied cliffs and
+//! vesting, and the pallet account is funded for them. Allocations are then
+//! added to the dev and generated accounts with
+//! `TokenAllocation::add_allocation`, the dev accounts send each other
+//! transfers, and payouts are brought forward epoch after epoch by moving
+//! `NextPayoutAt` to the current block.
+//!
+//! The runtime only gives envelopes through its genesis, and the bundled
+//! metadata has no Sudo or Utility pallet, so those calls are dynamic and
+//! checked against the metadata of the node.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use subxt::dynamic::Value;
+use subxt::ext::codec::Encode;
+use subxt::ext::scale_value::ValueDef;
+use subxt::utils::{AccountId32, MultiAddress};
+use subxt_signer::SecretUri;
+use subxt_signer::sr25519::{Keypair, dev};
+
+use super::allfeat::runtime_types::pallet_token_allocation::EnvelopeId;
+use super::{AllfeatClient, ChainBackend, ChainConnection, allfeat};
+use crate::ENVELOPES;
+use crate::utils::format_balance;
+
+type SeedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// One token, at 12 decimals.
+const UNIT: u128 = 1_000_000_000_000;
+
+/// Calls dispatched by a single sudo batch.
+const BATCH_SIZE: usize = 50;
+
+/// How long a node is waited for before giving up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Blocks waited for a payout brought forward to run.
+const PAYOUT_WAIT_BLOCKS: u32 = 30;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeedDevOptions {
+    pub allocations: usize,
+    pub transfers: usize,
+    /// Payout epochs brought forward
+    pub epochs: u32,
+    /// Secret URI of the sudo key
+    pub sudo: String,
+}
+
+impl Default for SeedDevOptions {
+    fn default() -> Self {
+        Self {
+            allocations: 300,
+            transfers: 100,
+            epochs: 3,
+            sudo: "//Alice".to_string(),
+        }
+    }
+}
+
+/// Deterministic xorshift, so two runs seed the same data.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound.max(1)
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+/// Upfront rate, cliff and vesting duration of the envelopes created, in
+/// blocks, from released at once to vesting over a month.
+const SCHEDULES: [(u8, u32, u32); 6] = [
+    (100, 0, 0),
+    (25, 0, 1_200),
+    (10, 300, 14_400),
+    (5, 1_200, 100_800),
+    (0, 14_400, 432_000),
+    (20, 600, 50),
+];
+
+/// Generated beneficiary `index`, no key needed.
+fn beneficiary(index: u64) -> AccountId32 {
+    let hash = Blake2b::<U32>::digest((b"seed-dev", index).encode());
+    AccountId32(hash.into())
+}
+
+fn dev_accounts() -> Vec<Keypair> {
+    vec![
+        dev::alice(),
+        dev::bob(),
+        dev::charlie(),
+        dev::dave(),
+        dev::eve(),
+        dev::ferdie(),
+    ]
+}
+
+fn account_of(keypair: &Keypair) -> AccountId32 {
+    AccountId32(keypair.public_key().0)
+}
+
+/// Account of the token allocation pallet, holding what it pays out.
+fn pallet_account(pallet_id: [u8; 8]) -> AccountId32 {
+    let mut account = [0; 32];
+    account[..4].copy_from_slice(b"modl");
+    account[4..12].copy_from_slice(&pallet_id);
+    AccountId32(account)
+}
+
+fn envelope_value(id: &EnvelopeId) -> Value {
+    Value::unnamed_variant(format!("{id:?}"), [])
+}
+
+fn address_value(account: &AccountId32) -> Value {
+    Value::unnamed_variant("Id", [Value::from_bytes(account.0)])
+}
+
+fn set_storage(items: Vec<(Vec<u8>, Vec<u8>)>) -> Value {
+    let items = items.into_iter().map(|(key, value)| {
+        Value::unnamed_composite([Value::from_bytes(key), Value::from_bytes(value)])
+    });
+
+    Value::unnamed_variant(
+        "System",
+        [Value::named_variant(
+            "set_storage",
+            [("items", Value::unnamed_composite(items))],
+        )],
+    )
+}
+
+/// Dispatches `call` as root and waits for it to be finalized.
+async fn sudo(client: &AllfeatClient, signer: &Keypair, call: Value) -> Result<(), SeedError> {
+    let tx = subxt::dynamic::tx("Sudo", "sudo", vec![call]);
+    let events = client
+        .tx()
+        .sign_and_submit_then_watch_default(&tx, signer)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    // The sudo extrinsic succeeds whatever the outcome of the call
+    for event in events.iter() {
+        let event = event?;
+        if event.pallet_name() != "Sudo" || event.variant_name() != "Sudid" {
+            continue;
+        }
+        if let Some(ValueDef::Variant(result)) = event
+            .field_values()?
+            .into_values()
+            .next()
+            .map(|value| value.value)
+            && result.name == "Err"
+        {
+            return Err(format!("Sudo call failed: {}", result.values).into());
+        }
+    }
+    Ok(())
+}
+
+/// Dispatches `calls` as root, batched, each batch reverting as a whole.
+async fn sudo_batches(
+    client: &AllfeatClient,
+    signer: &Keypair,
+    calls: Vec<Value>,
+) -> Result<(), SeedError> {
+    let mut calls = calls.into_iter().peekable();
+    while calls.peek().is_some() {
+        let batch = Value::unnamed_variant(
+            "Utility",
+            [Value::named_variant(
+                "batch_all",
+                [(
+                    "calls",
+                    Value::unnamed_composite(calls.by_ref().take(BATCH_SIZE)),
+                )],
+            )],
+        );
+        sudo(client, signer, batch).await?;
+    }
+    Ok(())
+}
+
+pub async fn run(chain: &ChainConnection, options: &SeedDevOptions) -> Result<(), SeedError> {
+    let client = tokio::time::timeout(CONNECT_TIMEOUT, chain.wait_for_client())
+        .await
+        .map_err(|_| "No RPC endpoint is reachable")??;
+
+    let uri = SecretUri::from_str(&options.sudo).map_err(|_| "--sudo is not a secret URI")?;
+    let signer = Keypair::from_uri(&uri).map_err(|_| "--sudo is not a valid key")?;
+    let at = chain.latest_block().await.map_err(|e| e.to_string())?;
+    let sudo_key = chain.sudo_key(at.hash).await.map_err(|e| e.to_string())?;
+    if sudo_key != Some(account_of(&signer)) {
+        return Err(format!("{} is not the sudo key of this chain", options.sudo).into());
+    }
+
+    let mut rng = Rng(0x5eed_0000_0000_0001 ^ options.allocations as u64);
+    let envelopes = seed_envelopes(chain, &client, &signer, at.hash).await?;
+    seed_allocations(
+        &client,
+        &signer,
+        &envelopes,
+        options.allocations,
+        at.number,
+        &mut rng,
+    )
+    .await?;
+    seed_transfers(&client, options.transfers, &mut rng).await?;
+    advance_payouts(chain, &client, &signer, options.epochs).await?;
+
+    println!("Done, the explorer now has data to show");
+    Ok(())
+}
+
+/// Envelope open to allocations, with what is left of its cap.
+struct Envelope {
+    id: EnvelopeId,
+    remaining: u128,
+    unique_beneficiary: Option<AccountId32>,
+}
+
+/// Creates the envelopes the chain doesn't have, and returns every envelope
+/// with room left.
+async fn seed_envelopes(
+    chain: &ChainConnection,
+    client: &AllfeatClient,
+    signer: &Keypair,
+    at: subxt::utils::H256,
+) -> Result<Vec<Envelope>, SeedError> {
+    let keys = client.storage();
+    let api = allfeat::storage().token_allocation();
+
+    let mut envelopes = vec![];
+    let mut items = vec![];
+    let mut created_caps = 0u128;
+    for (index, (id, name)) in ENVELOPES.iter().enumerate() {
+        let (config, distributed) = tokio::try_join!(
+            chain.envelope(at, id.clone()),
+            chain.envelope_distributed(at, id.clone()),
+        )
+        .map_err(|e| e.to_string())?;
+
+        let Some(config) = config else {
+            let (upfront_rate, cliff, vesting_duration) = SCHEDULES[index % SCHEDULES.len()];
+            let total_cap = 10_000_000 * UNIT;
+            let unique_beneficiary: Option<[u8; 32]> = None;
+            items.push((
+                keys.address_bytes(&api.envelopes(id.clone()))?,
+                (
+                    total_cap,
+                    upfront_rate,
+                    cliff,
+                    vesting_duration,
+                    unique_beneficiary,
+                )
+                    .encode(),
+            ));
+            created_caps += total_cap;
+            println!(
+                "Creating the {name} envelope: cliff {cliff}, vesting {vesting_duration} blocks"
+            );
+
+            envelopes.push(Envelope {
+                id: id.clone(),
+                remaining: total_cap,
+                unique_beneficiary: None,
+            });
+            continue;
+        };
+
+        let remaining = config.total_cap.saturating_sub(distributed.unwrap_or(0));
+        if remaining > 0 {
+            envelopes.push(Envelope {
+                id: id.clone(),
+                remaining,
+                unique_beneficiary: config.unique_beneficiary,
+            });
+        }
+    }
+
+    if items.is_empty() {
+        return Ok(envelopes);
+    }
+
+    // The pallet pays the allocations out of its own account
+    let pallet_id = client
+        .constants()
+        .at(&allfeat::constants().token_allocation().pallet_id())?
+        .0;
+    let pallet = pallet_account(pallet_id);
+    let free = chain
+        .account(at, pallet.clone())
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|b| b.free)
+        .unwrap_or(0);
+    let fund = Value::unnamed_variant(
+        "Balances",
+        [Value::named_variant(
+            "force_set_balance",
+            [
+                ("who", address_value(&pallet)),
+                ("new_free", Value::u128(free + created_caps)),
+            ],
+        )],
+    );
+
+    sudo_batches(client, signer, vec![set_storage(items), fund]).await?;
+    println!(
+        "Funded the pallet account with {}",
+        format_balance(created_caps, true)
+    );
+    Ok(envelopes)
+}
+
+async fn seed_allocations(
+    client: &AllfeatClient,
+    signer: &Keypair,
+    envelopes: &[Envelope],
+    count: usize,
+    head: u32,
+    rng: &mut Rng,
+) -> Result<(), SeedError> {
+    if envelopes.is_empty() {
+        println!("Every envelope is fully distributed, no allocation added");
+        return Ok(());
+    }
+
+    let dev = dev_accounts().iter().map(account_of).collect::<Vec<_>>();
+    // A few allocations each, so accounts show several of them
+    let generated = (count as u64 / 3).max(1);
+
+    let mut remaining = envelopes.iter().map(|e| e.remaining).collect::<Vec<_>>();
+    let mut calls = vec![];
+    for _ in 0..count {
+        let index = rng.below(envelopes.len() as u64) as usize;
+        let envelope = &envelopes[index];
+        let total = ((1 + rng.below(50)) * 1_000) as u128 * UNIT;
+        if total > remaining[index] {
+            continue;
+        }
+        remaining[index] -= total;
+
+        let who = match &envelope.unique_beneficiary {
+            Some(account) => account.clone(),
+            None if rng.below(4) == 0 => rng.pick(&dev).clone(),
+            None => beneficiary(rng.below(generated)),
+        };
+        // Some start later, to show allocations yet to vest
+        let start = match rng.below(3) {
+            0 => Value::unnamed_variant(
+                "Some",
+                [Value::u128((head + 100 + rng.below(2_000) as u32) as u128)],
+            ),
+            _ => Value::unnamed_variant("None", []),
+        };
+
+        calls.push(Value::unnamed_variant(
+            "TokenAllocation",
+            [Value::named_variant(
+                "add_allocation",
+                [
+                    ("id", envelope_value(&envelope.id)),
+                    ("who", Value::from_bytes(who.0)),
+                    ("total", Value::u128(total)),
+                    ("start", start),
+                ],
+            )],
+        ));
+    }
+
+    let added = calls.len();
+    sudo_batches(client, signer, calls).await?;
+    println!("Added {added} allocations");
+    Ok(())
+}
+
+async fn seed_transfers(
+    client: &AllfeatClient,
+    count: usize,
+    rng: &mut Rng,
+) -> Result<(), SeedError> {
+    let dev = dev_accounts();
+
+    let mut sent = 0;
+    for _ in 0..count {
+        let from = rng.pick(&dev);
+        let to = match rng.below(3) {
+            0 => beneficiary(rng.below(100)),
+            _ => account_of(rng.pick(&dev)),
+        };
+        if to == account_of(from) {
+            continue;
+        }
+        let amount = (1 + rng.below(1_000)) as u128 * UNIT;
+
+        let call = allfeat::tx()
+            .balances()
+            .transfer_keep_alive(MultiAddress::Id(to), amount);
+        // Not waited for, the node hands out the next nonce of the pool
+        match client.tx().sign_and_submit_default(&call, from).await {
+            Ok(_) => sent += 1,
+            Err(e) => println!("Transfer from {} not sent: {e}", account_of(from)),
+        }
+    }
+
+    println!("Sent {sent} transfers");
+    Ok(())
+}
+
+/// Runs `epochs` payouts, one after the other.
+async fn advance_payouts(
+    chain: &ChainConnection,
+    client: &AllfeatClient,
+    signer: &Keypair,
+    epochs: u32,
+) -> Result<(), SeedError> {
+    let key = client
+        .storage()
+        .address_bytes(&allfeat::storage().token_allocation().next_payout_at())?;
+
+    for _ in 0..epochs {
+        let at = chain.latest_block().await.map_err(|e| e.to_string())?;
+        let due = at.number + 1;
+        sudo(
+            client,
+            signer,
+            set_storage(vec![(key.clone(), due.encode())]),
+        )
+        .await?;
+
+        let mut waited = 0;
+        loop {
+            let at = chain.latest_block().await.map_err(|e| e.to_string())?;
+            let payout = chain
+                .payout_state(at.hash)
+                .await
+                .map_err(|e| e.to_string())?;
+            if payout.next_payout_at > due && payout.payout_cursor.is_none() {
+                println!("Paid out epoch {}", payout.epoch_index);
+                break;
+            }
+
+            waited += 1;
+            if waited > PAYOUT_WAIT_BLOCKS {
+                return Err("The payout brought forward didn't run".into());
+            }
+            tokio::time::sleep(Duration::from_secs(crate::utils::secs_per_block())).await;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pallet_account_is_derived_like_the_runtime() {
+        let account = pallet_account(*b"py/alloc");
+        assert_eq!(&account.0[..12], b"modlpy/alloc");
+        assert!(account.0[12..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn seeds_the_same_data_each_run() {
+        let (mut a, mut b) = (Rng(7), Rng(7));
+        let draws = |rng: &mut Rng| (0..10).map(|_| rng.below(1_000)).collect::<Vec<_>>();
+        assert_eq!(draws(&mut a), draws(&mut b));
+        assert_ne!(beneficiary(0), beneficiary(1));
+    }
+}
@@ -0,0 +1,203 @@
+//! In-memory chain answering the [`ChainBackend`] queries.
+//!
+//! Storage has a single state, whatever block it is read at, and the latest
+//! block is the last one of `blocks`.
+
+use std::collections::HashMap;
+
+use subxt::utils::{AccountId32, H256};
+
+use super::ChainUnavailable;
+use super::allfeat::runtime_types::pallet_token_allocation::EnvelopeId;
+use super::backend::{
+    AllocationRecord, BackendFuture, BlockAt, BlockData, ChainBackend, EnvelopeConfig, PayoutState,
+};
+use crate::{AppError, Balances, FeeEstimate};
+
+#[derive(Clone, Debug, Default)]
+pub struct FixtureChain {
+    /// Oldest first
+    pub blocks: Vec<BlockData>,
+    pub total_issuance: Option<u128>,
+    /// By raw account id, `AccountId32` isn't `Hash`
+    pub accounts: HashMap<[u8; 32], Balances>,
+    pub envelopes: Vec<(EnvelopeId, EnvelopeConfig)>,
+    pub distributed: Vec<(EnvelopeId, u128)>,
+    /// In storage order
+    pub allocations: Vec<AllocationRecord>,
+    pub payout: PayoutState,
+    pub epoch_duration: u32,
+    pub max_payouts_per_block: u32,
+    pub sudo_key: Option<AccountId32>,
+    pub next_fee_multiplier: Option<u128>,
+    /// Answer to every fee query, which fail when `None`
+    pub fee: Option<FeeEstimate>,
+    /// Fails every query like an unreachable node
+    pub unavailable: bool,
+}
+
+impl FixtureChain {
+    /// Empty blocks numbered from 0 to `count - 1`.
+    pub fn with_blocks(mut self, count: u32) -> Self {
+        self.blocks = (0..count).map(empty_block).collect();
+        self
+    }
+
+    pub fn with_account(mut self, account: AccountId32, balances: Balances) -> Self {
+        self.accounts.insert(account.0, balances);
+        self
+    }
+
+    pub fn with_envelope(
+        mut self,
+        id: EnvelopeId,
+        config: EnvelopeConfig,
+        distributed: u128,
+    ) -> Self {
+        self.distributed.push((id.clone(), distributed));
+        self.envelopes.push((id, config));
+        self
+    }
+
+    pub fn with_allocation(mut self, allocation: AllocationRecord) -> Self {
+        self.allocations.push(allocation);
+        self
+    }
+
+    fn answer<T: Send + 'static>(&self, value: impl FnOnce() -> T) -> BackendFuture<'_, T> {
+        let result = if self.unavailable {
+            Err(ChainUnavailable.into())
+        } else {
+            Ok(value())
+        };
+        Box::pin(std::future::ready(result))
+    }
+
+    fn block_by_hash(&self, hash: H256) -> Option<&BlockData> {
+        self.blocks.iter().find(|block| block.hash == hash)
+    }
+}
+
+/// Hash of the fixture block `number`.
+pub fn block_hash(number: u32) -> H256 {
+    H256::from_low_u64_be(u64::from(number) + 1)
+}
+
+fn empty_block(number: u32) -> BlockData {
+    BlockData {
+        number,
+        hash: block_hash(number),
+        parent_hash: number.checked_sub(1).map(block_hash).unwrap_or_default(),
+        // One block every 6 seconds from 2025-01-01
+        timestamp: 1_735_689_600_000 + u64::from(number) * 6_000,
+        extrinsics: vec![],
+        events: vec![],
+    }
+}
+
+impl ChainBackend for FixtureChain {
+    fn latest_block(&self) -> BackendFuture<'_, BlockAt> {
+        self.answer(|| {
+            self.blocks
+                .last()
+                .map(|block| BlockAt {
+                    number: block.number,
+                    hash: block.hash,
+                })
+                .unwrap_or(BlockAt {
+                    number: 0,
+                    hash: H256::zero(),
+                })
+        })
+    }
+
+    fn block_hash(&self, number: u32) -> BackendFuture<'_, Option<H256>> {
+        self.answer(|| {
+            self.blocks
+                .iter()
+                .find(|block| block.number == number)
+                .map(|block| block.hash)
+        })
+    }
+
+    fn block_number(&self, hash: H256) -> BackendFuture<'_, Option<u32>> {
+        self.answer(|| self.block_by_hash(hash).map(|block| block.number))
+    }
+
+    fn block(&self, hash: H256) -> BackendFuture<'_, Option<BlockData>> {
+        self.answer(|| self.block_by_hash(hash).cloned())
+    }
+
+    fn total_issuance(&self, _at: H256) -> BackendFuture<'_, Option<u128>> {
+        self.answer(|| self.total_issuance)
+    }
+
+    fn account(&self, _at: H256, account: AccountId32) -> BackendFuture<'_, Option<Balances>> {
+        self.answer(|| self.accounts.get(&account.0).cloned())
+    }
+
+    fn envelope(&self, _at: H256, id: EnvelopeId) -> BackendFuture<'_, Option<EnvelopeConfig>> {
+        self.answer(|| {
+            self.envelopes
+                .iter()
+                .find(|(envelope, _)| *envelope == id)
+                .map(|(_, config)| config.clone())
+        })
+    }
+
+    fn envelope_distributed(&self, _at: H256, id: EnvelopeId) -> BackendFuture<'_, Option<u128>> {
+        self.answer(|| {
+            self.distributed
+                .iter()
+                .find(|(envelope, _)| *envelope == id)
+                .map(|(_, distributed)| *distributed)
+        })
+    }
+
+    fn allocations(&self, _at: H256) -> BackendFuture<'_, Vec<AllocationRecord>> {
+        self.answer(|| self.allocations.clone())
+    }
+
+    fn payout_state(&self, _at: H256) -> BackendFuture<'_, PayoutState> {
+        self.answer(|| self.payout.clone())
+    }
+
+    fn epoch_duration(&self) -> Result<u32, AppError> {
+        if self.unavailable {
+            return Err(ChainUnavailable.into());
+        }
+        Ok(self.epoch_duration)
+    }
+
+    fn max_payouts_per_block(&self) -> Result<u32, AppError> {
+        if self.unavailable {
+            return Err(ChainUnavailable.into());
+        }
+        Ok(self.max_payouts_per_block)
+    }
+
+    fn sudo_key(&self, _at: H256) -> BackendFuture<'_, Option<AccountId32>> {
+        self.answer(|| self.sudo_key.clone())
+    }
+
+    fn next_fee_multiplier(&self, _at: H256) -> BackendFuture<'_, Option<u128>> {
+        self.answer(|| self.next_fee_multiplier)
+    }
+
+    fn query_fee_info(&self, extrinsic: Vec<u8>) -> BackendFuture<'_, FeeEstimate> {
+        if self.unavailable {
+            return Box::pin(std::future::ready(Err(ChainUnavailable.into())));
+        }
+
+        let result = match &self.fee {
+            Some(fee) => Ok(FeeEstimate {
+                length: extrinsic.len() as u32,
+                ..fee.clone()
+            }),
+            None => Err(AppError::InvalidInput(
+                "Fee estimation failed: invalid extrinsic".to_string(),
+            )),
+        };
+        Box::pin(std::future::ready(result))
+    }
+}
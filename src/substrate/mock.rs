@@ -0,0 +1,832 @@
+//! In-process node answering the client from a chain fixture, to run the
+//! explorer and its end-to-end tests without network (`mock-chain` feature).
+//!
+//! Endpoints written `mock://<path>` load the JSON fixture at `<path>`. It
+//! describes the chain the way the pages show it: accounts, envelopes,
+//! allocations and payouts are encoded into the storage of every block,
+//! which are empty and finalized. The runtime is the one of the bundled
+//! metadata, and `storage` entries are raw hex keys and values layered on
+//! top, for what the fixture has no field for.
+//!
+//! Paths ending in `.jsonl` are [recordings](super::recording) instead,
+//! replayed request by request: the same request gets the answers it got
+//! while recording, in order, the last one repeating.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+use std::sync::Mutex;
+
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use futures::{StreamExt, stream};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use subxt::backend::rpc::{RawRpcFuture, RawRpcSubscription, RawValue, RpcClientT};
+use subxt::client::RuntimeVersion;
+use subxt::ext::codec::{Compact, Decode, Encode};
+use subxt::ext::scale_encode::EncodeAsType;
+use subxt::ext::subxt_rpcs::Error as RpcError;
+use subxt::utils::{AccountId32, H256};
+use subxt::{Metadata, OfflineClient, SubstrateConfig};
+
+use super::allfeat;
+use super::allfeat::runtime_types::pallet_token_allocation::EnvelopeId;
+use super::backend::{NEXT_FEE_MULTIPLIER_STORAGE, SUDO_KEY_STORAGE};
+use super::recording::{Entry, to_value};
+use crate::ENVELOPES;
+use crate::indexer::TIMESTAMP_NOW_STORAGE;
+use crate::utils::decode_hex;
+
+/// Scheme of the endpoints served by a [`MockRpc`].
+pub const SCHEME: &str = "mock://";
+
+const METADATA: &[u8] = include_bytes!("../../artifacts/allfeat_metadata.scale");
+
+/// `AccountData.flags` of accounts created after the balances migration.
+const NEW_LOGIC_FLAG: u128 = 1 << 127;
+
+type LoadError = Box<dyn std::error::Error + Send + Sync>;
+
+type Answer = Result<Value, String>;
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Fixture {
+    /// Blocks numbered from 0, the last one being the finalized head
+    pub blocks: u32,
+    /// Timestamp of block 0, in milliseconds
+    pub genesis_timestamp: u64,
+    pub block_time_ms: u64,
+    pub spec_version: u32,
+    pub transaction_version: u32,
+    pub total_issuance: u128,
+    pub accounts: Vec<FixtureAccount>,
+    pub envelopes: Vec<FixtureEnvelope>,
+    /// In storage order
+    pub allocations: Vec<FixtureAllocation>,
+    pub payout: FixturePayout,
+    pub sudo_key: Option<String>,
+    pub next_fee_multiplier: Option<u128>,
+    /// Raw hex storage, overriding the entries above
+    pub storage: BTreeMap<String, String>,
+}
+
+impl Default for Fixture {
+    fn default() -> Self {
+        Self {
+            blocks: 10,
+            // 2025-01-01
+            genesis_timestamp: 1_735_689_600_000,
+            block_time_ms: 6_000,
+            spec_version: 1,
+            transaction_version: 1,
+            total_issuance: 0,
+            accounts: vec![],
+            envelopes: vec![],
+            allocations: vec![],
+            payout: FixturePayout::default(),
+            sudo_key: None,
+            next_fee_multiplier: None,
+            storage: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureAccount {
+    pub address: String,
+    pub free: u128,
+    #[serde(default)]
+    pub reserved: u128,
+    #[serde(default)]
+    pub frozen: u128,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureEnvelope {
+    /// Variant name of the `EnvelopeId`, e.g. `CommunityRewards`
+    pub id: String,
+    pub total_cap: u128,
+    pub upfront_rate: u8,
+    pub cliff: u32,
+    pub vesting_duration: u32,
+    #[serde(default)]
+    pub unique_beneficiary: Option<String>,
+    #[serde(default)]
+    pub distributed: u128,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureAllocation {
+    pub envelope: String,
+    pub beneficiary: String,
+    pub total: u128,
+    pub upfront: u128,
+    pub vested_total: u128,
+    #[serde(default)]
+    pub released: u128,
+    #[serde(default)]
+    pub start: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FixturePayout {
+    pub next_payout_at: u32,
+    pub epoch_index: u64,
+    pub payout_cursor: Option<u32>,
+    /// Number of allocations when not given
+    pub next_allocation_id: Option<u32>,
+}
+
+#[derive(Debug)]
+struct MockBlock {
+    number: u32,
+    hash: H256,
+    parent_hash: H256,
+    timestamp: u64,
+}
+
+impl MockBlock {
+    fn header(&self) -> Value {
+        json!({
+            "parentHash": format!("{:?}", self.parent_hash),
+            "number": format!("{:#x}", self.number),
+            "stateRoot": format!("{:?}", H256::zero()),
+            "extrinsicsRoot": format!("{:?}", H256::zero()),
+            "digest": { "logs": [] },
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct MockRpc {
+    spec_version: u32,
+    transaction_version: u32,
+    /// Oldest first, never empty
+    blocks: Vec<MockBlock>,
+    storage: BTreeMap<Vec<u8>, Vec<u8>>,
+    /// `Timestamp.Now`, answered from the block read at
+    timestamp_key: Vec<u8>,
+    replay: Option<Replay>,
+}
+
+impl MockRpc {
+    /// Loads the fixture or the recording at `path`.
+    pub fn load(path: &str) -> Result<Self, LoadError> {
+        if path.ends_with(".jsonl") {
+            return Ok(Self {
+                replay: Some(Replay::read(path)?),
+                ..Self::new(Fixture::default())?
+            });
+        }
+
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read mock chain {path}: {e}"))?;
+        let fixture: Fixture =
+            serde_json::from_str(&json).map_err(|e| format!("Invalid mock chain {path}: {e}"))?;
+        Self::new(fixture)
+    }
+
+    pub fn new(fixture: Fixture) -> Result<Self, LoadError> {
+        let mut blocks: Vec<MockBlock> = vec![];
+        for number in 0..fixture.blocks.max(1) {
+            let parent_hash = blocks.last().map(|b| b.hash).unwrap_or_default();
+            blocks.push(MockBlock {
+                number,
+                hash: header_hash(parent_hash, number),
+                parent_hash,
+                timestamp: fixture.genesis_timestamp + u64::from(number) * fixture.block_time_ms,
+            });
+        }
+
+        // Keys are computed from the metadata the client will be given
+        let metadata = Metadata::decode(&mut &METADATA[..])?;
+        let client = OfflineClient::<SubstrateConfig>::new(
+            blocks[0].hash,
+            RuntimeVersion {
+                spec_version: fixture.spec_version,
+                transaction_version: fixture.transaction_version,
+            },
+            metadata,
+        );
+        let keys = client.storage();
+        let api = allfeat::storage();
+
+        let mut storage = BTreeMap::new();
+        // No block has events
+        storage.insert(keys.address_bytes(&api.system().events())?, vec![0]);
+        storage.insert(
+            keys.address_bytes(&api.balances().total_issuance())?,
+            fixture.total_issuance.encode(),
+        );
+
+        for account in &fixture.accounts {
+            let id = parse_account(&account.address)?;
+            // AccountInfo: nonce, consumers, providers, sufficients, then AccountData
+            let info = (
+                0u32,
+                0u32,
+                1u32,
+                0u32,
+                account.free,
+                account.reserved,
+                account.frozen,
+                NEW_LOGIC_FLAG,
+            );
+            storage.insert(
+                keys.address_bytes(&api.system().account(id))?,
+                info.encode(),
+            );
+        }
+
+        for envelope in &fixture.envelopes {
+            let id = parse_envelope(&envelope.id)?;
+            let unique_beneficiary = envelope
+                .unique_beneficiary
+                .as_deref()
+                .map(parse_account)
+                .transpose()?
+                .map(|account| account.0);
+            let config = (
+                envelope.total_cap,
+                envelope.upfront_rate,
+                envelope.cliff,
+                envelope.vesting_duration,
+                unique_beneficiary,
+            );
+            storage.insert(
+                keys.address_bytes(&api.token_allocation().envelopes(id.clone()))?,
+                config.encode(),
+            );
+            storage.insert(
+                keys.address_bytes(&api.token_allocation().envelope_distributed(id))?,
+                envelope.distributed.encode(),
+            );
+        }
+
+        // Runtime enums only implement `EncodeAsType`, which needs their type
+        let metadata = client.metadata();
+        let envelope_ty = metadata
+            .pallet_by_name("TokenAllocation")
+            .and_then(|pallet| pallet.storage())
+            .and_then(|storage| storage.entry_by_name("Envelopes"))
+            .and_then(|entry| entry.entry_type().key_ty())
+            .ok_or("TokenAllocation::Envelopes is missing from the metadata")?;
+
+        for (index, allocation) in fixture.allocations.iter().enumerate() {
+            let mut record = parse_envelope(&allocation.envelope)?
+                .encode_as_type(envelope_ty, metadata.types())?;
+            (
+                parse_account(&allocation.beneficiary)?.0,
+                allocation.total,
+                allocation.upfront,
+                allocation.vested_total,
+                allocation.released,
+                allocation.start,
+            )
+                .encode_to(&mut record);
+            storage.insert(
+                keys.address_bytes(&api.token_allocation().allocations(index as u32))?,
+                record,
+            );
+        }
+
+        let payout = &fixture.payout;
+        let next_allocation_id = payout
+            .next_allocation_id
+            .unwrap_or(fixture.allocations.len() as u32);
+        storage.insert(
+            keys.address_bytes(&api.token_allocation().next_payout_at())?,
+            payout.next_payout_at.encode(),
+        );
+        storage.insert(
+            keys.address_bytes(&api.token_allocation().epoch_index())?,
+            payout.epoch_index.encode(),
+        );
+        storage.insert(
+            keys.address_bytes(&api.token_allocation().next_allocation_id())?,
+            next_allocation_id.encode(),
+        );
+        if let Some(cursor) = payout.payout_cursor {
+            storage.insert(
+                keys.address_bytes(&api.token_allocation().payout_cursor())?,
+                cursor.encode(),
+            );
+        }
+
+        // Neither pallet is part of the bundled metadata
+        if let Some(sudo_key) = &fixture.sudo_key {
+            storage.insert(
+                decode_hex(SUDO_KEY_STORAGE).unwrap_or_default(),
+                parse_account(sudo_key)?.0.encode(),
+            );
+        }
+        if let Some(multiplier) = fixture.next_fee_multiplier {
+            storage.insert(
+                decode_hex(NEXT_FEE_MULTIPLIER_STORAGE).unwrap_or_default(),
+                multiplier.encode(),
+            );
+        }
+
+        for (key, value) in &fixture.storage {
+            let (Some(key), Some(value)) = (decode_hex(key), decode_hex(value)) else {
+                return Err(format!("storage: {key:?} is not a hex key and value").into());
+            };
+            storage.insert(key, value);
+        }
+
+        let timestamp_key = decode_hex(TIMESTAMP_NOW_STORAGE).unwrap_or_default();
+
+        Ok(Self {
+            spec_version: fixture.spec_version,
+            transaction_version: fixture.transaction_version,
+            blocks,
+            storage,
+            timestamp_key,
+            replay: None,
+        })
+    }
+
+    fn head(&self) -> &MockBlock {
+        self.blocks.last().expect("at least one block")
+    }
+
+    /// Block a query is made at, the head when not given.
+    fn block_at(&self, at: Option<&Value>) -> Option<&MockBlock> {
+        match at {
+            None | Some(Value::Null) => Some(self.head()),
+            Some(Value::String(hash)) => {
+                let hash = decode_hex(hash)?;
+                self.blocks
+                    .iter()
+                    .find(|block| block.hash.0[..] == hash[..])
+            }
+            _ => None,
+        }
+    }
+
+    fn runtime_version(&self) -> Value {
+        json!({
+            "specName": "allfeat",
+            "implName": "allfeat",
+            "specVersion": self.spec_version,
+            "transactionVersion": self.transaction_version,
+        })
+    }
+
+    fn storage_value(&self, key: &[u8], at: Option<&Value>) -> Option<Vec<u8>> {
+        if key == self.timestamp_key {
+            return self.block_at(at).map(|block| block.timestamp.encode());
+        }
+        self.storage.get(key).cloned()
+    }
+
+    /// Answer to a request, or why it can't be answered.
+    fn call(&self, method: &str, params: &[Value]) -> Result<Value, String> {
+        let param = |index: usize| params.get(index);
+        let bytes = |index: usize| {
+            param(index)
+                .and_then(Value::as_str)
+                .and_then(decode_hex)
+                .ok_or_else(|| format!("{method}: parameter {index} is not hex"))
+        };
+
+        let result = match method {
+            "chain_getBlockHash" => {
+                let number = match param(0) {
+                    None | Some(Value::Null) => Some(self.head().number),
+                    Some(Value::Number(number)) => {
+                        number.as_u64().and_then(|n| u32::try_from(n).ok())
+                    }
+                    Some(Value::String(hex)) => {
+                        u32::from_str_radix(hex.trim_start_matches("0x"), 16).ok()
+                    }
+                    Some(_) => None,
+                };
+                let hash = number
+                    .and_then(|number| self.blocks.get(number as usize))
+                    .map(|block| block.hash);
+                json!(hash.map(|hash| format!("{hash:?}")))
+            }
+            "chain_getFinalizedHead" => json!(format!("{:?}", self.head().hash)),
+            "chain_getHeader" => json!(self.block_at(param(0)).map(MockBlock::header)),
+            "chain_getBlock" => json!(self.block_at(param(0)).map(|block| json!({
+                "block": { "header": block.header(), "extrinsics": [] },
+                "justifications": null,
+            }))),
+            "state_getRuntimeVersion" => self.runtime_version(),
+            "state_getMetadata" => json!(to_hex(METADATA)),
+            "state_call" => {
+                let name = param(0).and_then(Value::as_str).unwrap_or_default();
+                // `0x` decodes to nothing
+                let data = bytes(1).unwrap_or_default();
+                let version = u32::from(METADATA[4]);
+                let output = match name {
+                    "Metadata_metadata_versions" => vec![version].encode(),
+                    "Metadata_metadata_at_version" => {
+                        let requested = u32::decode(&mut &data[..]).map_err(|e| e.to_string())?;
+                        (requested == version).then(|| METADATA.to_vec()).encode()
+                    }
+                    "Metadata_metadata" => METADATA.to_vec().encode(),
+                    _ => {
+                        return Err(format!(
+                            "Runtime API {name} is not served by the mock chain"
+                        ));
+                    }
+                };
+                json!(to_hex(&output))
+            }
+            "state_getStorage" => {
+                let value = self.storage_value(&bytes(0)?, param(1));
+                json!(value.map(|value| to_hex(&value)))
+            }
+            "state_getKeysPaged" => {
+                let prefix = bytes(0)?;
+                let count = param(1).and_then(Value::as_u64).unwrap_or(u64::MAX) as usize;
+                let start = match param(2) {
+                    None | Some(Value::Null) => Bound::Included(prefix.clone()),
+                    Some(_) => Bound::Excluded(bytes(2)?),
+                };
+                let keys: Vec<String> = self
+                    .storage
+                    .range((start, Bound::Unbounded))
+                    .map(|(key, _)| key)
+                    .take_while(|key| key.starts_with(&prefix))
+                    .take(count)
+                    .map(|key| to_hex(key))
+                    .collect();
+                json!(keys)
+            }
+            "state_queryStorageAt" => {
+                let keys: Vec<Vec<u8>> = param(0)
+                    .and_then(Value::as_array)
+                    .map(|keys| {
+                        keys.iter()
+                            .filter_map(|k| k.as_str().and_then(decode_hex))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let at = self
+                    .block_at(param(1))
+                    .ok_or_else(|| format!("{method}: unknown block"))?;
+                let changes: Vec<Value> = keys
+                    .iter()
+                    .map(|key| {
+                        let value = self.storage_value(key, param(1));
+                        json!([to_hex(key), value.map(|value| to_hex(&value))])
+                    })
+                    .collect();
+                json!([{ "block": format!("{:?}", at.hash), "changes": changes }])
+            }
+            "system_health" => json!({ "peers": 0, "isSyncing": false, "shouldHavePeers": false }),
+            _ => return Err(format!("{method} is not served by the mock chain")),
+        };
+
+        Ok(result)
+    }
+
+    /// Notifications of a subscription, which then stays open.
+    fn notifications(&self, method: &str) -> Result<Vec<Value>, String> {
+        match method {
+            "chain_subscribeFinalizedHeads"
+            | "chain_subscribeNewHeads"
+            | "chain_subscribeAllHeads" => Ok(vec![self.head().header()]),
+            "state_subscribeRuntimeVersion" => Ok(vec![self.runtime_version()]),
+            _ => Err(format!("{method} is not served by the mock chain")),
+        }
+    }
+}
+
+impl RpcClientT for MockRpc {
+    fn request_raw<'a>(
+        &'a self,
+        method: &'a str,
+        params: Option<Box<RawValue>>,
+    ) -> RawRpcFuture<'a, Box<RawValue>> {
+        Box::pin(async move {
+            let params = to_value(params.as_deref());
+            let result = match &self.replay {
+                // Health checks don't depend on how long the recording ran
+                Some(replay) if method != "system_health" => replay.request(method, &params),
+                _ => self.call(method, &positional(params)),
+            };
+            to_raw(&result.map_err(client_error)?)
+        })
+    }
+
+    fn subscribe_raw<'a>(
+        &'a self,
+        sub: &'a str,
+        params: Option<Box<RawValue>>,
+        _unsub: &'a str,
+    ) -> RawRpcFuture<'a, RawRpcSubscription> {
+        Box::pin(async move {
+            let params = to_value(params.as_deref());
+            let notifications = match &self.replay {
+                Some(replay) => replay.subscribe(sub, &params),
+                None => self
+                    .notifications(sub)
+                    .map(|items| items.into_iter().map(Ok).collect()),
+            }
+            .map_err(client_error)?
+            .into_iter()
+            .map(|item| to_raw(&item.map_err(client_error)?))
+            .collect::<Vec<_>>();
+            Ok(RawRpcSubscription {
+                stream: stream::iter(notifications).chain(stream::pending()).boxed(),
+                id: Some(sub.to_string()),
+            })
+        })
+    }
+}
+
+/// Recorded traffic, see [`super::recording`].
+#[derive(Debug, Default)]
+struct Replay {
+    /// Answers by request, in recording order
+    requests: HashMap<String, Vec<Answer>>,
+    /// Notifications of every subscription, by subscription request
+    subscriptions: HashMap<String, Vec<Result<Vec<Answer>, String>>>,
+    /// Answers replayed so far, by request
+    served: Mutex<HashMap<String, usize>>,
+}
+
+impl Replay {
+    fn read(path: &str) -> Result<Self, LoadError> {
+        let recording = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read recording {path}: {e}"))?;
+
+        let mut replay = Self::default();
+        // Subscription request and index of every recorded subscription id
+        let mut opened = HashMap::new();
+        for (index, line) in recording.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: Entry = serde_json::from_str(line)
+                .map_err(|e| format!("Invalid recording {path}:{}: {e}", index + 1))?;
+
+            match entry {
+                Entry::Request {
+                    method,
+                    params,
+                    result,
+                    error,
+                } => {
+                    replay
+                        .requests
+                        .entry(request_key(&method, &params))
+                        .or_default()
+                        .push(answer(result, error));
+                }
+                Entry::Subscribe {
+                    id,
+                    method,
+                    params,
+                    error,
+                } => {
+                    let key = request_key(&method, &params);
+                    let subscriptions = replay.subscriptions.entry(key.clone()).or_default();
+                    opened.insert(id, (key, subscriptions.len()));
+                    subscriptions.push(match error {
+                        Some(error) => Err(error),
+                        None => Ok(vec![]),
+                    });
+                }
+                Entry::Notification { id, result, error } => {
+                    if let Some((key, index)) = opened.get(&id)
+                        && let Some(Ok(notifications)) = replay
+                            .subscriptions
+                            .get_mut(key)
+                            .and_then(|subscriptions| subscriptions.get_mut(*index))
+                    {
+                        notifications.push(answer(result, error));
+                    }
+                }
+            }
+        }
+
+        Ok(replay)
+    }
+
+    /// Next of the `answers` to `key`, the last one repeating.
+    fn next<'a, T>(&self, key: &str, answers: &'a [T]) -> Option<&'a T> {
+        let mut served = self.served.lock().unwrap_or_else(|e| e.into_inner());
+        let count = served.entry(key.to_string()).or_default();
+        let answer = answers.get(*count).or(answers.last());
+        *count += 1;
+        answer
+    }
+
+    fn request(&self, method: &str, params: &Value) -> Answer {
+        let key = request_key(method, params);
+        let answers = self
+            .requests
+            .get(&key)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        self.next(&key, answers)
+            .cloned()
+            .unwrap_or_else(|| Err(format!("{key} was not recorded")))
+    }
+
+    fn subscribe(&self, method: &str, params: &Value) -> Result<Vec<Answer>, String> {
+        let key = request_key(method, params);
+        let subscriptions = self
+            .subscriptions
+            .get(&key)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        self.next(&key, subscriptions)
+            .cloned()
+            .unwrap_or_else(|| Err(format!("{key} was not recorded")))
+    }
+}
+
+fn request_key(method: &str, params: &Value) -> String {
+    format!("{method} {params}")
+}
+
+fn answer(result: Option<Value>, error: Option<String>) -> Answer {
+    match error {
+        Some(error) => Err(error),
+        None => Ok(result.unwrap_or(Value::Null)),
+    }
+}
+
+/// Parameters of a request, which are positional for every method served.
+fn positional(params: Value) -> Vec<Value> {
+    match params {
+        Value::Array(params) => params,
+        Value::Null => vec![],
+        params => vec![params],
+    }
+}
+
+fn to_raw(value: &Value) -> Result<Box<RawValue>, RpcError> {
+    RawValue::from_string(value.to_string()).map_err(|e| RpcError::Client(Box::new(e)))
+}
+
+fn client_error(message: String) -> RpcError {
+    RpcError::Client(message.into())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("0x{hex}")
+}
+
+/// Hash of an empty header: blake2-256 of its SCALE encoding.
+fn header_hash(parent_hash: H256, number: u32) -> H256 {
+    let header = (
+        parent_hash,
+        Compact(number),
+        H256::zero(),
+        H256::zero(),
+        // No digest log
+        Compact(0u32),
+    );
+    H256::from_slice(&Blake2b::<U32>::digest(header.encode()))
+}
+
+fn parse_account(address: &str) -> Result<AccountId32, String> {
+    crate::ss58::decode_account(address)
+        .map(|(_, account)| AccountId32(account))
+        .map_err(|e| format!("{address:?}: {e}"))
+}
+
+fn parse_envelope(name: &str) -> Result<EnvelopeId, String> {
+    ENVELOPES
+        .iter()
+        .find(|(id, _)| format!("{id:?}") == name)
+        .map(|(id, _)| id.clone())
+        .ok_or_else(|| format!("{name:?} is not an envelope"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock() -> MockRpc {
+        MockRpc::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/end2end/fixtures/chain.json"
+        ))
+        .expect("valid fixture")
+    }
+
+    #[test]
+    fn blocks_chain_up_to_the_finalized_head() {
+        let mock = mock();
+        let head = mock.call("chain_getFinalizedHead", &[]).unwrap();
+        let header = mock.call("chain_getHeader", std::slice::from_ref(&head)).unwrap();
+        let parent = mock
+            .call("chain_getBlockHash", &[json!(mock.head().number - 1)])
+            .unwrap();
+
+        assert_eq!(header["parentHash"], parent);
+        assert_eq!(
+            mock.call("chain_getBlockHash", &[json!(0)]).unwrap(),
+            json!(format!("{:?}", mock.blocks[0].hash))
+        );
+        assert_eq!(
+            mock.call("chain_getBlockHash", &[json!(10_000)]).unwrap(),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn timestamps_follow_the_block_read_at() {
+        let mock = mock();
+        let key = json!(to_hex(&mock.timestamp_key));
+        let at = json!(format!("{:?}", mock.blocks[1].hash));
+
+        let now = mock.call("state_getStorage", &[key, at]).unwrap();
+        let now = decode_hex(now.as_str().unwrap()).unwrap();
+        assert_eq!(
+            u64::decode(&mut &now[..]).unwrap(),
+            mock.blocks[1].timestamp
+        );
+    }
+
+    #[test]
+    fn pages_keys_under_a_prefix() {
+        let mock = mock();
+        let prefix = mock.storage.keys().next().unwrap()[..16].to_vec();
+        let all = mock
+            .storage
+            .keys()
+            .filter(|key| key.starts_with(&prefix))
+            .count();
+
+        let first = mock
+            .call("state_getKeysPaged", &[json!(to_hex(&prefix)), json!(1)])
+            .unwrap();
+        let rest = mock
+            .call(
+                "state_getKeysPaged",
+                &[json!(to_hex(&prefix)), json!(1000), first[0].clone()],
+            )
+            .unwrap();
+        assert_eq!(first.as_array().unwrap().len(), 1);
+        assert_eq!(rest.as_array().unwrap().len(), all - 1);
+    }
+
+    #[test]
+    fn replays_recorded_answers_in_order() {
+        let path = std::env::temp_dir().join("token-app-replay-test.jsonl");
+        let lines = [
+            r#"{"type":"request","method":"chain_getFinalizedHead","params":[],"result":"0x01"}"#,
+            r#"{"type":"request","method":"chain_getFinalizedHead","params":[],"result":"0x02"}"#,
+            r#"{"type":"subscribe","id":0,"method":"chain_subscribeFinalizedHeads","params":[]}"#,
+            r#"{"type":"notification","id":0,"result":{"number":"0x2"}}"#,
+            r#"{"type":"request","method":"chain_getHeader","params":["0x03"],"error":"gone"}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        let replay = Replay::read(path.to_str().unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let head = |replay: &Replay| replay.request("chain_getFinalizedHead", &json!([]));
+        assert_eq!(head(&replay), Ok(json!("0x01")));
+        assert_eq!(head(&replay), Ok(json!("0x02")));
+        assert_eq!(head(&replay), Ok(json!("0x02")));
+
+        assert_eq!(
+            replay.subscribe("chain_subscribeFinalizedHeads", &json!([])),
+            Ok(vec![Ok(json!({ "number": "0x2" }))])
+        );
+        assert_eq!(
+            replay.request("chain_getHeader", &json!(["0x03"])),
+            Err("gone".to_string())
+        );
+        assert!(replay.request("chain_getHeader", &json!(["0x04"])).is_err());
+    }
+
+    #[test]
+    fn serves_the_bundled_metadata() {
+        let mock = mock();
+        let versions = mock
+            .call(
+                "state_call",
+                &[json!("Metadata_metadata_versions"), json!("0x")],
+            )
+            .unwrap();
+        let versions = decode_hex(versions.as_str().unwrap()).unwrap();
+
+        assert_eq!(
+            Vec::<u32>::decode(&mut &versions[..]).unwrap(),
+            [u32::from(METADATA[4])]
+        );
+        assert!(
+            mock.call(
+                "state_call",
+                &[json!("TransactionPaymentApi_query_info"), json!("0x")]
+            )
+            .is_err()
+        );
+    }
+}
@@ -1,3 +1,12 @@
+pub mod backend;
+pub mod connection;
+pub mod dev_seed;
+pub mod fixture;
+#[cfg(feature = "mock-chain")]
+pub mod mock;
+pub mod recording;
+
+use subxt::backend::legacy::LegacyRpcMethods;
 use subxt::{OnlineClient, SubstrateConfig};
 
 // Generate an interface that we can use from the node's metadata.
@@ -7,4 +16,11 @@ use subxt::{OnlineClient, SubstrateConfig};
 )]
 pub mod allfeat {}
 
+pub use backend::ChainBackend;
+pub use connection::{ChainConnection, ChainUnavailable};
+
 pub type AllfeatClient = OnlineClient<SubstrateConfig>;
+
+/// Raw node RPC methods, for what the client doesn't expose (e.g. block
+/// hashes by number).
+pub type AllfeatRpc = LegacyRpcMethods<SubstrateConfig>;
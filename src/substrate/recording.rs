@@ -0,0 +1,169 @@
+//! Recording of the JSON-RPC traffic between the client and the node, to
+//! reproduce what the explorer saw without network.
+//!
+//! With `rpc.record` set, every request, subscription and notification is
+//! appended to the file as one JSON entry per line, as soon as it happens.
+//! Builds with the `mock-chain` feature replay a recording given as a
+//! `mock://<path>.jsonl` endpoint.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use subxt::backend::rpc::{RawRpcFuture, RawRpcSubscription, RawValue, RpcClientT};
+use tracing::warn;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Entry {
+    Request {
+        method: String,
+        params: Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        result: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// Subscription opened, numbered by `id` in the recording
+    Subscribe {
+        id: u64,
+        method: String,
+        params: Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    Notification {
+        id: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        result: Option<Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+}
+
+/// File the traffic is recorded to, shared by the clients of every endpoint.
+#[derive(Debug)]
+pub struct RpcRecording {
+    file: Mutex<File>,
+    next_subscription: AtomicU64,
+}
+
+impl RpcRecording {
+    /// Starts a recording at `path`, replacing any previous one.
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            file: Mutex::new(File::create(path)?),
+            next_subscription: AtomicU64::new(0),
+        })
+    }
+
+    fn write(&self, entry: &Entry) {
+        let Ok(mut line) = serde_json::to_string(entry) else {
+            return;
+        };
+        line.push('\n');
+
+        // One write per entry keeps the lines whole
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = file.write_all(line.as_bytes()) {
+            warn!(target: "allfeat", "Failed to record RPC traffic: {e}");
+        }
+    }
+}
+
+/// JSON of raw parameters or results, `null` when there are none.
+pub fn to_value(raw: Option<&RawValue>) -> Value {
+    raw.and_then(|raw| serde_json::from_str(raw.get()).ok())
+        .unwrap_or(Value::Null)
+}
+
+/// Records the traffic of the client it wraps.
+pub struct Recorder<R> {
+    pub inner: R,
+    pub recording: Arc<RpcRecording>,
+}
+
+impl<R: RpcClientT> RpcClientT for Recorder<R> {
+    fn request_raw<'a>(
+        &'a self,
+        method: &'a str,
+        params: Option<Box<RawValue>>,
+    ) -> RawRpcFuture<'a, Box<RawValue>> {
+        Box::pin(async move {
+            let recorded_params = to_value(params.as_deref());
+            let result = self.inner.request_raw(method, params).await;
+
+            self.recording.write(&Entry::Request {
+                method: method.to_string(),
+                params: recorded_params,
+                result: result.as_deref().ok().map(|raw| to_value(Some(raw))),
+                error: result.as_ref().err().map(ToString::to_string),
+            });
+            result
+        })
+    }
+
+    fn subscribe_raw<'a>(
+        &'a self,
+        sub: &'a str,
+        params: Option<Box<RawValue>>,
+        unsub: &'a str,
+    ) -> RawRpcFuture<'a, RawRpcSubscription> {
+        Box::pin(async move {
+            let id = self
+                .recording
+                .next_subscription
+                .fetch_add(1, Ordering::Relaxed);
+            let recorded_params = to_value(params.as_deref());
+            let result = self.inner.subscribe_raw(sub, params, unsub).await;
+
+            self.recording.write(&Entry::Subscribe {
+                id,
+                method: sub.to_string(),
+                params: recorded_params,
+                error: result.as_ref().err().map(ToString::to_string),
+            });
+
+            let mut subscription = result?;
+            let recording = self.recording.clone();
+            subscription.stream = subscription
+                .stream
+                .inspect(move |item| {
+                    recording.write(&Entry::Notification {
+                        id,
+                        result: item.as_deref().ok().map(|raw| to_value(Some(raw))),
+                        error: item.as_ref().err().map(ToString::to_string),
+                    })
+                })
+                .boxed();
+            Ok(subscription)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_are_tagged_lines() {
+        let entry = Entry::Request {
+            method: "chain_getBlockHash".to_string(),
+            params: serde_json::json!([0]),
+            result: Some(Value::String("0x01".to_string())),
+            error: None,
+        };
+
+        let line = serde_json::to_string(&entry).unwrap();
+        assert_eq!(
+            line,
+            r#"{"type":"request","method":"chain_getBlockHash","params":[0],"result":"0x01"}"#
+        );
+        assert_eq!(serde_json::from_str::<Entry>(&line).unwrap(), entry);
+    }
+}
@@ -0,0 +1,205 @@
+//! Circulating supply methodologies, shared by the server and the browser.
+//!
+//! Aggregators don't agree on what circulates. Every methodology starts from
+//! the same `SupplyBreakdown` so their figures can be compared side by side,
+//! `Standard` being the one the explorer has always served.
+
+use serde::{Deserialize, Serialize};
+
+use crate::SupplyBreakdown;
+#[cfg(feature = "ssr")]
+use crate::substrate::allfeat::runtime_types::pallet_token_allocation::EnvelopeId;
+
+/// Envelopes excluded by `ExcludeTeamAndReserve`. Runtime types only exist
+/// on the server, which flags them in `EnvelopeSupply::insider`.
+#[cfg(feature = "ssr")]
+pub fn is_insider(id: &EnvelopeId) -> bool {
+    matches!(id, EnvelopeId::Teams | EnvelopeId::Reserve)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupplyMethodology {
+    /// Distributed by the envelopes, less what is still vesting
+    #[default]
+    Standard,
+    /// `Standard`, less the free balance of the treasury
+    ExcludeTreasury,
+    /// `Standard`, less the unlocked part of the Teams and Reserve envelopes
+    ExcludeTeamAndReserve,
+    /// `Standard`, less the balances frozen by locks and holds of every
+    /// account
+    ExcludeFrozen,
+}
+
+impl SupplyMethodology {
+    pub const ALL: [Self; 4] = [
+        Self::Standard,
+        Self::ExcludeTreasury,
+        Self::ExcludeTeamAndReserve,
+        Self::ExcludeFrozen,
+    ];
+
+    /// Identifier used in the configuration and the API.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::ExcludeTreasury => "exclude_treasury",
+            Self::ExcludeTeamAndReserve => "exclude_team_and_reserve",
+            Self::ExcludeFrozen => "exclude_frozen",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Standard => "Standard",
+            Self::ExcludeTreasury => "Excluding Treasury",
+            Self::ExcludeTeamAndReserve => "Excluding Team & Reserve",
+            Self::ExcludeFrozen => "Excluding Frozen Balances",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Standard => {
+                "Tokens distributed by the allocation envelopes, less the vested amounts not released yet."
+            }
+            Self::ExcludeTreasury => {
+                "The standard figure, less the free balance of the treasury account."
+            }
+            Self::ExcludeTeamAndReserve => {
+                "The standard figure, less the tokens released by the Teams and Reserve envelopes."
+            }
+            Self::ExcludeFrozen => {
+                "The standard figure, less the balances frozen by locks and holds across every account."
+            }
+        }
+    }
+
+    /// Circulating supply under this methodology. Fails when the breakdown
+    /// lacks a component the methodology needs.
+    pub fn circulating(&self, data: &SupplyBreakdown) -> Result<u128, String> {
+        let standard = data.circulating();
+
+        match self {
+            Self::Standard => Ok(standard),
+            Self::ExcludeTreasury => Ok(standard.saturating_sub(data.treasury)),
+            Self::ExcludeTeamAndReserve => {
+                let insiders: u128 = data
+                    .envelopes
+                    .iter()
+                    .filter(|e| e.insider)
+                    .map(|e| e.distributed.saturating_sub(e.locked_in_vesting))
+                    .sum();
+                Ok(standard.saturating_sub(insiders))
+            }
+            Self::ExcludeFrozen => data
+                .frozen
+                .map(|frozen| standard.saturating_sub(frozen))
+                .ok_or_else(|| "Account balances are not indexed yet".to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for SupplyMethodology {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+impl std::str::FromStr for SupplyMethodology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|m| m.id() == s.trim())
+            .ok_or_else(|| format!("Unknown circulating supply methodology: {s}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EnvelopeSupply;
+
+    fn envelope(
+        id: EnvelopeId,
+        name: &str,
+        distributed: u128,
+        locked_in_vesting: u128,
+    ) -> EnvelopeSupply {
+        EnvelopeSupply {
+            envelope: name.to_string(),
+            insider: is_insider(&id),
+            total_cap: 1_000,
+            distributed,
+            locked_in_vesting,
+        }
+    }
+
+    /// Chain state with 1 000 distributed, 300 of it still vesting.
+    fn fixture(frozen: Option<u128>) -> SupplyBreakdown {
+        SupplyBreakdown {
+            block: 100,
+            total_issuance: 5_000,
+            envelopes: vec![
+                envelope(EnvelopeId::Airdrop, "Airdrop", 400, 0),
+                envelope(EnvelopeId::Teams, "Teams", 300, 200),
+                envelope(EnvelopeId::Reserve, "Reserve", 200, 50),
+                envelope(EnvelopeId::Public1, "Public Funding #1", 100, 50),
+            ],
+            treasury: 120,
+            reserved: Some(10),
+            frozen,
+            holders_at: frozen.map(|_| 90),
+            methodology: SupplyMethodology::Standard,
+        }
+    }
+
+    #[test]
+    fn standard_is_distributed_less_vesting() {
+        assert_eq!(
+            SupplyMethodology::Standard.circulating(&fixture(None)),
+            Ok(700)
+        );
+    }
+
+    #[test]
+    fn exclude_treasury() {
+        assert_eq!(
+            SupplyMethodology::ExcludeTreasury.circulating(&fixture(None)),
+            Ok(580)
+        );
+    }
+
+    #[test]
+    fn exclude_team_and_reserve() {
+        // Teams released 100 and Reserve 150
+        assert_eq!(
+            SupplyMethodology::ExcludeTeamAndReserve.circulating(&fixture(None)),
+            Ok(450)
+        );
+    }
+
+    #[test]
+    fn exclude_frozen_needs_indexed_balances() {
+        assert_eq!(
+            SupplyMethodology::ExcludeFrozen.circulating(&fixture(Some(250))),
+            Ok(450)
+        );
+        assert!(
+            SupplyMethodology::ExcludeFrozen
+                .circulating(&fixture(None))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parses_identifiers() {
+        for methodology in SupplyMethodology::ALL {
+            assert_eq!(methodology.id().parse(), Ok(methodology));
+        }
+        assert!("circulating".parse::<SupplyMethodology>().is_err());
+    }
+}
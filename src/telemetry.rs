@@ -0,0 +1,378 @@
+//! Probes and Prometheus metrics of the explorer itself.
+//!
+//! `/healthz` only tells the process is up, `/readyz` that it is connected to
+//! a node following the chain, and `/metrics` exposes the counters below in
+//! the Prometheus text format.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::Json;
+use axum::extract::{Request, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+use crate::state::AppState;
+use crate::substrate::ChainConnection;
+
+/// Upper bounds of the latency histograms, in seconds.
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Time without a new finalized block after which the explorer is not ready.
+const MAX_HEAD_AGE: Duration = Duration::from_secs(60);
+
+/// Prefix of the server function routes.
+const SERVER_FN_PREFIX: &str = "/api/";
+
+#[derive(Debug, Default)]
+struct CallStats {
+    /// Calls per bucket of `BUCKETS`, not cumulated
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    errors: u64,
+    sum: f64,
+}
+
+/// Latency and failures of calls, by name.
+#[derive(Debug, Default)]
+pub struct Calls(Mutex<BTreeMap<String, CallStats>>);
+
+impl Calls {
+    pub fn record(&self, name: &str, elapsed: Duration, failed: bool) {
+        let mut calls = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = calls.entry(name.to_string()).or_default();
+
+        let secs = elapsed.as_secs_f64();
+        if let Some(bucket) = BUCKETS.iter().position(|le| secs <= *le) {
+            stats.buckets[bucket] += 1;
+        }
+        stats.count += 1;
+        stats.sum += secs;
+        if failed {
+            stats.errors += 1;
+        }
+    }
+
+    fn render(&self, out: &mut String, metric: &str, label: &str, help: &str) {
+        let calls = self.0.lock().unwrap_or_else(|e| e.into_inner());
+
+        let _ = writeln!(out, "# HELP {metric}_duration_seconds {help}");
+        let _ = writeln!(out, "# TYPE {metric}_duration_seconds histogram");
+        for (name, stats) in calls.iter() {
+            let mut cumulated = 0;
+            for (le, count) in BUCKETS.iter().zip(stats.buckets) {
+                cumulated += count;
+                let _ = writeln!(
+                    out,
+                    "{metric}_duration_seconds_bucket{{{label}=\"{name}\",le=\"{le}\"}} {cumulated}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{metric}_duration_seconds_bucket{{{label}=\"{name}\",le=\"+Inf\"}} {}",
+                stats.count
+            );
+            let _ = writeln!(
+                out,
+                "{metric}_duration_seconds_sum{{{label}=\"{name}\"}} {}",
+                stats.sum
+            );
+            let _ = writeln!(
+                out,
+                "{metric}_duration_seconds_count{{{label}=\"{name}\"}} {}",
+                stats.count
+            );
+        }
+
+        let _ = writeln!(out, "# HELP {metric}_errors_total Failed calls.");
+        let _ = writeln!(out, "# TYPE {metric}_errors_total counter");
+        for (name, stats) in calls.iter() {
+            let _ = writeln!(
+                out,
+                "{metric}_errors_total{{{label}=\"{name}\"}} {}",
+                stats.errors
+            );
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CacheStats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CacheStats {
+    pub fn hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Telemetry {
+    pub server_fns: Calls,
+    pub rpc: Calls,
+    pub allocations_cache: CacheStats,
+    /// Finalized block subscriptions restarted by the indexer
+    reconnects: AtomicU64,
+    /// Endpoints given up after failing their health checks
+    failovers: AtomicU64,
+    finality_lag: AtomicU32,
+    /// Latest finalized block followed and when it was received
+    head: Mutex<Option<(u32, Instant)>>,
+}
+
+impl Telemetry {
+    pub fn reconnected(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn failed_over(&self) {
+        self.failovers.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn finalized(&self, number: u32) {
+        *self.head.lock().unwrap_or_else(|e| e.into_inner()) = Some((number, Instant::now()));
+    }
+
+    pub fn set_finality_lag(&self, lag: u32) {
+        self.finality_lag.store(lag, Ordering::Relaxed);
+    }
+
+    fn head(&self) -> Option<(u32, Duration)> {
+        self.head
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .map(|(number, at)| (number, at.elapsed()))
+    }
+
+    pub fn render(&self, chain: &ChainConnection) -> String {
+        let mut out = String::new();
+
+        self.server_fns.render(
+            &mut out,
+            "explorer_server_fn",
+            "function",
+            "Latency of the server functions.",
+        );
+        self.rpc.render(
+            &mut out,
+            "explorer_rpc",
+            "method",
+            "Latency of the calls to the RPC node.",
+        );
+
+        let hits = self.allocations_cache.hits.load(Ordering::Relaxed);
+        let misses = self.allocations_cache.misses.load(Ordering::Relaxed);
+        let ratio = if hits + misses == 0 {
+            0.0
+        } else {
+            hits as f64 / (hits + misses) as f64
+        };
+        let cache = "{cache=\"allocations\"}";
+        write_metric(
+            &mut out,
+            "explorer_cache_hits_total",
+            "counter",
+            "Cache lookups answered from the cache.",
+            &[(cache, hits.to_string())],
+        );
+        write_metric(
+            &mut out,
+            "explorer_cache_misses_total",
+            "counter",
+            "Cache lookups that had to query the node.",
+            &[(cache, misses.to_string())],
+        );
+        write_metric(
+            &mut out,
+            "explorer_cache_hit_ratio",
+            "gauge",
+            "Share of the cache lookups answered from the cache.",
+            &[(cache, ratio.to_string())],
+        );
+
+        write_metric(
+            &mut out,
+            "explorer_rpc_connected",
+            "gauge",
+            "Whether a node is reachable.",
+            &[("", u8::from(chain.is_connected()).to_string())],
+        );
+        write_metric(
+            &mut out,
+            "explorer_rpc_failovers_total",
+            "counter",
+            "Endpoints given up after failed health checks.",
+            &[("", self.failovers.load(Ordering::Relaxed).to_string())],
+        );
+        write_metric(
+            &mut out,
+            "explorer_subscription_reconnects_total",
+            "counter",
+            "Restarts of the finalized block subscription.",
+            &[("", self.reconnects.load(Ordering::Relaxed).to_string())],
+        );
+        write_metric(
+            &mut out,
+            "explorer_finality_lag_blocks",
+            "gauge",
+            "Blocks between the best and the finalized block.",
+            &[("", self.finality_lag.load(Ordering::Relaxed).to_string())],
+        );
+        if let Some((number, age)) = self.head() {
+            write_metric(
+                &mut out,
+                "explorer_finalized_block",
+                "gauge",
+                "Latest finalized block followed.",
+                &[("", number.to_string())],
+            );
+            write_metric(
+                &mut out,
+                "explorer_finalized_block_age_seconds",
+                "gauge",
+                "Time since the latest finalized block was received.",
+                &[("", age.as_secs_f64().to_string())],
+            );
+        }
+
+        out
+    }
+}
+
+/// Writes a metric with its help and type lines. Samples are given with
+/// their already formatted labels.
+fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(&str, String)]) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{name}{labels} {value}");
+    }
+}
+
+/// Name of the server function called at `path`, without the hash suffixed
+/// by the `#[server]` macro.
+pub(crate) fn server_fn_name(path: &str) -> Option<&str> {
+    path.strip_prefix(SERVER_FN_PREFIX)
+        .map(|name| name.trim_end_matches(|c: char| c.is_ascii_digit()))
+        .filter(|name| !name.is_empty() && !name.contains('/'))
+}
+
+/// Middleware timing the server function calls.
+pub async fn track_server_fns(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(name) = server_fn_name(request.uri().path()).map(str::to_string) else {
+        return next.run(request).await;
+    };
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+
+    // Unknown functions would make up as many series as scanned URLs
+    let status = response.status();
+    if status != StatusCode::NOT_FOUND {
+        let failed = status.is_client_error() || status.is_server_error();
+        state
+            .telemetry
+            .server_fns
+            .record(&name, started.elapsed(), failed);
+    }
+
+    response
+}
+
+#[derive(Debug, Serialize)]
+struct Readiness {
+    endpoint: Option<String>,
+    finalized_block: Option<u32>,
+    finalized_block_age_secs: Option<u64>,
+}
+
+/// Served at `/healthz`. Answers as long as the server runs.
+pub async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// Served at `/readyz`. Answers 503 until a node is reachable and a
+/// finalized block was received within `MAX_HEAD_AGE`.
+pub async fn readyz(State(state): State<AppState>) -> impl IntoResponse {
+    let head = state.telemetry.head();
+    let readiness = Readiness {
+        endpoint: state.chain.endpoint(),
+        finalized_block: head.map(|(number, _)| number),
+        finalized_block_age_secs: head.map(|(_, age)| age.as_secs()),
+    };
+
+    // Without the indexer no block is followed
+    let fresh = !state.config.features.indexer || head.is_some_and(|(_, age)| age < MAX_HEAD_AGE);
+    let status = if readiness.endpoint.is_some() && fresh {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(readiness))
+}
+
+/// Served at `/metrics`, in the Prometheus text format.
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.telemetry.render(&state.chain),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_the_server_fn_hash() {
+        assert_eq!(
+            server_fn_name("/api/get_balance_of17236841687231"),
+            Some("get_balance_of")
+        );
+        assert_eq!(server_fn_name("/api/"), None);
+        assert_eq!(server_fn_name("/accounts/123"), None);
+    }
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let calls = Calls::default();
+        calls.record("chain_getHeader", Duration::from_millis(3), false);
+        calls.record("chain_getHeader", Duration::from_millis(70), true);
+        calls.record("chain_getHeader", Duration::from_secs(30), false);
+
+        let mut out = String::new();
+        calls.render(&mut out, "explorer_rpc", "method", "Latency.");
+
+        assert!(out.contains(
+            "explorer_rpc_duration_seconds_bucket{method=\"chain_getHeader\",le=\"0.005\"} 1\n"
+        ));
+        assert!(out.contains(
+            "explorer_rpc_duration_seconds_bucket{method=\"chain_getHeader\",le=\"0.1\"} 2\n"
+        ));
+        assert!(out.contains(
+            "explorer_rpc_duration_seconds_bucket{method=\"chain_getHeader\",le=\"10\"} 2\n"
+        ));
+        assert!(out.contains(
+            "explorer_rpc_duration_seconds_bucket{method=\"chain_getHeader\",le=\"+Inf\"} 3\n"
+        ));
+        assert!(out.contains("explorer_rpc_errors_total{method=\"chain_getHeader\"} 1\n"));
+    }
+}
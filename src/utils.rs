@@ -1,10 +1,13 @@
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
+use crate::address_book::{self, AddressLabel};
+
 pub const TREASURY_ACCOUNT: &str = "qSwoJVKfgchSRjD6CZ739j9G7zR1khXqkvbeMVCN1NPKJgeup";
 
 pub const SS58_PREFIX: u16 = 440;
 
+/// Default of the `chain.block_time_secs` setting.
 pub const SECS_PER_BLOCK: u64 = 6;
 
 pub const DAY: u64 = 86_400;
@@ -12,24 +15,32 @@ pub const WEEK: u64 = 7 * DAY;
 pub const MONTH: u64 = 30 * DAY;
 pub const YEAR: u64 = 12 * MONTH;
 
-/// Known addresses with human-readable names
-const KNOWN_ADDRESSES: &[(&str, &str)] = &[
-    (TREASURY_ACCOUNT, "Allfeat Foundation"),
-];
-
-/// Returns the known name for an address, or None if not known
-pub fn get_known_address_name(address: &str) -> Option<&'static str> {
-    KNOWN_ADDRESSES
-        .iter()
-        .find(|(addr, _)| *addr == address)
-        .map(|(_, name)| *name)
+/// Expected time between two blocks, from the chain settings.
+pub fn secs_per_block() -> u64 {
+    crate::config::chain_settings().block_time_secs
+}
+
+/// Returns the known name for an address from the address book, or None if not known
+pub fn get_known_address_name(address: &str) -> Option<String> {
+    address_book::label_of(address).map(|label| label.name)
+}
+
+/// Returns the labeled addresses whose name contains `query`, ignoring case
+pub fn search_known_addresses(query: &str) -> Vec<AddressLabel> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return vec![];
+    }
+
+    address_book::labels()
+        .into_iter()
+        .filter(|label| label.name.to_lowercase().contains(&query))
+        .collect()
 }
 
 /// Returns either the known name or the address itself
 pub fn display_address(address: &str) -> String {
-    get_known_address_name(address)
-        .map(|name| name.to_string())
-        .unwrap_or_else(|| address.to_string())
+    get_known_address_name(address).unwrap_or_else(|| address.to_string())
 }
 
 /// Returns the known name with the address as a shortened suffix, or just the address if not known
@@ -166,9 +177,9 @@ pub fn ss58_identicon_svg(address: &str, size: u32) -> String {
 /// It handles scientific notation for very large numbers using suffixes (K, M, B, etc.)
 /// and cleans up trailing zeros for precision.
 pub fn format_balance(amount: u128, with_symbol: bool) -> String {
-    // Defines the scaling factor: 10^12 (1 trillion)
-    const UNIT_DECIMALS: u32 = 12;
-    let divisor: f64 = 10.0f64.powi(UNIT_DECIMALS as i32);
+    let settings = crate::config::chain_settings();
+    // Defines the scaling factor: 10^decimals
+    let divisor: f64 = 10.0f64.powi(settings.token_decimals as i32);
 
     let mut value = amount as f64 / divisor;
 
@@ -216,12 +227,30 @@ pub fn format_balance(amount: u128, with_symbol: bool) -> String {
 
     if with_symbol {
         formatted.push(' ');
-        formatted.push_str("$AFT"); // Use your desired symbol
+        formatted.push('$');
+        formatted.push_str(&settings.token_symbol);
     }
 
     formatted
 }
 
+/// Decodes a hex string, with or without its `0x` prefix.
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.trim();
+    let hex = hex.strip_prefix("0x").unwrap_or(hex);
+    if hex.is_empty() || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+        })
+        .collect()
+}
+
 pub fn trim_trailing_zero(mut s: String) -> String {
     if s.ends_with(".0") {
         s.truncate(s.len() - 2);
@@ -229,13 +258,31 @@ pub fn trim_trailing_zero(mut s: String) -> String {
     s
 }
 
+/// Formats a millisecond UNIX timestamp as a UTC date and time (e.g., "2025-01-31 14:05 UTC").
+pub fn format_timestamp(timestamp_ms: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms as i64)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Time elapsed since a timestamp in milliseconds, e.g. "42s ago".
+pub fn format_age(timestamp_ms: u64, now_ms: u64) -> String {
+    let secs = now_ms.saturating_sub(timestamp_ms) / 1000;
+    match secs {
+        0..60 => format!("{secs}s ago"),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..DAY => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / DAY),
+    }
+}
+
 /// Converts block count into a human-readable duration string showing the two largest units.
 pub fn blocks_to_human_duration(blocks: u32) -> String {
     if blocks == 0 {
         return "None".to_string();
     }
 
-    let secs = blocks as u64 * SECS_PER_BLOCK;
+    let secs = blocks as u64 * secs_per_block();
 
     if secs < DAY {
         // Less than a day: use the smaller units (hours, minutes, or blocks)
@@ -251,7 +298,7 @@ pub fn blocks_to_human_duration(blocks: u32) -> String {
         }
 
         // Default to blocks if less than a minute
-        if blocks < 60 / SECS_PER_BLOCK as u32 {
+        if blocks < (60 / secs_per_block()) as u32 {
             return format!("{} blocks", blocks);
         }
 
@@ -300,7 +347,7 @@ fn format_duration_pair(mut secs: u64) -> String {
         (DAY, "day"),
         (3600, "hour"),
         (60, "minute"),
-        // SECS_PER_BLOCK is implicitly handled below
+        // The block time is implicitly handled below
     ];
 
     for (divisor, name) in units.iter() {
@@ -320,7 +367,7 @@ fn format_duration_pair(mut secs: u64) -> String {
     // If no major unit was hit (less than a day) and we have remaining seconds
     if result.is_empty() {
         if secs > 0 {
-            let blocks = secs / SECS_PER_BLOCK; // Remaining seconds are converted back to blocks
+            let blocks = secs / secs_per_block(); // Remaining seconds are converted back to blocks
             return format!("{} blocks", blocks);
         } else {
             // Should only happen if input was 0 blocks
@@ -330,3 +377,34 @@ fn format_duration_pair(mut secs: u64) -> String {
 
     result.join(" ")
 }
+
+/// Decodes the newline-terminated JSON values of a streaming server function.
+/// Chunks don't necessarily end on a line boundary.
+pub fn json_lines<T, S>(
+    stream: S,
+) -> impl futures::Stream<Item = Result<T, leptos::prelude::ServerFnError>>
+where
+    T: serde::de::DeserializeOwned,
+    S: futures::Stream<Item = Result<String, leptos::prelude::ServerFnError>> + Unpin,
+{
+    use futures::StreamExt;
+    use leptos::prelude::ServerFnError;
+
+    futures::stream::unfold(
+        (stream, String::new()),
+        |(mut stream, mut buffer)| async move {
+            loop {
+                if let Some(end) = buffer.find('\n') {
+                    let line: String = buffer.drain(..=end).collect();
+                    let item =
+                        serde_json::from_str(&line).map_err(|e| ServerFnError::new(e.to_string()));
+                    return Some((item, (stream, buffer)));
+                }
+                match stream.next().await? {
+                    Ok(chunk) => buffer.push_str(&chunk),
+                    Err(e) => return Some((Err(e), (stream, buffer))),
+                }
+            }
+        },
+    )
+}
@@ -0,0 +1,764 @@
+//! Server functions, answered by an in-memory chain.
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use leptos::config::LeptosOptions;
+use leptos::prelude::*;
+use subxt::utils::AccountId32;
+use token_app::config::Config;
+use token_app::indexer::holders::{HolderBalance, HolderIndex, HoldersSnapshot};
+use token_app::indexer::payouts::EpochRecord;
+use token_app::indexer::tokenomics::TokenomicsMonitor;
+use token_app::rate_limit::RateLimiter;
+use token_app::state::AppState;
+use token_app::substrate::ChainConnection;
+use token_app::substrate::allfeat::runtime_types::pallet_token_allocation::EnvelopeId;
+use token_app::substrate::backend::{
+    AllocationRecord, EnvelopeConfig, EventData, ExtrinsicData, PayoutState,
+};
+use token_app::substrate::fixture::{FixtureChain, block_hash};
+use token_app::telemetry::Telemetry;
+use token_app::utils::{SS58_PREFIX, TREASURY_ACCOUNT};
+use token_app::*;
+
+const ALICE: AccountId32 = AccountId32([1; 32]);
+const BOB: AccountId32 = AccountId32([2; 32]);
+
+const ENVELOPES: [EnvelopeId; 13] = [
+    EnvelopeId::Airdrop,
+    EnvelopeId::CommunityRewards,
+    EnvelopeId::Private1,
+    EnvelopeId::Private2,
+    EnvelopeId::Public2,
+    EnvelopeId::Public4,
+    EnvelopeId::Public1,
+    EnvelopeId::Public3,
+    EnvelopeId::Teams,
+    EnvelopeId::Reserve,
+    EnvelopeId::Listing,
+    EnvelopeId::ResearchDevelopment,
+    EnvelopeId::KoL,
+];
+
+fn address(account: &AccountId32) -> String {
+    ss58::encode_account(SS58_PREFIX, &account.0)
+}
+
+fn balances(free: u128) -> Balances {
+    Balances {
+        free,
+        reserved: 0,
+        frozen: 0,
+    }
+}
+
+fn envelope(total_cap: u128) -> EnvelopeConfig {
+    EnvelopeConfig {
+        total_cap,
+        upfront_rate: 10,
+        cliff: 0,
+        vesting_duration: 1_000,
+        unique_beneficiary: None,
+    }
+}
+
+fn allocation(envelope: EnvelopeId, beneficiary: AccountId32, total: u128) -> AllocationRecord {
+    AllocationRecord {
+        envelope,
+        beneficiary,
+        total,
+        upfront: total / 10,
+        vested_total: total / 2,
+        released: total / 4,
+        start: 0,
+    }
+}
+
+/// Ten blocks, every envelope with 1000 distributed out of 10000, and
+/// allocations to Alice and Bob.
+fn chain() -> FixtureChain {
+    let mut chain = FixtureChain {
+        total_issuance: Some(1_000_000),
+        ..Default::default()
+    }
+    .with_blocks(10)
+    .with_account(ALICE, balances(500))
+    .with_account(
+        AccountId32::from_str(TREASURY_ACCOUNT).unwrap(),
+        balances(300),
+    )
+    .with_allocation(allocation(EnvelopeId::Airdrop, ALICE, 400))
+    .with_allocation(allocation(EnvelopeId::Teams, BOB, 800));
+
+    for id in ENVELOPES {
+        chain = chain.with_envelope(id, envelope(10_000), 1_000);
+    }
+    chain
+}
+
+fn state(chain: FixtureChain) -> AppState {
+    let config = Config::default();
+    let telemetry = Arc::new(Telemetry::default());
+
+    AppState {
+        leptos_options: LeptosOptions::builder().output_name("token-app").build(),
+        chain: Arc::new(ChainConnection::new(vec![], telemetry.clone())),
+        backend: Arc::new(chain),
+        telemetry,
+        rate_limiter: Arc::new(RateLimiter::from_config(&config.rate_limit)),
+        allocations_cache: Default::default(),
+        sudo_log: Default::default(),
+        fee_index: Default::default(),
+        payout_index: Default::default(),
+        extrinsic_index: Default::default(),
+        tokenomics: Arc::new(TokenomicsMonitor::new(config.tokenomics.clone())),
+        holders: Arc::new(HolderIndex::new(config.cache.holders_refresh_blocks)),
+        block_feed: Default::default(),
+        metrics_cache: Default::default(),
+        envelope_supply: Default::default(),
+        supply_methodology: config.supply.methodology,
+        #[cfg(feature = "faucet")]
+        faucet: None,
+        config: Arc::new(config),
+    }
+}
+
+/// Provides `state` to the server functions called afterwards on this
+/// thread, like the server does for each request.
+fn serve(state: &AppState) -> Owner {
+    let owner = Owner::new();
+    owner.set();
+    provide_context(state.clone());
+    owner
+}
+
+#[tokio::test]
+async fn block_stream_starts_with_the_recent_blocks() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    let block = BlockSummary {
+        number: 9,
+        hash: format!("{:?}", block_hash(9)),
+        timestamp: 0,
+        extrinsic_count: 1,
+        event_count: 2,
+        author: None,
+        finality_lag: 0,
+    };
+    state
+        .block_feed
+        .publish(BlockStreamEvent::Block(block.clone()));
+
+    let mut lines = Box::pin(get_block_stream().await.unwrap().into_inner());
+    let line = lines.next().await.unwrap().unwrap();
+
+    let event: BlockStreamEvent = serde_json::from_str(&line).unwrap();
+    assert_eq!(event, BlockStreamEvent::Block(block));
+}
+
+#[tokio::test]
+async fn metrics_are_pushed_on_new_blocks() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    assert!(
+        subscribe_metrics(vec![MetricKey::TotalIssuance; 33])
+            .await
+            .is_err()
+    );
+
+    let stream = subscribe_metrics(vec![
+        MetricKey::TotalIssuance,
+        MetricKey::Balances(address(&ALICE)),
+    ])
+    .await
+    .unwrap();
+    // Unreadable blocks have no hash to read the values at
+    state.block_feed.publish(BlockStreamEvent::Error {
+        number: 9,
+        message: "unreadable".to_string(),
+    });
+    state
+        .block_feed
+        .publish(BlockStreamEvent::Block(BlockSummary {
+            number: 10,
+            hash: format!("{:?}", block_hash(10)),
+            timestamp: 0,
+            extrinsic_count: 1,
+            event_count: 2,
+            author: None,
+            finality_lag: 0,
+        }));
+
+    let mut lines = Box::pin(stream.into_inner());
+    let line = lines.next().await.unwrap().unwrap();
+    let updates: Vec<MetricUpdate> = serde_json::from_str(&line).unwrap();
+
+    assert_eq!(updates.len(), 2);
+    assert_eq!(updates[0].block, 10);
+    assert_eq!(updates[0].value, MetricValue::Amount(1_000_000));
+    assert_eq!(updates[1].value, MetricValue::Balances(balances(500)));
+}
+
+#[tokio::test]
+async fn allocations_of_every_envelope() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    let allocations = get_allocations().await.unwrap();
+    assert_eq!(allocations.len(), ENVELOPES.len());
+    assert_eq!(allocations[0].name, "Airdrop");
+    assert_eq!(allocations[0].total_cap, 10_000);
+    assert_eq!(allocations[0].distributed, 1_000);
+    assert_eq!(allocations[0].upfront_rate, 10);
+}
+
+#[tokio::test]
+async fn stale_allocations_are_served_while_the_chain_is_down() {
+    let up = state(chain());
+    {
+        let _owner = serve(&up);
+        get_allocations().await.unwrap();
+    }
+
+    let mut config = Config::default();
+    config.cache.allocations_ttl_secs = 0;
+    let down = AppState {
+        config: Arc::new(config),
+        backend: Arc::new(FixtureChain {
+            unavailable: true,
+            ..Default::default()
+        }),
+        ..up.clone()
+    };
+    let _owner = serve(&down);
+
+    assert_eq!(get_allocations().await.unwrap().len(), ENVELOPES.len());
+}
+
+#[tokio::test]
+async fn epoch_duration() {
+    let state = state(FixtureChain {
+        epoch_duration: 14_400,
+        ..Default::default()
+    });
+    let _owner = serve(&state);
+
+    assert_eq!(get_epoch_duration().await.unwrap(), 14_400);
+}
+
+#[tokio::test]
+async fn unreachable_chain_is_retryable() {
+    let state = state(FixtureChain {
+        unavailable: true,
+        ..Default::default()
+    });
+    let _owner = serve(&state);
+
+    let error = get_epoch_duration().await.unwrap_err();
+    assert!(matches!(error, AppError::RpcUnavailable(_)));
+    assert!(error.is_retryable());
+}
+
+#[tokio::test]
+async fn payout_status() {
+    let state = state(FixtureChain {
+        payout: PayoutState {
+            next_payout_at: 20,
+            epoch_index: 3,
+            payout_cursor: Some(7),
+            next_allocation_id: 42,
+        },
+        epoch_duration: 100,
+        max_payouts_per_block: 16,
+        ..chain()
+    });
+    let _owner = serve(&state);
+
+    let status = get_payout_status().await.unwrap();
+    assert_eq!(status.current_block, 9);
+    assert_eq!(status.blocks_until_next(), 11);
+    assert_eq!(status.total_allocations, 42);
+    assert_eq!(status.max_payouts_per_block, 16);
+    assert!(status.in_progress());
+}
+
+#[tokio::test]
+async fn payout_history_is_newest_first() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    {
+        let mut index = state.payout_index.write().await;
+        for epoch in 1..=3 {
+            index.epochs.insert(
+                epoch,
+                EpochRecord {
+                    first_block: epoch as u32 * 100,
+                    last_block: epoch as u32 * 100,
+                    completed: true,
+                    ..Default::default()
+                },
+            );
+        }
+        index.indexed_since = Some(100);
+    }
+
+    let history = get_payout_history(2).await.unwrap();
+    assert_eq!(history.epochs.len(), 2);
+    assert_eq!(history.epochs[0].epoch, 3);
+    assert_eq!(history.indexed_since, Some(100));
+}
+
+#[tokio::test]
+async fn allocations_of_an_account() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    let allocations = get_allocations_of(address(&ALICE)).await.unwrap();
+    assert_eq!(allocations.len(), 1);
+    assert_eq!(allocations[0].envelope.name, "Airdrop");
+    assert_eq!(allocations[0].total, 400);
+
+    assert!(
+        get_allocations_of(address(&AccountId32([3; 32])))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(matches!(
+        get_allocations_of("nope".to_string()).await,
+        Err(AppError::InvalidAddress(_))
+    ));
+}
+
+#[tokio::test]
+async fn total_issuance() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    assert_eq!(get_total_issuance().await.unwrap(), 1_000_000);
+}
+
+#[tokio::test]
+async fn missing_total_issuance_is_not_found() {
+    let state = state(FixtureChain::default());
+    let _owner = serve(&state);
+
+    assert!(matches!(
+        get_total_issuance().await,
+        Err(AppError::NotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn circulating_supply_excludes_vesting() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    // 13 envelopes distributed 1000 each, 100 + 200 vested but not released
+    assert_eq!(
+        get_circulating_supply(None).await.unwrap(),
+        13 * 1_000 - 300
+    );
+}
+
+#[tokio::test]
+async fn supply_breakdown() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    let breakdown = get_supply_breakdown().await.unwrap();
+    assert_eq!(breakdown.block, 9);
+    assert_eq!(breakdown.treasury, 300);
+    assert_eq!(breakdown.locked_in_vesting(), 300);
+    assert_eq!(breakdown.undistributed(), 13 * 9_000);
+    assert_eq!(breakdown.holders_at, None);
+}
+
+#[tokio::test]
+async fn balance_of() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    assert_eq!(
+        get_balance_of(address(&ALICE)).await.unwrap(),
+        balances(500)
+    );
+    // Unknown accounts have zero balances
+    assert_eq!(get_balance_of(address(&BOB)).await.unwrap(), balances(0));
+}
+
+#[tokio::test]
+async fn batched_balances_keep_per_account_errors() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    let results = get_balances_of_many(vec![address(&ALICE), "nope".to_string()])
+        .await
+        .unwrap();
+    assert_eq!(results[0].result, Ok(balances(500)));
+    assert_eq!(
+        results[1].result,
+        Err(AppError::InvalidAddress("nope".to_string()))
+    );
+
+    let too_many = vec![address(&ALICE); 101];
+    assert!(matches!(
+        get_balances_of_many(too_many).await,
+        Err(AppError::InvalidInput(_))
+    ));
+}
+
+#[tokio::test]
+async fn batched_allocations() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    let results = get_allocations_of_many(vec![address(&ALICE), address(&BOB)])
+        .await
+        .unwrap();
+    assert_eq!(results[0].result.as_ref().unwrap()[0].total, 400);
+    assert_eq!(
+        results[1].result.as_ref().unwrap()[0].envelope.name,
+        "Teams"
+    );
+}
+
+#[tokio::test]
+async fn batched_allocations_keep_per_account_errors() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    let results = get_allocations_of_many(vec![
+        address(&ALICE),
+        "nope".to_string(),
+        address(&ALICE),
+    ])
+    .await
+    .unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].result.as_ref().unwrap()[0].total, 400);
+    assert!(matches!(
+        &results[1].result,
+        Err(AppError::InvalidAddress(id)) if id == "nope"
+    ));
+    // Repeated accounts each get their allocations
+    assert_eq!(results[2].result.as_ref().unwrap()[0].total, 400);
+}
+
+#[tokio::test]
+async fn watchlist_batch_survives_a_bad_id() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    let ids = vec![address(&ALICE), "nope".to_string(), address(&BOB)];
+    let balances_of = get_balances_of_many(ids.clone()).await.unwrap();
+    let allocations_of = get_allocations_of_many(ids).await.unwrap();
+
+    assert_eq!(balances_of[0].result, Ok(balances(500)));
+    assert_eq!(balances_of[2].result, Ok(balances(0)));
+    assert_eq!(allocations_of[0].result.as_ref().unwrap()[0].total, 400);
+    assert_eq!(allocations_of[2].result.as_ref().unwrap()[0].total, 800);
+    assert_eq!(
+        balances_of[1].result,
+        Err(AppError::InvalidAddress("nope".to_string()))
+    );
+    assert!(matches!(
+        &allocations_of[1].result,
+        Err(AppError::InvalidAddress(id)) if id == "nope"
+    ));
+}
+
+#[tokio::test]
+async fn top_holders_wait_for_the_first_scan() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    assert!(
+        get_top_holders(HolderRanking::Total, 0, 10)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let holders = [(ALICE, 500), (BOB, 700)]
+        .into_iter()
+        .map(|(account, free)| HolderBalance {
+            address: address(&account),
+            free,
+            reserved: 0,
+            frozen: 0,
+        })
+        .collect();
+    *state.holders.snapshot.write().await =
+        Some(Arc::new(HoldersSnapshot::new(9, 1_000_000, holders)));
+
+    let page = get_top_holders(HolderRanking::Total, 0, 10)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.total, 2);
+    assert_eq!(page.items[0].address, address(&BOB));
+}
+
+#[tokio::test]
+async fn sudo_key() {
+    let state = state(FixtureChain {
+        sudo_key: Some(ALICE),
+        ..chain()
+    });
+    let _owner = serve(&state);
+
+    assert_eq!(get_sudo_key().await.unwrap(), Some(address(&ALICE)));
+}
+
+#[tokio::test]
+async fn sudo_activity_is_paginated() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    let mut log = state.sudo_log.write().await;
+    log.entries = (0..5)
+        .rev()
+        .map(|block| SudoActivity {
+            block,
+            block_hash: format!("{:?}", block_hash(block)),
+            event_index: 0,
+            timestamp: 0,
+            kind: SudoActivityKind::Sudid,
+            call: Some("System.remark".to_string()),
+            call_args: None,
+            account: None,
+            result: Some(Ok(())),
+        })
+        .collect();
+    log.indexed_since = Some(0);
+    drop(log);
+
+    let page = get_sudo_activity(1, 2).await.unwrap();
+    assert_eq!(page.total, 5);
+    assert_eq!(page.indexed_since, Some(0));
+    assert_eq!(
+        page.items.iter().map(|a| a.block).collect::<Vec<_>>(),
+        [3, 2]
+    );
+}
+
+#[tokio::test]
+async fn fee_analytics() {
+    let state = state(FixtureChain {
+        next_fee_multiplier: Some(2_000_000_000_000_000_000),
+        ..chain()
+    });
+    let _owner = serve(&state);
+
+    let analytics = get_fee_analytics(10, 5).await.unwrap();
+    assert_eq!(analytics.next_fee_multiplier, 2.0);
+    assert_eq!(analytics.indexed_since, None);
+}
+
+#[tokio::test]
+async fn fee_estimate_adds_the_length_prefix() {
+    let state = state(FixtureChain {
+        fee: Some(FeeEstimate {
+            partial_fee: 1_000,
+            class: "Normal".to_string(),
+            ref_time: 1,
+            proof_size: 2,
+            length: 0,
+        }),
+        ..chain()
+    });
+    let _owner = serve(&state);
+
+    // 3 bytes and their compact length
+    let estimate = estimate_fee("0x010203".to_string()).await.unwrap();
+    assert_eq!(estimate.partial_fee, 1_000);
+    assert_eq!(estimate.length, 4);
+
+    let estimate = estimate_fee("0x0c010203".to_string()).await.unwrap();
+    assert_eq!(estimate.length, 4);
+
+    assert!(matches!(
+        estimate_fee("0xzz".to_string()).await,
+        Err(AppError::InvalidInput(_))
+    ));
+}
+
+#[tokio::test]
+async fn search_blocks_and_accounts() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    let suggestions = search("#5".to_string()).await.unwrap();
+    assert_eq!(suggestions[0].kind, SearchKind::Block);
+    assert_eq!(suggestions[0].href, "/blocks/5");
+    assert!(search("42".to_string()).await.unwrap().is_empty());
+
+    let hash = format!("{:?}", block_hash(3));
+    let suggestions = search(hash.clone()).await.unwrap();
+    assert_eq!(suggestions[0].href, format!("/blocks/{hash}"));
+    // Any 32 bytes are also a public key
+    assert_eq!(suggestions[1].kind, SearchKind::Account);
+
+    let suggestions = search(address(&ALICE)).await.unwrap();
+    assert_eq!(
+        suggestions[0].href,
+        format!("/accounts/{}", address(&ALICE))
+    );
+}
+
+#[tokio::test]
+async fn address_book_has_the_builtin_labels() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    let labels = get_address_book().await.unwrap();
+    assert!(labels.iter().any(|l| l.address == TREASURY_ACCOUNT));
+}
+
+#[tokio::test]
+async fn accounts_resolve_from_names_and_keys() {
+    let state = state(chain());
+    let _owner = serve(&state);
+
+    assert_eq!(
+        resolve_account("Allfeat Foundation".to_string())
+            .await
+            .unwrap(),
+        TREASURY_ACCOUNT
+    );
+    assert_eq!(
+        resolve_account(format!("0x{}", "02".repeat(32)))
+            .await
+            .unwrap(),
+        address(&BOB)
+    );
+}
+
+#[tokio::test]
+async fn block_details_with_outcomes() {
+    let mut chain = chain();
+    let block = chain.blocks.last_mut().unwrap();
+    block.extrinsics = vec![
+        ExtrinsicData {
+            index: 0,
+            hash: block_hash(100),
+            call: Some("Timestamp.set".to_string()),
+            signer: None,
+        },
+        ExtrinsicData {
+            index: 1,
+            hash: block_hash(101),
+            call: None,
+            signer: Some(ALICE),
+        },
+    ];
+    block.events = [(0, "ExtrinsicSuccess"), (1, "ExtrinsicFailed")]
+        .into_iter()
+        .map(|(index, variant)| EventData {
+            pallet: "System".to_string(),
+            variant: variant.to_string(),
+            extrinsic: Some(index),
+        })
+        .collect();
+
+    let state = state(chain);
+    let _owner = serve(&state);
+
+    let details = get_block("9".to_string()).await.unwrap();
+    assert_eq!(details.hash, format!("{:?}", block_hash(9)));
+    assert_eq!(details.parent_hash, format!("{:?}", block_hash(8)));
+    assert_eq!(details.event_count, 2);
+    assert_eq!(details.extrinsics[0].success, Some(true));
+    assert_eq!(details.extrinsics[1].call, "Unknown");
+    assert_eq!(details.extrinsics[1].signer, Some(address(&ALICE)));
+    assert_eq!(details.extrinsics[1].success, Some(false));
+
+    let by_hash = get_block(format!("{:?}", block_hash(9))).await.unwrap();
+    assert_eq!(by_hash.number, 9);
+    assert!(matches!(
+        get_block("99".to_string()).await,
+        Err(AppError::NotFound(_))
+    ));
+}
+
+#[cfg(feature = "faucet")]
+#[tokio::test]
+async fn faucet_keeps_its_floor_and_checks_the_captcha() {
+    use token_app::config::Secret;
+    use token_app::faucet::{CaptchaConfig, Faucet, FaucetConfig};
+
+    {
+        let _owner = serve(&state(chain()));
+        assert!(matches!(
+            faucet::get_faucet_info().await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    let faucet = Faucet::new(FaucetConfig {
+        seed: Secret("//Alice".to_string()),
+        amount: 100,
+        balance_floor: 50,
+        captcha: Some(CaptchaConfig {
+            site_key: "site".to_string(),
+            secret: Secret("secret".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+    .unwrap()
+    .unwrap();
+    let chain = chain().with_account(faucet.account.clone(), balances(1_000));
+    let state = AppState {
+        faucet: Some(Arc::new(faucet)),
+        ..state(chain)
+    };
+    let _owner = serve(&state);
+
+    let info = faucet::get_faucet_info().await.unwrap();
+    assert_eq!(info.amount, 100);
+    assert_eq!(info.available, 950);
+    assert_eq!(info.captcha.unwrap().widget_class, "h-captcha");
+
+    // Rejected before anything is sent
+    assert!(matches!(
+        faucet::request_drip("nobody".to_string(), None).await,
+        Err(AppError::InvalidAddress(_))
+    ));
+    assert!(matches!(
+        faucet::request_drip(address(&BOB), None).await,
+        Err(AppError::InvalidInput(_))
+    ));
+}
+
+#[cfg(feature = "faucet")]
+#[tokio::test]
+async fn faucet_running_low_is_not_retryable() {
+    use token_app::config::Secret;
+    use token_app::faucet::{Faucet, FaucetConfig};
+
+    let faucet = Faucet::new(FaucetConfig {
+        seed: Secret("//Alice".to_string()),
+        amount: 100,
+        balance_floor: 950,
+        ..Default::default()
+    })
+    .unwrap()
+    .unwrap();
+    let chain = chain().with_account(faucet.account.clone(), balances(1_000));
+    let state = AppState {
+        faucet: Some(Arc::new(faucet)),
+        ..state(chain)
+    };
+    let _owner = serve(&state);
+
+    let error = faucet::request_drip(address(&BOB), None).await.unwrap_err();
+    assert!(matches!(error, AppError::OutOfFunds(_)));
+    assert!(!error.is_retryable());
+}